@@ -4,6 +4,7 @@ use std::io;
 use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
+use std::process::Command;
 
 use walkdir::WalkDir;
 
@@ -44,8 +45,24 @@ fn schedule_rerun_if_folder_changed(path: &Path) {
     }
 }
 
+// The build cache is not reused by another compiler, that might lay out or hash its content
+// differently
+fn export_rustc_version() {
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+
+    let version = Command::new(rustc)
+        .arg("-V")
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_default();
+
+    println!("cargo:rustc-env=ROCK_RUSTC_VERSION={}", version);
+}
+
 // build script's entry point
 fn main() {
+    export_rustc_version();
+
     schedule_rerun_if_folder_changed(&PathBuf::from("src/lib/testcases/"));
 
     let out_dir = "src/lib";
@@ -55,7 +72,12 @@ fn main() {
     // write test file header, put `use`, `const` etc there
     write_header(&mut output_file);
 
-    for file in visit_dirs(Path::new(&"src/lib/testcases".to_string())).unwrap() {
+    let mut files = visit_dirs(Path::new(&"src/lib/testcases".to_string())).unwrap();
+
+    // `read_dir` gives no order, so the file would change from one build to another
+    files.sort();
+
+    for file in files {
         write_test(&mut output_file, &file);
     }
 }
@@ -88,10 +110,18 @@ fn write_header(output_file: &mut File) {
 
             let expected_ret = expected_ret.parse::<i64>().unwrap();
 
-            let (ret_code, stdout) = super::helpers::test_utils::run(path, input.to_string(), config);
+            let (ret_code, stdout, stderr) = super::helpers::test_utils::run(path, input.to_string(), config.clone());
 
             assert_eq!(expected_ret, ret_code);
-            assert_eq!(expected_output, stdout);
+            assert_eq!(expected_output, stderr);
+
+            // Differential run: the interpreter must behave like the compiled program
+            let (interp_ret_code, interp_stdout) = super::helpers::test_utils::interpret(input.to_string(), config);
+
+            if let Some(interp_ret_code) = interp_ret_code {{
+                assert_eq!(ret_code, interp_ret_code, "The interpreter exited with another code");
+            }}
+            assert_eq!(stdout, interp_stdout, "The interpreter printed another output");
         }}
         "##
     )
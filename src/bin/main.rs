@@ -4,8 +4,9 @@ extern crate rock;
 #[macro_use]
 extern crate log;
 
-use clap::{App, Arg, SubCommand};
+use clap::{App, Arg, ArgMatches, SubCommand};
 use std::{
+    collections::HashMap,
     fs::{self, File},
     io::Write,
     path::{Path, PathBuf},
@@ -14,60 +15,136 @@ use std::{
 
 pub mod logger;
 
-use rock::diagnostics::DiagnosticKind;
 pub(crate) use rock::*;
+use rock::{
+    config::{
+        CrateType, EmitKind, MessageFormat, OptLevel, PackageType, ProjectConfig, MANIFEST_FILE,
+    },
+    diagnostics::{
+        self, Diagnostic, DiagnosticKind, DiagnosticType, JsonDiagnostic, Lint, LintLevel,
+        WARNINGS_GROUP,
+    },
+    doc::DocFormat,
+};
 
 fn build(config: &Config) -> bool {
     debug!(" -> Building");
 
-    let entry_file = "./src/main.rk";
+    let entry_file = config.project_config.entry_file();
+    let entry_file = entry_file.to_str().unwrap();
 
     fs::create_dir_all(config.build_folder.clone()).unwrap();
 
     if let Err(diagnostic) = rock::compile_file(entry_file.to_string(), config) {
-        if let DiagnosticKind::NoError = diagnostic.get_kind() {
-        } else {
-            println!("Error: {}", diagnostic.get_kind());
-        }
+        report_error(config, &diagnostic);
 
         return false;
     }
 
-    let clang_cmd = Command::new("clang")
-        .args(&[
-            config.build_folder.join("out.bc").to_str().unwrap(),
-            "-o",
-            config.build_folder.join("a.out").to_str().unwrap(),
-        ])
-        .output()
-        .expect("failed to compile to ir");
+    if !config.emit.contains(&EmitKind::Link) {
+        return true;
+    }
 
-    match clang_cmd.status.code() {
-        Some(code) => {
-            if code != 0 {
-                println!(
-                    "BUG: Cannot compile: \n{}",
-                    String::from_utf8(clang_cmd.stderr).unwrap()
-                );
+    if let Err(msg) = link(config) {
+        report_error(config, &Diagnostic::new_link_error(msg));
 
-                return false;
-            }
-        }
-        None => println!(
-            "\nError running: \n{}",
-            String::from_utf8(clang_cmd.stderr).unwrap()
-        ),
+        return false;
     }
 
     true
 }
 
-fn run(config: Config) {
+// The diagnostics that stopped the compilation are already printed, but not the ones that
+// happened before it, like a missing entry file
+fn report_error(config: &Config, diagnostic: &Diagnostic) {
+    if let DiagnosticKind::NoError = diagnostic.get_kind() {
+    } else if config.message_format == MessageFormat::Json {
+        let json = JsonDiagnostic::new(diagnostic, &DiagnosticType::Error, &HashMap::new());
+
+        println!("{}", json.to_json_line());
+    } else {
+        let kind = diagnostic.get_kind();
+
+        println!("[{}] Error: {}", kind.code(), kind);
+    }
+}
+
+// Fails with the reason, reported as a link error
+fn link(config: &Config) -> Result<(), String> {
+    let object = config.build_folder.join(EmitKind::Link.file_name());
+    let output = config.output_file();
+
+    let mut cmd = match config.crate_type {
+        CrateType::Bin => Command::new("clang"),
+        CrateType::Cdylib => {
+            let mut cmd = Command::new("clang");
+
+            cmd.arg("-shared");
+
+            cmd
+        }
+        CrateType::StaticLib => {
+            // `ar` would otherwise keep the members of a previous archive
+            let _ = fs::remove_file(&output);
+
+            let mut cmd = Command::new("ar");
+
+            cmd.arg("rcs").arg(&output);
+
+            cmd
+        }
+    };
+
+    if config.crate_type != CrateType::StaticLib {
+        if let Some(target) = &config.target {
+            cmd.arg(format!("--target={}", target));
+        }
+
+        cmd.arg("-o").arg(&output);
+    }
+
+    let program = cmd.get_program().to_string_lossy().to_string();
+
+    let link_cmd = cmd
+        .arg(&object)
+        .output()
+        .map_err(|e| format!("`{}` cannot run: {}", program, e))?;
+
+    if !link_cmd.status.success() {
+        return Err(format!(
+            "`{}` failed ({}):\n{}",
+            program,
+            link_cmd.status,
+            String::from_utf8_lossy(&link_cmd.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+fn run(mut config: Config, args: Vec<&str>) {
+    if config.crate_type != CrateType::Bin {
+        println!("Error: Only binaries can be run");
+
+        return;
+    }
+
+    if matches!(&config.target, Some(target) if *target != rock::host_triple()) {
+        println!("Error: Cannot run a binary built for another target");
+
+        return;
+    }
+
+    if !config.emit.contains(&EmitKind::Link) {
+        config.emit.push(EmitKind::Link);
+    }
+
     if !build(&config) {
         return;
     }
 
-    let cmd = Command::new(config.build_folder.join("a.out").to_str().unwrap())
+    let cmd = Command::new(config.output_file().to_str().unwrap())
+        .args(args)
         .output()
         .expect("failed to execute binary");
 
@@ -86,7 +163,171 @@ fn run(config: Config) {
     std::process::exit(-1);
 }
 
+// The interpreter needs a bigger stack than the one of the main thread, as each call of
+// the program is several nested calls of it
+const INTERP_STACK_SIZE: usize = 1 << 30;
+
+fn run_interp(config: Config, args: Vec<&str>) {
+    let entry_file = config
+        .project_config
+        .entry_file()
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    // The name of the program is the one of the binary it would be
+    let args = std::iter::once(config.output_file().to_str().unwrap().to_string())
+        .chain(args.into_iter().map(str::to_string))
+        .collect();
+
+    let interp = std::thread::Builder::new()
+        .stack_size(INTERP_STACK_SIZE)
+        .spawn(move || {
+            let res = rock::interpret_file(entry_file, &config, args);
+
+            (config, res)
+        })
+        .unwrap();
+
+    match interp.join() {
+        Ok((_config, Ok(code))) => std::process::exit(code.unwrap_or_default() as i32),
+        Ok((config, Err(diagnostic))) => report_error(&config, &diagnostic),
+        Err(_) => println!("\nError running: the interpreter crashed"),
+    }
+
+    std::process::exit(-1);
+}
+
+// A test that ran, with the output of its process
+struct TestResult {
+    name: String,
+    location: String,
+    passed: bool,
+    output: String,
+}
+
+// The tests are built in their own folder, with a main function that runs the one given
+// in `ROCK_TEST`, or lists them when it is not set. Each test runs in its own process, so
+// a failed assertion or a crash only fails that test.
+fn run_tests(mut config: Config, filter: Option<&str>) -> bool {
+    if !config.std {
+        println!("Error: The tests need the std");
+
+        return false;
+    }
+
+    if matches!(&config.target, Some(target) if *target != rock::host_triple()) {
+        println!("Error: Cannot run the tests of another target");
+
+        return false;
+    }
+
+    config.test = true;
+    config.crate_type = CrateType::Bin;
+    config.build_folder = config.build_folder.join("test");
+
+    if !config.emit.contains(&EmitKind::Link) {
+        config.emit.push(EmitKind::Link);
+    }
+
+    if !build(&config) {
+        return false;
+    }
+
+    let harness = config.output_file();
+
+    let listing = Command::new(&harness)
+        .env_remove("ROCK_TEST")
+        .output()
+        .expect("failed to execute the tests");
+
+    let tests = String::from_utf8_lossy(&listing.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, ' ');
+
+            Some((
+                parts.next()?.to_string(),
+                parts.next()?.to_string(),
+                parts.next().unwrap_or_default().to_string(),
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    let selected = tests
+        .iter()
+        .filter(|(_, _, name)| filter.map_or(true, |filter| name.contains(filter)))
+        .collect::<Vec<_>>();
+
+    println!(
+        "\nrunning {} test{}",
+        selected.len(),
+        if selected.len() == 1 { "" } else { "s" }
+    );
+
+    let mut results = vec![];
+
+    for (index, location, name) in selected.iter() {
+        let test = Command::new(&harness)
+            .env("ROCK_TEST", index)
+            .output()
+            .expect("failed to execute the tests");
+
+        let mut output = String::from_utf8_lossy(&test.stdout).to_string();
+
+        output += &String::from_utf8_lossy(&test.stderr);
+
+        if test.status.code().is_none() {
+            output += "The test crashed\n";
+        }
+
+        let result = TestResult {
+            name: name.clone(),
+            location: location.clone(),
+            passed: test.status.success(),
+            output,
+        };
+
+        println!(
+            "test {} ... {}",
+            result.name,
+            if result.passed { "ok" } else { "FAILED" }
+        );
+
+        results.push(result);
+    }
+
+    let failures = results
+        .iter()
+        .filter(|result| !result.passed)
+        .collect::<Vec<_>>();
+
+    if !failures.is_empty() {
+        println!("\nfailures:\n");
+
+        for failure in &failures {
+            println!("---- {} at {} ----", failure.name, failure.location);
+            print!("{}", failure.output);
+            println!();
+        }
+    }
+
+    println!(
+        "\ntest result: {}. {} passed; {} failed; {} filtered out\n",
+        if failures.is_empty() { "ok" } else { "FAILED" },
+        results.len() - failures.len(),
+        failures.len(),
+        tests.len() - selected.len()
+    );
+
+    failures.is_empty()
+}
+
 fn main() {
+    let lint_names = std::iter::once(WARNINGS_GROUP)
+        .chain(Lint::all().iter().map(Lint::name))
+        .collect::<Vec<_>>();
+
     let matches = App::new("Rock")
         .version(env!("CARGO_PKG_VERSION"))
         .about("A compiler for the Rock programming language")
@@ -130,7 +371,22 @@ fn main() {
                 .short("N")
                 .long("no-optimize")
                 .takes_value(false)
-                .help("Disable LLVM optimization passes"),
+                .help("Disable LLVM optimization passes, same as -O0"),
+        )
+        .arg(
+            Arg::with_name("opt-level")
+                .short("O")
+                .long("opt-level")
+                .takes_value(true)
+                .possible_values(&["0", "1", "2", "3", "s"])
+                .conflicts_with("no-optimize")
+                .help("Optimization level. Defaults to 0 with the debug profile and 3 with the release one"),
+        )
+        .arg(
+            Arg::with_name("passes")
+                .long("passes")
+                .takes_value(true)
+                .help("Comma separated list of LLVM passes to run instead of the standard pipeline (e.g. mem2reg,instcombine,simplifycfg)"),
         )
         .arg(
             Arg::with_name("ir")
@@ -145,16 +401,171 @@ fn main() {
                 .takes_value(false)
                 .help("Does not include stdlib"),
         )
+        .arg(
+            Arg::with_name("timings")
+                .long("timings")
+                .takes_value(false)
+                .help(
+                    "Show the time spent in each compilation stage, and the ones that were skipped",
+                ),
+        )
+        .arg(
+            Arg::with_name("manifest-path")
+                .long("manifest-path")
+                .takes_value(true)
+                .help("Path to the Rock.toml of the project. Defaults to the first one found in the current folder or its parents"),
+        )
         .arg(
             Arg::with_name("output-folder")
                 .short("o")
                 .long("output-folder")
                 .takes_value(true)
                 .default_value("./build")
-                .help("Choose a different output folder"),
+                .help("Choose a different output folder, relative to the project root"),
+        )
+        .arg(
+            Arg::with_name("emit")
+                .long("emit")
+                .takes_value(true)
+                .use_delimiter(true)
+                .possible_values(&["obj", "asm", "llvm-ir", "llvm-bc", "link"])
+                .default_value("link")
+                .help("Comma separated list of outputs to write in the output folder"),
+        )
+        .arg(
+            Arg::with_name("crate-type")
+                .long("crate-type")
+                .takes_value(true)
+                .possible_values(&["bin", "staticlib", "cdylib"])
+                .help("Kind of linked output. Defaults to bin, or staticlib for lib packages"),
+        )
+        .arg(
+            Arg::with_name("output-name")
+                .long("output-name")
+                .takes_value(true)
+                .help("Name of the linked output. Defaults to a.out, lib<name>.a or lib<name>.so"),
+        )
+        .arg(
+            Arg::with_name("debug-info")
+                .short("g")
+                .takes_value(false)
+                .help("Emit DWARF debug info"),
+        )
+        .arg(
+            Arg::with_name("target")
+                .long("target")
+                .takes_value(true)
+                .help("LLVM target triple to build for (e.g. aarch64-unknown-linux-gnu). Defaults to the host"),
+        )
+        .arg(
+            Arg::with_name("message-format")
+                .long("message-format")
+                .takes_value(true)
+                .possible_values(&["human", "json"])
+                .default_value("human")
+                .help("Print the diagnostics for humans or as JSON lines"),
+        )
+        .arg(
+            Arg::with_name("allow")
+                .short("A")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .possible_values(&lint_names)
+                .help("Silence a lint, or every warning with `warnings`"),
+        )
+        .arg(
+            Arg::with_name("warn")
+                .short("W")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .possible_values(&lint_names)
+                .help("Report a lint as a warning"),
+        )
+        .arg(
+            Arg::with_name("deny")
+                .short("D")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .possible_values(&lint_names)
+                .help("Report a lint as an error, or every warning with `warnings`"),
+        )
+        .arg(
+            Arg::with_name("deny-warnings")
+                .long("deny-warnings")
+                .takes_value(false)
+                .help("Same as -D warnings"),
         )
         .subcommand(SubCommand::with_name("build").about("Build the current project directory"))
-        .subcommand(SubCommand::with_name("run").about("Run the current project directory"))
+        .subcommand(
+            SubCommand::with_name("run")
+                .about("Run the current project directory")
+                .arg(
+                    Arg::with_name("interp")
+                        .long("interp")
+                        .takes_value(false)
+                        .help("Run the program with the interpreter, without building it"),
+                )
+                .arg(
+                    Arg::with_name("args")
+                        .multiple(true)
+                        .last(true)
+                        .help("Arguments given to the program, after a `--`"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("test")
+                .about("Build and run the tests of the current project directory")
+                .arg(
+                    Arg::with_name("filter")
+                        .help("Only run the tests whose name contains this text"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("targets").about("List the targets supported by the --target flag"),
+        )
+        .subcommand(
+            SubCommand::with_name("explain")
+                .about("Print the extended explanation of an error code")
+                .arg(
+                    Arg::with_name("code")
+                        .required(true)
+                        .help("The code of the error, like R0011"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("fmt")
+                .about("Format the source files of the current project directory")
+                .arg(
+                    Arg::with_name("check")
+                        .long("check")
+                        .takes_value(false)
+                        .help("List the files that are not formatted instead of rewriting them, and fail if any"),
+                )
+                .arg(
+                    Arg::with_name("files")
+                        .multiple(true)
+                        .help("The files to format. Defaults to every .rk file in the src folder of the project"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("doc")
+                .about("Generate the documentation of the current project directory from its ## comments")
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .takes_value(true)
+                        .possible_values(&["html", "markdown"])
+                        .default_value("html")
+                        .help("Write the pages in HTML or in Markdown"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("lsp")
+                .about("Start a language server, that talks on the standard input and output"),
+        )
         .subcommand(
             SubCommand::with_name("new")
                 .about("Create a new empty project folder")
@@ -166,25 +577,116 @@ fn main() {
         )
         .get_matches();
 
-    let config = rock::Config {
+    let mut config = rock::Config {
         verbose: matches.is_present("verbose"),
         quiet: matches.is_present("quiet"),
         show_ast: matches.is_present("ast"),
         show_hir: matches.is_present("hir"),
         show_thir: matches.is_present("thir"),
         show_ir: matches.is_present("ir"),
-        no_optimize: matches.is_present("no-optimize"),
+        passes: matches.value_of("passes").map(str::to_string),
+        emit: matches
+            .values_of("emit")
+            .unwrap()
+            .filter_map(EmitKind::parse)
+            .collect(),
+        output_name: matches.value_of("output-name").map(str::to_string),
+        target: matches.value_of("target").map(str::to_string),
+        debug_info: matches.is_present("debug-info"),
+        message_format: matches
+            .value_of("message-format")
+            .and_then(MessageFormat::parse)
+            .unwrap(),
         build_folder: PathBuf::from(matches.value_of("output-folder").unwrap()),
         std: !matches.is_present("nostd"),
+        timings: matches.is_present("timings"),
         ..Default::default()
     };
 
     logger::init_logger();
 
     if let Some(_matches) = matches.subcommand_matches("build") {
-        build(&config);
-    } else if let Some(_matches) = matches.subcommand_matches("run") {
-        run(config);
+        if !load_project(&mut config, matches.value_of("manifest-path")) {
+            std::process::exit(1);
+        }
+
+        set_build_options(&mut config, &matches);
+
+        if !build(&config) {
+            std::process::exit(1);
+        }
+    } else if let Some(run_matches) = matches.subcommand_matches("run") {
+        if !load_project(&mut config, matches.value_of("manifest-path")) {
+            std::process::exit(1);
+        }
+
+        set_build_options(&mut config, &matches);
+
+        let args = run_matches
+            .values_of("args")
+            .map(|args| args.collect())
+            .unwrap_or_default();
+
+        if run_matches.is_present("interp") {
+            run_interp(config, args);
+        } else {
+            run(config, args);
+        }
+    } else if let Some(test_matches) = matches.subcommand_matches("test") {
+        if !load_project(&mut config, matches.value_of("manifest-path")) {
+            std::process::exit(1);
+        }
+
+        set_build_options(&mut config, &matches);
+
+        if !run_tests(config, test_matches.value_of("filter")) {
+            std::process::exit(1);
+        }
+    } else if matches.subcommand_matches("targets").is_some() {
+        print_targets();
+    } else if let Some(matches) = matches.subcommand_matches("explain") {
+        if !explain(matches.value_of("code").unwrap()) {
+            std::process::exit(1);
+        }
+    } else if let Some(fmt_matches) = matches.subcommand_matches("fmt") {
+        let files = match fmt_matches.values_of("files") {
+            Some(files) => files.map(PathBuf::from).collect(),
+            None => {
+                if !load_project(&mut config, matches.value_of("manifest-path")) {
+                    std::process::exit(1);
+                }
+
+                let mut files = vec![];
+
+                find_source_files(&config.project_config.path(Path::new("src")), &mut files);
+
+                files
+            }
+        };
+
+        if !format_files(&config, &files, fmt_matches.is_present("check")) {
+            std::process::exit(1);
+        }
+    } else if let Some(doc_matches) = matches.subcommand_matches("doc") {
+        if !load_project(&mut config, matches.value_of("manifest-path")) {
+            std::process::exit(1);
+        }
+
+        let format = doc_matches
+            .value_of("format")
+            .and_then(DocFormat::parse)
+            .unwrap();
+
+        match rock::doc::generate(&config, format) {
+            Ok(index) => println!("Documentation generated in {}", index.display()),
+            Err(_) => std::process::exit(1),
+        }
+    } else if matches.subcommand_matches("lsp").is_some() {
+        if let Err(e) = rock::lsp::run(&config) {
+            eprintln!("Error: {}", e);
+
+            std::process::exit(1);
+        }
     } else if let Some(matches) = matches.subcommand_matches("new") {
         create_project_folder(matches.value_of("name").unwrap());
     } else {
@@ -192,6 +694,168 @@ fn main() {
     }
 }
 
+// The entry point and the build folder are relative to the root of the project,
+// that the config keeps once it is found.
+fn load_project(config: &mut Config, manifest_path: Option<&str>) -> bool {
+    let current_dir = std::env::current_dir().unwrap();
+
+    let manifest_path = match manifest_path {
+        Some(manifest_path) => Some(current_dir.join(manifest_path)),
+        None => ProjectConfig::find_manifest(&current_dir),
+    };
+
+    config.project_config = match manifest_path {
+        Some(manifest_path) => match ProjectConfig::from_manifest(&manifest_path) {
+            Ok(project_config) => project_config,
+            Err(msg) => {
+                println!("Error: {}", msg);
+
+                return false;
+            }
+        },
+        None => ProjectConfig::new(String::new(), current_dir.clone(), PackageType::Bin),
+    };
+
+    config.project_config.relative_to(&current_dir);
+
+    config.build_folder = config.project_config.path(&config.build_folder);
+
+    true
+}
+
+// The options given on the command line win over the defaults of the project
+fn set_build_options(config: &mut Config, matches: &ArgMatches) {
+    config.opt_level = if matches.is_present("no-optimize") {
+        OptLevel::O0
+    } else {
+        matches
+            .value_of("opt-level")
+            .and_then(OptLevel::parse)
+            .unwrap_or_else(|| config.project_config.profile.opt_level())
+    };
+
+    config.lints = config.project_config.lints.clone();
+
+    if matches.is_present("deny-warnings") {
+        config.lints.set(WARNINGS_GROUP, LintLevel::Deny).unwrap();
+    }
+
+    // When a lint is given several times, the last one wins
+    let mut lints = vec![];
+
+    for (arg, level) in [
+        ("allow", LintLevel::Allow),
+        ("warn", LintLevel::Warn),
+        ("deny", LintLevel::Deny),
+    ] {
+        if let (Some(indices), Some(names)) = (matches.indices_of(arg), matches.values_of(arg)) {
+            lints.extend(indices.zip(names).map(|(index, name)| (index, name, level)));
+        }
+    }
+
+    lints.sort_by_key(|(index, _, _)| *index);
+
+    for (_, name, level) in lints {
+        config.lints.set(name, level).unwrap();
+    }
+
+    config.crate_type = matches
+        .value_of("crate-type")
+        .and_then(CrateType::parse)
+        .unwrap_or(match config.project_config.package_type {
+            PackageType::Bin => CrateType::Bin,
+            PackageType::Lib => CrateType::StaticLib,
+        });
+}
+
+fn find_source_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let mut entries = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect::<Vec<_>>(),
+        Err(_) => return,
+    };
+
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            find_source_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "rk") {
+            files.push(path);
+        }
+    }
+}
+
+// With `check`, the files are left untouched and the ones that differ are listed
+fn format_files(config: &Config, files: &[PathBuf], check: bool) -> bool {
+    let mut success = true;
+
+    for path in files {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) => {
+                println!("Error: Cannot read {}: {}", path.display(), e);
+
+                success = false;
+
+                continue;
+            }
+        };
+
+        // The diagnostics of the files that do not parse are already printed
+        let formatted = match formatter::format_str(path.to_str().unwrap(), &source, config) {
+            Ok(formatted) => formatted,
+            Err(_) => {
+                success = false;
+
+                continue;
+            }
+        };
+
+        if formatted == source {
+            continue;
+        }
+
+        if check {
+            println!("{} is not formatted", path.display());
+
+            success = false;
+        } else if let Err(e) = fs::write(path, formatted) {
+            println!("Error: Cannot write {}: {}", path.display(), e);
+
+            success = false;
+        }
+    }
+
+    success
+}
+
+fn explain(code: &str) -> bool {
+    match diagnostics::explain(code) {
+        Some(explanation) => {
+            println!("{}: {}\n", explanation.code, explanation.title);
+            print!("{}", explanation.text);
+
+            true
+        }
+        None => {
+            println!("Error: Unknown error code {}", code);
+
+            false
+        }
+    }
+}
+
+fn print_targets() {
+    println!("Host: {}\n", rock::host_triple());
+    println!("Available targets:");
+
+    for (name, description) in rock::available_targets() {
+        println!("  {:<12} - {}", name, description);
+    }
+}
+
 fn create_project_folder(name: &str) {
     let path = Path::new(name);
 
@@ -206,4 +870,16 @@ fn create_project_folder(name: &str) {
     let mut file = File::create(path.join("src/main.rk")).expect("Failed to create main.rk");
 
     file.write(b"main: -> \"Hello World !\".print!").unwrap();
+
+    let mut manifest = File::create(path.join(MANIFEST_FILE)).expect("Failed to create Rock.toml");
+
+    manifest
+        .write_all(
+            format!(
+                "[package]\nname = \"{}\"\nversion = \"0.0.1\"\ntype = \"bin\"\nprofile = \"debug\"\n",
+                name
+            )
+            .as_bytes(),
+        )
+        .unwrap();
 }
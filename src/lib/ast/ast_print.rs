@@ -92,6 +92,12 @@ impl_visitor_trait!(
     // Assign
     Impl
     FunctionDecl
+    FunctionClauses
+    Clause
+    Pattern
+    Const
+    TypeAlias
+    Newtype
     Identifier
     Body
     Statement
@@ -99,6 +105,7 @@ impl_visitor_trait!(
     While
     ForIn
     // Expression
+    TypeAscription
     /* If
     Else */
     // UnaryExpr
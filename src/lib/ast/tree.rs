@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use crate::{
     ast::NodeId,
-    helpers::*,
+    helpers::{scopes::Scope, *},
     infer::trait_solver::TraitSolver,
     parser::span::Span,
     resolver::ResolutionMap,
@@ -19,6 +19,8 @@ pub struct Root {
     pub operators_list: HashMap<String, u8>,
     pub unused: Vec<NodeId>,
     pub spans: HashMap<NodeId, Span>,
+    pub mod_scopes: HashMap<IdentifierPath, Scope<String, NodeId>>, // The top level names of each mod
+    pub docs: HashMap<NodeId, String>, // The `##` comments of the items
 }
 
 impl Root {
@@ -29,7 +31,9 @@ impl Root {
             operators_list: HashMap::new(),
             unused: vec![],
             spans: HashMap::new(),
+            mod_scopes: HashMap::new(),
             trait_solver: TraitSolver::new(),
+            docs: HashMap::new(),
         }
     }
 
@@ -38,7 +42,7 @@ impl Root {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Mod {
     pub top_levels: Vec<TopLevel>,
 }
@@ -49,11 +53,16 @@ impl Mod {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TopLevel {
     Extern(Prototype),
     FnSignature(Prototype),
     Function(FunctionDecl),
+    Clauses(FunctionClauses),
+    Test(Test),
+    Const(Const),
+    TypeAlias(TypeAlias),
+    Newtype(Newtype),
     Trait(Trait),
     Impl(Impl),
     Struct(StructDecl),
@@ -67,6 +76,44 @@ impl TopLevel {
         Self::Function(f)
     }
 
+    // A clause that only binds its arguments is a function, until another clause follows it
+    pub fn new_clause(c: Clause) -> Self {
+        if c.guard.is_some() || c.patterns.iter().any(|p| matches!(p, Pattern::Literal(..))) {
+            return Self::Clauses(FunctionClauses::new(vec![c]));
+        }
+
+        Self::Function(FunctionDecl {
+            name: c.name,
+            signature: FuncType::from_args_nb(c.patterns.len()),
+            arguments: c
+                .patterns
+                .into_iter()
+                .filter_map(|p| match p {
+                    Pattern::Identifier(id) => Some(id),
+                    Pattern::Literal(..) => None,
+                })
+                .collect(),
+            body: c.body,
+            node_id: c.node_id,
+        })
+    }
+
+    pub fn new_test(t: Test) -> Self {
+        Self::Test(t)
+    }
+
+    pub fn new_const(c: Const) -> Self {
+        Self::Const(c)
+    }
+
+    pub fn new_type_alias(t: TypeAlias) -> Self {
+        Self::TypeAlias(t)
+    }
+
+    pub fn new_newtype(n: Newtype) -> Self {
+        Self::Newtype(n)
+    }
+
     pub fn new_infix(op: Operator, pred: u8) -> Self {
         Self::Infix(op, pred)
     }
@@ -100,7 +147,7 @@ impl TopLevel {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StructDecl {
     pub name: Identifier,
     pub defs: Vec<Prototype>,
@@ -112,24 +159,26 @@ impl StructDecl {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StructCtor {
     pub name: Identifier,
-    pub defs: HashMap<Identifier, Expression>,
+    // In source order, with the duplicates, so they can be reported
+    pub defs: Vec<(Identifier, Expression)>,
 }
 
 impl StructCtor {
-    pub fn new(name: Identifier, defs: HashMap<Identifier, Expression>) -> Self {
+    pub fn new(name: Identifier, defs: Vec<(Identifier, Expression)>) -> Self {
         Self { name, defs }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Trait {
     pub name: Type,
     pub types: Vec<Type>,
     pub defs: Vec<Prototype>,
     pub default_impl: Vec<FunctionDecl>,
+    pub node_id: NodeId, // The span of the name
 }
 
 impl Trait {
@@ -138,30 +187,38 @@ impl Trait {
         types: Vec<Type>,
         defs: Vec<Prototype>,
         default_impl: Vec<FunctionDecl>,
+        node_id: NodeId,
     ) -> Self {
         Self {
             name,
             types,
             defs,
             default_impl,
+            node_id,
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Impl {
     pub name: Type,
     pub types: Vec<Type>,
     pub defs: Vec<FunctionDecl>,
+    pub node_id: NodeId, // The span of the name
 }
 
 impl Impl {
-    pub fn new(name: Type, types: Vec<Type>, defs: Vec<FunctionDecl>) -> Self {
-        Self { name, types, defs }
+    pub fn new(name: Type, types: Vec<Type>, defs: Vec<FunctionDecl>, node_id: NodeId) -> Self {
+        Self {
+            name,
+            types,
+            defs,
+            node_id,
+        }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Prototype {
     pub name: Identifier,
     pub signature: FuncType,
@@ -184,7 +241,7 @@ impl Prototype {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Use {
     pub path: IdentifierPath,
     pub node_id: NodeId,
@@ -196,7 +253,7 @@ impl Use {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FunctionDecl {
     pub name: Identifier,
     pub arguments: Vec<Identifier>,
@@ -234,7 +291,150 @@ impl FunctionDecl {
 
 generate_has_name!(FunctionDecl);
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+// A function defined by several clauses, like `fact: 0 -> 1` then `fact: n -> n * fact (n - 1)`.
+// The adjacent clauses of a name are merged by the parser, and the lowering turns them into
+// one function whose body tests each clause in turn.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionClauses {
+    pub name: Identifier,
+    pub clauses: Vec<Clause>,
+    pub node_id: NodeId, // The one of the first clause, that the callers resolve to
+    pub signature: FuncType,
+}
+
+impl FunctionClauses {
+    pub fn new(clauses: Vec<Clause>) -> Self {
+        let first = clauses.first().unwrap();
+
+        Self {
+            name: first.name.clone(),
+            node_id: first.node_id,
+            signature: FuncType::from_args_nb(first.patterns.len()),
+            clauses,
+        }
+    }
+
+    pub fn push(&mut self, clause: Clause) {
+        self.clauses.push(clause);
+    }
+}
+
+generate_has_name!(FunctionClauses);
+
+// `name: pattern, ... | guard -> body`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Clause {
+    pub name: Identifier,
+    pub patterns: Vec<Pattern>,
+    pub guard: Option<Expression>,
+    pub body: Body,
+    pub node_id: NodeId,
+}
+
+impl Clause {
+    pub fn new(
+        node_id: NodeId,
+        name: Identifier,
+        patterns: Vec<Pattern>,
+        guard: Option<Expression>,
+        body: Body,
+    ) -> Self {
+        Self {
+            name,
+            patterns,
+            guard,
+            body,
+            node_id,
+        }
+    }
+}
+
+// A function is the clause that binds each of its arguments
+impl From<FunctionDecl> for Clause {
+    fn from(f: FunctionDecl) -> Self {
+        Self::new(
+            f.node_id,
+            f.name,
+            f.arguments.into_iter().map(Pattern::Identifier).collect(),
+            None,
+            f.body,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Pattern {
+    Identifier(Identifier),
+    Literal(Literal, Identifier), // The `==` that compares the argument with the literal
+}
+
+// A `test "name": -> body` declaration. Its body is a function without arguments, that is
+// only called by the harness of `rock test`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Test {
+    pub name: String,
+    pub func: FunctionDecl,
+}
+
+impl Test {
+    pub fn new(name: String, func: FunctionDecl) -> Self {
+        Self { name, func }
+    }
+}
+
+// `const name: Type = value`, a global that is evaluated once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Const {
+    pub name: Identifier,
+    pub ty: Type,
+    pub value: Expression,
+    pub node_id: NodeId,
+}
+
+impl Const {
+    pub fn new(node_id: NodeId, name: Identifier, ty: Type, value: Expression) -> Self {
+        Self {
+            name,
+            ty,
+            value,
+            node_id,
+        }
+    }
+}
+
+// `type Name = Type`, another name for a type. The resolver expands it where it is used.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeAlias {
+    pub name: Identifier,
+    pub ty: Type,
+}
+
+impl TypeAlias {
+    pub fn new(name: Identifier, ty: Type) -> Self {
+        Self { name, ty }
+    }
+}
+
+// `newtype Name = Type`, a distinct type with the same representation as `ty`.
+// It is built with `Name value`, and `value.0` gives the wrapped value back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Newtype {
+    pub name: Identifier,
+    pub ty: Type,
+    pub node_id: NodeId,
+}
+
+impl Newtype {
+    pub fn new(node_id: NodeId, name: Identifier, ty: Type) -> Self {
+        Self { name, ty, node_id }
+    }
+
+    pub fn to_type(&self) -> Type {
+        Type::Newtype(self.name.name.clone(), Box::new(self.ty.clone()))
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub struct IdentifierPath {
     pub path: Vec<Identifier>,
 }
@@ -324,7 +524,7 @@ impl IdentifierPath {
     }
 }
 
-#[derive(Debug, Clone, Eq)]
+#[derive(Debug, Clone, Eq, Serialize, Deserialize)]
 pub struct Identifier {
     pub name: String,
     pub node_id: NodeId,
@@ -358,7 +558,7 @@ impl std::ops::Deref for Identifier {
 
 generate_has_name!(Identifier);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Body {
     pub stmts: Vec<Statement>,
 }
@@ -381,7 +581,7 @@ impl Body {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Statement {
     Expression(Box<Expression>),
     Assign(Box<Assign>),
@@ -407,13 +607,13 @@ impl Statement {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum For {
     In(ForIn),
     While(While),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct While {
     pub predicat: Expression,
     pub body: Body,
@@ -425,7 +625,7 @@ impl While {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ForIn {
     pub value: Identifier,
     pub expr: Expression,
@@ -438,31 +638,51 @@ impl ForIn {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AssignLeftSide {
     Identifier(Expression),
     Indice(Expression),
     Dot(Expression),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Assign {
     pub name: AssignLeftSide,
     pub value: Expression,
     pub is_let: bool,
+    pub ty: Option<TypeAscription>, // `let x: Float64 = ...`
 }
 
 impl Assign {
-    pub fn new(name: AssignLeftSide, value: Expression, is_let: bool) -> Self {
+    pub fn new(
+        name: AssignLeftSide,
+        value: Expression,
+        is_let: bool,
+        ty: Option<TypeAscription>,
+    ) -> Self {
         Self {
             name,
             value,
             is_let,
+            ty,
         }
     }
 }
 
-#[derive(Debug, Clone)]
+// The type written after a `let` name or in `(expr : Type)`, its node id spans the annotation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeAscription {
+    pub node_id: NodeId,
+    pub ty: Type,
+}
+
+impl TypeAscription {
+    pub fn new(node_id: NodeId, ty: Type) -> Self {
+        Self { node_id, ty }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct If {
     pub node_id: NodeId,
     pub predicat: Expression,
@@ -506,7 +726,7 @@ impl If {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Else {
     If(If),
     Body(Body),
@@ -528,13 +748,14 @@ impl Else {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Expression {
     BinopExpr(UnaryExpr, Operator, Box<Expression>),
     UnaryExpr(UnaryExpr),
     NativeOperation(NativeOperator, Identifier, Identifier),
     StructCtor(StructCtor),
     Return(Box<Expression>), // NOTE: Shouldn't that be a statement?
+    Ascription(Box<Expression>, TypeAscription),
 }
 
 impl Expression {
@@ -599,6 +820,10 @@ impl Expression {
         Expression::StructCtor(ctor)
     }
 
+    pub fn new_ascription(expr: Expression, ty: TypeAscription) -> Expression {
+        Expression::Ascription(Box::new(expr), ty)
+    }
+
     pub fn new_native_operator(
         operator: NativeOperator,
         id1: Identifier,
@@ -615,7 +840,7 @@ impl Expression {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum UnaryExpr {
     PrimaryExpr(PrimaryExpr),
     #[allow(dead_code)]
@@ -674,10 +899,10 @@ impl UnaryExpr {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Operator(pub Identifier);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PrimaryExpr {
     pub node_id: NodeId,
     pub op: Operand,
@@ -726,7 +951,7 @@ impl PrimaryExpr {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Operand {
     Literal(Literal),
     Identifier(IdentifierPath),
@@ -803,7 +1028,7 @@ impl Operand {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SecondaryExpr {
     Arguments(Vec<Argument>),
     Indice(Box<Expression>), // Boxing here to keep the enum size low
@@ -826,7 +1051,7 @@ impl SecondaryExpr {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Literal {
     pub kind: LiteralKind,
     pub node_id: NodeId,
@@ -890,7 +1115,7 @@ impl Literal {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum LiteralKind {
     Bool(bool),
     Number(i64),
@@ -900,7 +1125,7 @@ pub enum LiteralKind {
     Char(char),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Array {
     pub values: Vec<Expression>,
 }
@@ -913,7 +1138,7 @@ impl Array {
 
 pub type Arguments = Vec<Argument>;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Argument {
     pub arg: UnaryExpr,
 }
@@ -924,7 +1149,7 @@ impl Argument {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NativeOperator {
     pub kind: NativeOperatorKind,
     pub node_id: NodeId,
@@ -936,7 +1161,7 @@ impl NativeOperator {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum NativeOperatorKind {
     IAdd,
     ISub,
@@ -961,29 +1186,29 @@ pub enum NativeOperatorKind {
 }
 
 impl NativeOperatorKind {
-    pub fn from_str(s: &str) -> Self {
+    pub fn from_str(s: &str) -> Option<Self> {
         match s {
-            "IAdd" => Self::IAdd,
-            "ISub" => Self::ISub,
-            "IMul" => Self::IMul,
-            "IDiv" => Self::IDiv,
-            "FAdd" => Self::FAdd,
-            "FSub" => Self::FSub,
-            "FMul" => Self::FMul,
-            "FDiv" => Self::FDiv,
-            "IEq" => Self::IEq,
-            "Igt" => Self::Igt,
-            "Ige" => Self::Ige,
-            "Ilt" => Self::Ilt,
-            "Ile" => Self::Ile,
-            "FEq" => Self::FEq,
-            "Fgt" => Self::Fgt,
-            "Fge" => Self::Fge,
-            "Flt" => Self::Flt,
-            "Fle" => Self::Fle,
-            "BEq" => Self::BEq,
-            "Len" => Self::Len,
-            _ => panic!("Unknown native operator"),
+            "IAdd" => Some(Self::IAdd),
+            "ISub" => Some(Self::ISub),
+            "IMul" => Some(Self::IMul),
+            "IDiv" => Some(Self::IDiv),
+            "FAdd" => Some(Self::FAdd),
+            "FSub" => Some(Self::FSub),
+            "FMul" => Some(Self::FMul),
+            "FDiv" => Some(Self::FDiv),
+            "IEq" => Some(Self::IEq),
+            "Igt" => Some(Self::Igt),
+            "Ige" => Some(Self::Ige),
+            "Ilt" => Some(Self::Ilt),
+            "Ile" => Some(Self::Ile),
+            "FEq" => Some(Self::FEq),
+            "Fgt" => Some(Self::Fgt),
+            "Fge" => Some(Self::Fge),
+            "Flt" => Some(Self::Flt),
+            "Fle" => Some(Self::Fle),
+            "BEq" => Some(Self::BEq),
+            "Len" => Some(Self::Len),
+            _ => None,
         }
     }
 }
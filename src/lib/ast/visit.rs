@@ -37,6 +37,12 @@ generate_visitor_trait!(
     Trait
     Impl
     FunctionDecl
+    FunctionClauses
+    Clause
+    Pattern
+    Const
+    TypeAlias
+    Newtype
     StructDecl
     Identifier
     IdentifierPath
@@ -46,6 +52,7 @@ generate_visitor_trait!(
     ForIn
     While
     Expression
+    TypeAscription
     If
     Else
     UnaryExpr
@@ -83,10 +90,35 @@ pub fn walk_top_level<'a, V: Visitor<'a>>(visitor: &mut V, top_level: &'a TopLev
             visitor.visit_mod(m);
         }
         TopLevel::Function(f) => visitor.visit_function_decl(f),
+        TopLevel::Clauses(c) => visitor.visit_function_clauses(c),
+        TopLevel::Test(t) => visitor.visit_function_decl(&t.func),
+        TopLevel::Const(c) => visitor.visit_const(c),
+        TopLevel::TypeAlias(t) => visitor.visit_type_alias(t),
+        TopLevel::Newtype(n) => visitor.visit_newtype(n),
         TopLevel::Infix(ident, _) => visitor.visit_operator(ident),
     };
 }
 
+pub fn walk_const<'a, V: Visitor<'a>>(visitor: &mut V, c: &'a Const) {
+    visitor.visit_identifier(&c.name);
+
+    visitor.visit_type(&c.ty);
+
+    visitor.visit_expression(&c.value);
+}
+
+pub fn walk_type_alias<'a, V: Visitor<'a>>(visitor: &mut V, t: &'a TypeAlias) {
+    visitor.visit_identifier(&t.name);
+
+    visitor.visit_type(&t.ty);
+}
+
+pub fn walk_newtype<'a, V: Visitor<'a>>(visitor: &mut V, n: &'a Newtype) {
+    visitor.visit_identifier(&n.name);
+
+    visitor.visit_type(&n.ty);
+}
+
 pub fn walk_struct_decl<'a, V: Visitor<'a>>(visitor: &mut V, s: &'a StructDecl) {
     visitor.visit_identifier(&s.name);
 
@@ -127,6 +159,32 @@ pub fn walk_function_decl<'a, V: Visitor<'a>>(visitor: &mut V, function_decl: &'
     visitor.visit_body(&function_decl.body);
 }
 
+pub fn walk_function_clauses<'a, V: Visitor<'a>>(visitor: &mut V, clauses: &'a FunctionClauses) {
+    walk_list!(visitor, visit_clause, &clauses.clauses);
+}
+
+pub fn walk_clause<'a, V: Visitor<'a>>(visitor: &mut V, clause: &'a Clause) {
+    visitor.visit_identifier(&clause.name);
+
+    walk_list!(visitor, visit_pattern, &clause.patterns);
+
+    if let Some(guard) = &clause.guard {
+        visitor.visit_expression(guard);
+    }
+
+    visitor.visit_body(&clause.body);
+}
+
+pub fn walk_pattern<'a, V: Visitor<'a>>(visitor: &mut V, pattern: &'a Pattern) {
+    match pattern {
+        Pattern::Identifier(id) => visitor.visit_identifier(id),
+        Pattern::Literal(lit, op) => {
+            visitor.visit_literal(lit);
+            visitor.visit_identifier(op);
+        }
+    }
+}
+
 pub fn walk_identifier_path<'a, V: Visitor<'a>>(
     visitor: &mut V,
     identifier_path: &'a IdentifierPath,
@@ -179,6 +237,11 @@ pub fn walk_assign_left_side<'a, V: Visitor<'a>>(visitor: &mut V, assign_left: &
 
 pub fn walk_assign<'a, V: Visitor<'a>>(visitor: &mut V, assign: &'a Assign) {
     visitor.visit_assign_left_side(&assign.name);
+
+    if let Some(ty) = &assign.ty {
+        visitor.visit_type_ascription(ty);
+    }
+
     visitor.visit_expression(&assign.value);
 }
 
@@ -214,9 +277,17 @@ pub fn walk_expression<'a, V: Visitor<'a>>(visitor: &mut V, expr: &'a Expression
         Expression::Return(expr) => {
             visitor.visit_expression(expr);
         }
+        Expression::Ascription(expr, ty) => {
+            visitor.visit_expression(expr);
+            visitor.visit_type_ascription(ty);
+        }
     }
 }
 
+pub fn walk_type_ascription<'a, V: Visitor<'a>>(visitor: &mut V, ty: &'a TypeAscription) {
+    visitor.visit_type(&ty.ty);
+}
+
 pub fn walk_struct_ctor<'a, V: Visitor<'a>>(visitor: &mut V, s: &'a StructCtor) {
     visitor.visit_identifier(&s.name);
 
@@ -37,6 +37,12 @@ generate_visitor_mut_trait!(
     Trait
     Impl
     FunctionDecl
+    FunctionClauses
+    Clause
+    Pattern
+    Const
+    TypeAlias
+    Newtype
     StructDecl
     Identifier
     IdentifierPath
@@ -46,6 +52,7 @@ generate_visitor_mut_trait!(
     ForIn
     While
     Expression
+    TypeAscription
     If
     Else
     UnaryExpr
@@ -83,10 +90,35 @@ pub fn walk_top_level<'a, V: VisitorMut<'a>>(visitor: &mut V, top_level: &'a mut
             visitor.visit_mod(m);
         }
         TopLevel::Function(f) => visitor.visit_function_decl(f),
+        TopLevel::Clauses(c) => visitor.visit_function_clauses(c),
+        TopLevel::Test(t) => visitor.visit_function_decl(&mut t.func),
+        TopLevel::Const(c) => visitor.visit_const(c),
+        TopLevel::TypeAlias(t) => visitor.visit_type_alias(t),
+        TopLevel::Newtype(n) => visitor.visit_newtype(n),
         TopLevel::Infix(ident, _) => visitor.visit_operator(ident),
     };
 }
 
+pub fn walk_const<'a, V: VisitorMut<'a>>(visitor: &mut V, c: &'a mut Const) {
+    visitor.visit_identifier(&mut c.name);
+
+    visitor.visit_type(&mut c.ty);
+
+    visitor.visit_expression(&mut c.value);
+}
+
+pub fn walk_type_alias<'a, V: VisitorMut<'a>>(visitor: &mut V, t: &'a mut TypeAlias) {
+    visitor.visit_identifier(&mut t.name);
+
+    visitor.visit_type(&mut t.ty);
+}
+
+pub fn walk_newtype<'a, V: VisitorMut<'a>>(visitor: &mut V, n: &'a mut Newtype) {
+    visitor.visit_identifier(&mut n.name);
+
+    visitor.visit_type(&mut n.ty);
+}
+
 pub fn walk_struct_decl<'a, V: VisitorMut<'a>>(visitor: &mut V, s: &'a mut StructDecl) {
     visitor.visit_identifier(&mut s.name);
 
@@ -130,6 +162,35 @@ pub fn walk_function_decl<'a, V: VisitorMut<'a>>(
     visitor.visit_body(&mut function_decl.body);
 }
 
+pub fn walk_function_clauses<'a, V: VisitorMut<'a>>(
+    visitor: &mut V,
+    clauses: &'a mut FunctionClauses,
+) {
+    walk_list!(visitor, visit_clause, &mut clauses.clauses);
+}
+
+pub fn walk_clause<'a, V: VisitorMut<'a>>(visitor: &mut V, clause: &'a mut Clause) {
+    visitor.visit_identifier(&mut clause.name);
+
+    walk_list!(visitor, visit_pattern, &mut clause.patterns);
+
+    if let Some(guard) = &mut clause.guard {
+        visitor.visit_expression(guard);
+    }
+
+    visitor.visit_body(&mut clause.body);
+}
+
+pub fn walk_pattern<'a, V: VisitorMut<'a>>(visitor: &mut V, pattern: &'a mut Pattern) {
+    match pattern {
+        Pattern::Identifier(id) => visitor.visit_identifier(id),
+        Pattern::Literal(lit, op) => {
+            visitor.visit_literal(lit);
+            visitor.visit_identifier(op);
+        }
+    }
+}
+
 pub fn walk_identifier_path<'a, V: VisitorMut<'a>>(
     visitor: &mut V,
     identifier_path: &'a mut IdentifierPath,
@@ -185,6 +246,11 @@ pub fn walk_assign_left_side<'a, V: VisitorMut<'a>>(
 
 pub fn walk_assign<'a, V: VisitorMut<'a>>(visitor: &mut V, assign: &'a mut Assign) {
     visitor.visit_assign_left_side(&mut assign.name);
+
+    if let Some(ty) = &mut assign.ty {
+        visitor.visit_type_ascription(ty);
+    }
+
     visitor.visit_expression(&mut assign.value);
 }
 
@@ -220,9 +286,17 @@ pub fn walk_expression<'a, V: VisitorMut<'a>>(visitor: &mut V, expr: &'a mut Exp
         Expression::Return(expr) => {
             visitor.visit_expression(expr);
         }
+        Expression::Ascription(expr, ty) => {
+            visitor.visit_expression(expr);
+            visitor.visit_type_ascription(ty);
+        }
     }
 }
 
+pub fn walk_type_ascription<'a, V: VisitorMut<'a>>(visitor: &mut V, ty: &'a mut TypeAscription) {
+    visitor.visit_type(&mut ty.ty);
+}
+
 pub fn walk_struct_ctor<'a, V: VisitorMut<'a>>(visitor: &mut V, s: &'a mut StructCtor) {
     visitor.visit_identifier(&mut s.name);
 
@@ -8,7 +8,7 @@ use crate::{
     ty::*,
 };
 
-use super::{hir_map::HirMap, InfixDesugar};
+use super::{check_clauses, hir_map::HirMap, InfixDesugar};
 
 pub struct AstLoweringContext {
     hir_map: HirMap,
@@ -21,6 +21,8 @@ pub struct AstLoweringContext {
     struct_methods: BTreeMap<HirId, HashMap<FuncType, hir::FunctionDecl>>,
     structs: HashMap<String, hir::StructDecl>,
     signatures: ResolutionMap<NodeId>, // FnHirId -> SignatureHirId
+    clause_resolutions: ResolutionMap<HirId>, // The identifiers that the clauses introduce
+    mod_path: Vec<String>,
 }
 
 impl AstLoweringContext {
@@ -35,6 +37,8 @@ impl AstLoweringContext {
             struct_methods: BTreeMap::new(),
             structs: HashMap::new(),
             signatures: ResolutionMap::new(),
+            clause_resolutions: ResolutionMap::new(),
+            mod_path: Vec::new(),
             operators_list,
         }
     }
@@ -43,10 +47,14 @@ impl AstLoweringContext {
         self.resolutions = root.resolutions.clone();
         self.lower_mod(&root.r#mod);
 
+        let mut resolutions = root.resolutions.lower_resolution_map(&self.hir_map);
+
+        resolutions.extend(self.clause_resolutions.clone());
+
         let mut hir = hir::Root {
             arena: Arena::new(),
             hir_map: self.hir_map.clone(),
-            resolutions: root.resolutions.lower_resolution_map(&self.hir_map),
+            resolutions,
             type_envs: Envs::default(),
             node_types: BTreeMap::new(),
             top_levels: self.top_levels.clone(),
@@ -105,6 +113,20 @@ impl AstLoweringContext {
 
                 self.top_levels.push(top_level);
             }
+            TopLevel::Clauses(c) => {
+                let top_level = hir::TopLevel {
+                    kind: hir::TopLevelKind::Function(self.lower_function_clauses(c)),
+                };
+
+                self.top_levels.push(top_level);
+            }
+            TopLevel::Test(t) => {
+                let top_level = hir::TopLevel {
+                    kind: hir::TopLevelKind::Function(self.lower_function_decl(&t.func)),
+                };
+
+                self.top_levels.push(top_level);
+            }
             TopLevel::Trait(t) => {
                 self.lower_trait(t);
             }
@@ -114,7 +136,29 @@ impl AstLoweringContext {
             TopLevel::Impl(i) => {
                 self.lower_impl(i);
             }
-            TopLevel::Mod(_name, mod_) => self.lower_mod(mod_),
+            TopLevel::Const(c) => {
+                let top_level = hir::TopLevel {
+                    kind: hir::TopLevelKind::Const(self.lower_const(c)),
+                };
+
+                self.top_levels.push(top_level);
+            }
+            TopLevel::Newtype(n) => {
+                let top_level = hir::TopLevel {
+                    kind: hir::TopLevelKind::Newtype(self.lower_newtype(n)),
+                };
+
+                self.top_levels.push(top_level);
+            }
+            // Already expanded by the parser
+            TopLevel::TypeAlias(_) => (),
+            TopLevel::Mod(name, mod_) => {
+                self.mod_path.push(name.name.clone());
+
+                self.lower_mod(mod_);
+
+                self.mod_path.pop();
+            }
             TopLevel::Infix(_, _) => (),
             TopLevel::Use(_u) => (),
         };
@@ -238,6 +282,277 @@ impl AstLoweringContext {
         }
     }
 
+    // fact: 0 -> 1
+    // fact: n -> n * fact (n - 1)
+    //
+    // --- becomes
+    //
+    // fact: arg0 ->
+    //   if arg0 == 0 then 1
+    //   else arg0 * fact (arg0 - 1)
+    //
+    // The identifiers of the patterns are aliases of the arguments. The conditions of a clause
+    // are evaluated in turn in a flag, so that its guard only runs when its literals match:
+    //
+    //   let matched = arg0 == 0
+    //   if matched then matched = guard else matched = false
+    //   if matched then body else <the next clauses>
+    pub fn lower_function_clauses(&mut self, c: &FunctionClauses) -> hir::FunctionDecl {
+        let body_id = self.hir_map.next_body_id();
+        let id = self.hir_map.next_hir_id(c.node_id);
+        let ident = self.lower_identifier(&c.name);
+
+        let arguments = c.clauses[0]
+            .patterns
+            .iter()
+            .enumerate()
+            .map(|(i, pattern)| {
+                let (node_id, name) = match pattern {
+                    Pattern::Identifier(id) => (id.node_id, id.name.clone()),
+                    Pattern::Literal(lit, _) => (lit.node_id, format!("arg{}", i)),
+                };
+
+                hir::ArgumentDecl {
+                    name: hir::Identifier {
+                        hir_id: self.hir_map.next_hir_id(node_id),
+                        name,
+                    },
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // In reverse, so that the arguments keep the span of the first clause
+        for clause in c.clauses.iter().rev() {
+            for (pattern, arg) in clause.patterns.iter().zip(&arguments) {
+                if let Pattern::Identifier(id) = pattern {
+                    self.hir_map
+                        .add_hir_mapping(arg.name.hir_id.clone(), id.node_id);
+                }
+            }
+        }
+
+        // The unreachable clauses have been reported, and the last one matches when the
+        // others do not as the clauses are exhaustive
+        let check = check_clauses(c);
+
+        let clauses = c
+            .clauses
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| check.is_reachable(*i))
+            .map(|(_, clause)| clause)
+            .collect::<Vec<_>>();
+
+        let body = self.lower_clauses(&clauses, &arguments);
+
+        self.bodies.insert(
+            body_id.clone(),
+            hir::FnBody {
+                id: body_id.clone(),
+                fn_id: id.clone(),
+                name: ident.clone(),
+                mangled_name: None,
+                body,
+            },
+        );
+
+        hir::FunctionDecl {
+            name: ident,
+            mangled_name: None,
+            arguments,
+            body_id,
+            signature: c.signature.clone(),
+            hir_id: id,
+        }
+    }
+
+    fn lower_clauses(&mut self, clauses: &[&Clause], arguments: &[hir::ArgumentDecl]) -> hir::Body {
+        let (clause, rest) = clauses.split_first().unwrap();
+
+        if rest.is_empty() {
+            return self.lower_body(&clause.body);
+        }
+
+        let mut conditions = clause
+            .patterns
+            .iter()
+            .zip(arguments)
+            .filter_map(|(pattern, arg)| match pattern {
+                Pattern::Literal(lit, op) => Some(self.lower_literal_pattern(lit, op, arg)),
+                Pattern::Identifier(_) => None,
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(guard) = &clause.guard {
+            conditions.push(self.lower_expression(guard));
+        }
+
+        // The synthesized nodes have the span of the name of the clause
+        let node_id = clause.name.node_id;
+
+        let mut stmts = vec![];
+
+        let predicat = if conditions.len() == 1 {
+            conditions.pop().unwrap()
+        } else {
+            let mut conditions = conditions.into_iter();
+
+            let flag = hir::Identifier {
+                hir_id: self.hir_map.next_hir_id(node_id),
+                name: "matched".to_string(),
+            };
+
+            stmts.push(hir::Statement {
+                kind: Box::new(hir::StatementKind::Assign(hir::Assign {
+                    name: hir::AssignLeftSide::Identifier(flag.clone()),
+                    value: conditions.next().unwrap(),
+                    is_let: true,
+                })),
+            });
+
+            for condition in conditions {
+                let otherwise = hir::Expression::new_literal(hir::Literal {
+                    hir_id: self.hir_map.next_hir_id(node_id),
+                    kind: hir::LiteralKind::Bool(false),
+                });
+
+                let if_chain = hir::IfChain {
+                    ifs: vec![hir::If {
+                        hir_id: self.hir_map.next_hir_id(node_id),
+                        predicat: self.clause_flag(&flag, node_id),
+                        body: self.assign_clause_flag(&flag, node_id, condition),
+                    }],
+                    else_body: Some(self.assign_clause_flag(&flag, node_id, otherwise)),
+                };
+
+                stmts.push(hir::Statement {
+                    kind: Box::new(hir::StatementKind::If(if_chain)),
+                });
+            }
+
+            self.clause_flag(&flag, node_id)
+        };
+
+        let mut ifs = vec![hir::If {
+            hir_id: self.hir_map.next_hir_id(node_id),
+            predicat,
+            body: self.lower_body(&clause.body),
+        }];
+
+        let mut else_body = self.lower_clauses(rest, arguments);
+
+        // The next clauses continue the chain when they need no flag
+        if let [stmt] = else_body.stmts.as_slice() {
+            if let hir::StatementKind::If(if_chain) = &*stmt.kind {
+                ifs.extend(if_chain.ifs.clone());
+
+                if let Some(body) = if_chain.else_body.clone() {
+                    else_body = body;
+                }
+            }
+        }
+
+        stmts.push(hir::Statement {
+            kind: Box::new(hir::StatementKind::If(hir::IfChain {
+                ifs,
+                else_body: Some(else_body),
+            })),
+        });
+
+        hir::Body { stmts }
+    }
+
+    // `arg == lit`, with the `==` in scope
+    fn lower_literal_pattern(
+        &mut self,
+        lit: &Literal,
+        op: &Identifier,
+        arg: &hir::ArgumentDecl,
+    ) -> hir::Expression {
+        let arg_ident = hir::Identifier {
+            hir_id: self.hir_map.next_hir_id(lit.node_id),
+            name: arg.name.name.clone(),
+        };
+
+        self.clause_resolutions
+            .insert(arg_ident.hir_id.clone(), arg.name.hir_id.clone());
+
+        let op = self.lower_identifier(op);
+
+        hir::Expression::new_function_call(hir::FunctionCall {
+            hir_id: self.hir_map.next_hir_id(lit.node_id),
+            op: hir::Expression::new_identifier_path(hir::IdentifierPath { path: vec![op] }),
+            args: vec![
+                hir::Expression::new_identifier_path(hir::IdentifierPath {
+                    path: vec![arg_ident],
+                }),
+                hir::Expression::new_literal(self.lower_literal(lit)),
+            ],
+        })
+    }
+
+    fn clause_flag(&mut self, flag: &hir::Identifier, node_id: NodeId) -> hir::Expression {
+        let ident = hir::Identifier {
+            hir_id: self.hir_map.next_hir_id(node_id),
+            name: flag.name.clone(),
+        };
+
+        self.clause_resolutions
+            .insert(ident.hir_id.clone(), flag.hir_id.clone());
+
+        hir::Expression::new_identifier_path(hir::IdentifierPath { path: vec![ident] })
+    }
+
+    fn assign_clause_flag(
+        &mut self,
+        flag: &hir::Identifier,
+        node_id: NodeId,
+        value: hir::Expression,
+    ) -> hir::Body {
+        let ident = hir::Identifier {
+            hir_id: self.hir_map.next_hir_id(node_id),
+            name: flag.name.clone(),
+        };
+
+        self.clause_resolutions
+            .insert(ident.hir_id.clone(), flag.hir_id.clone());
+
+        hir::Body {
+            stmts: vec![hir::Statement {
+                kind: Box::new(hir::StatementKind::Assign(hir::Assign {
+                    name: hir::AssignLeftSide::Identifier(ident),
+                    value,
+                    is_let: false,
+                })),
+            }],
+        }
+    }
+
+    pub fn lower_const(&mut self, c: &Const) -> hir::Const {
+        let id = self.hir_map.next_hir_id(c.node_id);
+
+        let mut path = self.mod_path.clone();
+        path.push(c.name.name.clone());
+
+        hir::Const {
+            name: self.lower_identifier(&c.name),
+            symbol: format!("{}{}", hir::CONST_PREFIX, path.join(".")),
+            ty: c.ty.clone(),
+            value: self.lower_expression(&c.value),
+            hir_id: id,
+        }
+    }
+
+    pub fn lower_newtype(&mut self, n: &Newtype) -> hir::Newtype {
+        let id = self.hir_map.next_hir_id(n.node_id);
+
+        hir::Newtype {
+            name: self.lower_identifier(&n.name),
+            ty: n.to_type(),
+            hir_id: id,
+        }
+    }
+
     pub fn lower_argument_decl(&mut self, identifier: &Identifier) -> hir::ArgumentDecl {
         let id = self.hir_map.next_hir_id(identifier.node_id);
 
@@ -347,13 +662,32 @@ impl AstLoweringContext {
     }
 
     pub fn lower_assign(&mut self, assign: &Assign) -> hir::Assign {
+        let name = self.lower_assign_left_side(&assign.name);
+        let value = self.lower_expression(&assign.value);
+
         hir::Assign {
-            name: self.lower_assign_left_side(&assign.name),
-            value: self.lower_expression(&assign.value),
+            name,
+            // `let x: T = value` is checked like `let x = (value : T)`
+            value: match &assign.ty {
+                Some(ty) => self.lower_type_ascription(value, ty),
+                None => value,
+            },
             is_let: assign.is_let,
         }
     }
 
+    pub fn lower_type_ascription(
+        &mut self,
+        expr: hir::Expression,
+        ty: &TypeAscription,
+    ) -> hir::Expression {
+        hir::Expression::new_ascription(hir::Ascription {
+            hir_id: self.hir_map.next_hir_id(ty.node_id),
+            expr,
+            ty: ty.ty.clone(),
+        })
+    }
+
     pub fn lower_expression(&mut self, expr: &Expression) -> hir::Expression {
         match &expr {
             Expression::UnaryExpr(unary) => self.lower_unary(unary),
@@ -369,6 +703,11 @@ impl AstLoweringContext {
             Expression::Return(expr) => hir::Expression {
                 kind: Box::new(hir::ExpressionKind::Return(self.lower_expression(&*expr))),
             },
+            Expression::Ascription(expr, ty) => {
+                let expr = self.lower_expression(expr);
+
+                self.lower_type_ascription(expr, ty)
+            }
         }
     }
 
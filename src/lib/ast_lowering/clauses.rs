@@ -0,0 +1,135 @@
+use crate::ast::tree::{Clause, FunctionClauses, Literal, LiteralKind, Pattern};
+
+// What the patterns of the clauses of a function tell, before they are lowered
+#[derive(Debug, Default, PartialEq)]
+pub struct ClausesCheck {
+    pub arity_mismatch: Option<usize>, // The first clause that does not take as many arguments
+    pub unreachable: Vec<usize>,       // The clauses that the previous ones always match before
+    pub exhaustive: bool,
+}
+
+impl ClausesCheck {
+    pub fn is_reachable(&self, i: usize) -> bool {
+        !self.unreachable.contains(&i)
+    }
+}
+
+// A column of the matrix only holds wildcards or literals, as the identifiers bind anything
+#[derive(Debug, Clone)]
+enum Pat<'a> {
+    Wild,
+    Lit(&'a LiteralKind),
+}
+
+impl<'a> Pat<'a> {
+    fn from_pattern(pattern: &'a Pattern) -> Self {
+        match pattern {
+            Pattern::Identifier(_) => Pat::Wild,
+            Pattern::Literal(Literal { kind, .. }, _) => Pat::Lit(kind),
+        }
+    }
+}
+
+type Row<'a> = Vec<Pat<'a>>;
+
+pub fn check_clauses(clauses: &FunctionClauses) -> ClausesCheck {
+    let arity = clauses.clauses[0].patterns.len();
+
+    if let Some(i) = clauses
+        .clauses
+        .iter()
+        .position(|clause| clause.patterns.len() != arity)
+    {
+        return ClausesCheck {
+            arity_mismatch: Some(i),
+            ..Default::default()
+        };
+    }
+
+    let mut check = ClausesCheck::default();
+
+    // A guarded clause may not match, so only the unguarded ones cover the next ones
+    let mut covering: Vec<Row> = vec![];
+
+    for (i, clause) in clauses.clauses.iter().enumerate() {
+        let row = to_row(clause);
+
+        if !is_useful(&covering, &row) {
+            check.unreachable.push(i);
+        }
+
+        if clause.guard.is_none() {
+            covering.push(row);
+        }
+    }
+
+    check.exhaustive = !is_useful(&covering, &vec![Pat::Wild; arity]);
+
+    check
+}
+
+fn to_row(clause: &Clause) -> Row<'_> {
+    clause.patterns.iter().map(Pat::from_pattern).collect()
+}
+
+// Whether some arguments match `row` but none of the `rows`
+fn is_useful(rows: &[Row], row: &[Pat]) -> bool {
+    let (first, rest) = match row.split_first() {
+        Some(split) => split,
+        None => return rows.is_empty(),
+    };
+
+    match first {
+        Pat::Lit(lit) => is_useful(&specialize(rows, lit), rest),
+        Pat::Wild => {
+            let lits = rows
+                .iter()
+                .filter_map(|row| match &row[0] {
+                    Pat::Lit(lit) => Some(*lit),
+                    Pat::Wild => None,
+                })
+                .collect::<Vec<_>>();
+
+            // Only the booleans have a finite number of literals
+            let is_complete = [true, false].iter().all(|b| {
+                lits.iter()
+                    .any(|lit| same_literal(lit, &LiteralKind::Bool(*b)))
+            });
+
+            if is_complete {
+                lits.iter()
+                    .any(|lit| is_useful(&specialize(rows, lit), rest))
+            } else {
+                let defaults = rows
+                    .iter()
+                    .filter(|row| matches!(row[0], Pat::Wild))
+                    .map(|row| row[1..].to_vec())
+                    .collect::<Vec<_>>();
+
+                is_useful(&defaults, rest)
+            }
+        }
+    }
+}
+
+// The rest of the rows that match the literal `lit` in their first column
+fn specialize<'a>(rows: &[Row<'a>], lit: &LiteralKind) -> Vec<Row<'a>> {
+    rows.iter()
+        .filter(|row| match &row[0] {
+            Pat::Wild => true,
+            Pat::Lit(other) => same_literal(other, lit),
+        })
+        .map(|row| row[1..].to_vec())
+        .collect()
+}
+
+fn same_literal(a: &LiteralKind, b: &LiteralKind) -> bool {
+    match (a, b) {
+        (LiteralKind::Number(a), LiteralKind::Number(b)) => a == b,
+        (LiteralKind::Float(a), LiteralKind::Float(b)) => a.to_bits() == b.to_bits(),
+        (LiteralKind::Bool(a), LiteralKind::Bool(b)) => a == b,
+        (LiteralKind::String(a), LiteralKind::String(b)) => a == b,
+        (LiteralKind::Char(a), LiteralKind::Char(b)) => a == b,
+        _ => false,
+    }
+}
@@ -51,6 +51,20 @@ impl HirMap {
         self.rev_map.get(&node_id).cloned()
     }
 
+    // Every instance of a node, the monomorphization duplicates the generic ones
+    pub fn get_hir_ids(&self, node_id: NodeId) -> Vec<HirId> {
+        let mut hir_ids = self
+            .map
+            .iter()
+            .filter(|(_, other)| **other == node_id)
+            .map(|(hir_id, _)| hir_id.clone())
+            .collect::<Vec<_>>();
+
+        hir_ids.sort();
+
+        hir_ids
+    }
+
     pub fn get_node_id(&self, hir_id: &HirId) -> Option<NodeId> {
         self.map.get(hir_id).cloned()
     }
@@ -1,10 +1,15 @@
 use crate::{ast::tree::Root, hir};
 
 mod ast_lowering_context;
+mod clauses;
 mod hir_map;
 mod infix_desugar;
 
+#[cfg(test)]
+mod tests;
+
 use ast_lowering_context::AstLoweringContext;
+pub use clauses::*;
 pub use hir_map::*;
 pub use infix_desugar::*;
 
@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+
+use nom::Finish;
+
+use super::*;
+use crate::{
+    ast::tree::{FunctionClauses, TopLevel},
+    parser::{parse_root, Parser, ParserCtx},
+    Config,
+};
+
+fn clauses(source: &str) -> FunctionClauses {
+    let input = Parser::new_extra(source, ParserCtx::new(PathBuf::new(), Config::default()));
+
+    let (_rest, parsed) = parse_root(input).finish().unwrap();
+
+    match parsed.r#mod.top_levels.into_iter().next() {
+        Some(TopLevel::Clauses(c)) => c,
+        top_level => panic!("Expected clauses, got {:?}", top_level),
+    }
+}
+
+#[cfg(test)]
+mod check_clauses {
+    use super::*;
+
+    #[test]
+    fn exhaustive() {
+        let check = check_clauses(&clauses("fact: 0 -> 1\nfact: n -> n\n"));
+
+        assert_eq!(check.arity_mismatch, None);
+        assert!(check.unreachable.is_empty());
+        assert!(check.exhaustive);
+    }
+
+    #[test]
+    fn non_exhaustive() {
+        let check = check_clauses(&clauses("f: 0 -> 1\nf: n | n == 2 -> 2\n"));
+
+        assert!(check.unreachable.is_empty());
+        assert!(!check.exhaustive);
+    }
+
+    #[test]
+    fn unreachable() {
+        let check = check_clauses(&clauses("f: 0, b -> 1\nf: a, b -> 2\nf: 1, 2 -> 3\n"));
+
+        assert_eq!(check.unreachable, vec![2]);
+        assert!(check.exhaustive);
+    }
+
+    #[test]
+    fn guards_do_not_cover() {
+        let check = check_clauses(&clauses("f: n | n == 0 -> 1\nf: 0 -> 2\nf: n -> 3\n"));
+
+        assert!(check.unreachable.is_empty());
+    }
+
+    #[test]
+    fn complete_booleans() {
+        let check = check_clauses(&clauses("f: true -> 1\nf: false -> 0\nf: b -> 2\n"));
+
+        assert_eq!(check.unreachable, vec![2]);
+        assert!(check.exhaustive);
+    }
+
+    #[test]
+    fn arity_mismatch() {
+        let check = check_clauses(&clauses("h: 0, y -> y\nh: x -> x\n"));
+
+        assert_eq!(check.arity_mismatch, Some(1));
+    }
+}
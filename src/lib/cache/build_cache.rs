@@ -0,0 +1,105 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    diagnostics::Diagnostics,
+    parser::{self, ParsingCtx, SourceFile},
+    Config,
+};
+
+use super::{hash, ModuleFingerprint, ParsedModule, ResolvedModule};
+
+pub static CACHE_FILE: &str = "cache.bin";
+pub static CACHED_IR_FILE: &str = "cache.bc";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BuildCache {
+    pub config_hash: u64,
+    pub modules: BTreeMap<String, ModuleFingerprint>,
+    pub parsed: BTreeMap<String, ParsedModule>,
+    pub resolved: BTreeMap<String, ResolvedModule>,
+    pub diagnostics: Diagnostics, // The warnings of the build, printed again while it is up to date
+}
+
+impl BuildCache {
+    pub fn new(config: &Config, parsing_ctx: &mut ParsingCtx) -> Self {
+        let modules = std::mem::take(&mut parsing_ctx.modules);
+
+        Self {
+            config_hash: Self::hash_config(config),
+            modules: modules.graph.modules,
+            parsed: modules.parsed,
+            resolved: modules.resolved,
+            diagnostics: parsing_ctx.diagnostics.clone(),
+        }
+    }
+
+    // Only the options that change the generated code are part of the key, the root
+    // of the project as the paths of the files are relative to it, and the compiler
+    // that built rock as the hashes and the layout of the cache depend on it
+    fn hash_config(config: &Config) -> u64 {
+        hash(&(
+            env!("CARGO_PKG_VERSION"),
+            env!("ROCK_RUSTC_VERSION"),
+            &config.project_config.base_path,
+            config.std,
+            config.opt_level,
+            &config.passes,
+            &config.emit,
+            config.crate_type,
+            &config.target,
+            config.debug_info,
+            &config.lints,
+            config.test,
+        ))
+    }
+
+    // Nothing can be reused from a build made with other options
+    pub fn load(config: &Config) -> Option<Self> {
+        let bytes = fs::read(config.build_folder.join(CACHE_FILE)).ok()?;
+
+        let cache: Self = bincode::deserialize(&bytes).ok()?;
+
+        (cache.config_hash == Self::hash_config(config)).then_some(cache)
+    }
+
+    pub fn save(&self, build_folder: &Path) {
+        if let Ok(bytes) = bincode::serialize(self) {
+            if fs::write(build_folder.join(CACHE_FILE), bytes).is_err() {
+                warn!("Cannot write the build cache");
+            }
+        }
+    }
+
+    // The files of the build when none of them has changed and its outputs are still there,
+    // `input` being the entry file
+    pub fn up_to_date_files(
+        &self,
+        input: &SourceFile,
+        config: &Config,
+    ) -> Option<HashMap<PathBuf, SourceFile>> {
+        let outputs_exist = config.build_folder.join("out.bc").exists()
+            && config.build_folder.join(CACHED_IR_FILE).exists()
+            && config.emitted_files().iter().all(|file| file.exists());
+
+        if !outputs_exist || self.modules.is_empty() {
+            return None;
+        }
+
+        self.modules
+            .iter()
+            .map(|(name, module)| {
+                let file = if name == "root" {
+                    input.clone()
+                } else {
+                    parser::load_mod_file(module.file_path.to_str()?, config).ok()?
+                };
+
+                (hash(&file.content) == module.source_hash).then(|| (file.file_path.clone(), file))
+            })
+            .collect()
+    }
+}
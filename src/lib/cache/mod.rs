@@ -0,0 +1,12 @@
+mod build_cache;
+mod module_cache;
+mod module_graph;
+mod stable_hasher;
+
+#[cfg(test)]
+mod tests;
+
+pub use build_cache::*;
+pub use module_cache::*;
+pub use module_graph::*;
+pub use stable_hasher::*;
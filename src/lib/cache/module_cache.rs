@@ -0,0 +1,92 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use super::ModuleGraph;
+
+use crate::{
+    ast::{tree::Mod, NodeId},
+    diagnostics::{Diagnostics, LintLevels},
+    parser::span::Span,
+};
+
+// Each module numbers its nodes from its own base, so that a module keeps the same node ids
+// as long as its source does not change, whatever happens to the other ones. The root module
+// starts at 0.
+pub static ID_BASE_SHIFT: u32 = 32;
+static ID_SLOTS: u64 = 1 << 24;
+
+pub fn id_base(mod_path: &str, taken: &BTreeMap<NodeId, String>) -> NodeId {
+    let mut slot = super::hash(mod_path) % ID_SLOTS + 1;
+
+    while matches!(taken.get(&(slot << ID_BASE_SHIFT)), Some(other) if other != mod_path) {
+        slot += 1;
+    }
+
+    slot << ID_BASE_SHIFT
+}
+
+// What the parser gave for a module, with the modules it declares as they are part of its tree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParsedModule {
+    pub file_path: PathBuf,
+    pub source_hash: u64,
+    pub id_base: NodeId,
+    pub submodules: Vec<String>, // Every module declared under this one
+    pub ast: Mod,
+    pub identities: BTreeMap<NodeId, Span>,
+    pub lint_scopes: Vec<(Span, LintLevels)>,
+    pub operators: HashMap<String, u8>,
+    pub diagnostics: Diagnostics,
+}
+
+// The resolutions of the identifiers of a module, and the diagnostics of its items
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResolvedModule {
+    pub fingerprint: u64,
+    pub resolutions: Vec<(NodeId, NodeId)>,
+    pub diagnostics: Diagnostics,
+}
+
+// The modules of the previous build that can be reused, and the ones of this build
+#[derive(Debug, Clone, Default)]
+pub struct ModuleCache {
+    pub previous_parsed: Arc<BTreeMap<String, ParsedModule>>,
+    pub previous_resolved: BTreeMap<String, ResolvedModule>,
+    pub graph: ModuleGraph,
+    pub parsed: BTreeMap<String, ParsedModule>,
+    pub resolved: BTreeMap<String, ResolvedModule>,
+    pub reused_parses: BTreeSet<String>,
+    pub reused_resolutions: BTreeSet<String>,
+}
+
+impl ModuleCache {
+    pub fn new(
+        previous_parsed: BTreeMap<String, ParsedModule>,
+        previous_resolved: BTreeMap<String, ResolvedModule>,
+    ) -> Self {
+        Self {
+            previous_parsed: Arc::new(previous_parsed),
+            previous_resolved,
+            ..Default::default()
+        }
+    }
+
+    // The modules whose resolutions are still valid, as neither them nor their
+    // dependencies have changed
+    pub fn reusable_resolutions(&self, graph: &ModuleGraph) -> BTreeSet<String> {
+        graph
+            .modules
+            .iter()
+            .filter(|(name, module)| {
+                self.previous_resolved
+                    .get(*name)
+                    .map(|resolved| resolved.fingerprint == module.resolution_fingerprint)
+                    .unwrap_or(false)
+            })
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+}
@@ -0,0 +1,339 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fmt::Debug,
+    hash::{Hash, Hasher},
+    mem::discriminant,
+    path::PathBuf,
+};
+
+use crate::{
+    ast::{tree::*, visit::*, NodeId},
+    parser::ParsingCtx,
+    ty::Type,
+};
+
+use super::{hash, StableHasher};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleFingerprint {
+    pub file_path: PathBuf,
+    pub source_hash: u64,
+    pub id_base: NodeId,
+    pub signature_hash: u64,
+    pub deps: BTreeSet<String>,
+    pub fingerprint: u64, // The functions can be reused while it does not change
+    pub resolution_fingerprint: u64, // The resolutions can be reused while it does not change
+}
+
+// The module tree and the dependencies of each module, taken from the parsed tree
+#[derive(Debug, Clone, Default)]
+pub struct ModuleGraph {
+    pub modules: BTreeMap<String, ModuleFingerprint>,
+}
+
+impl ModuleGraph {
+    pub fn new(root: &Mod, parsing_ctx: &ParsingCtx) -> Self {
+        let mut graph = Self::default();
+
+        let root_file = parsing_ctx.get_current_file();
+
+        graph.visit("root", root, root_file.file_path, 0, parsing_ctx);
+        graph.compute_fingerprints();
+
+        graph
+    }
+
+    fn visit(
+        &mut self,
+        name: &str,
+        m: &Mod,
+        file_path: PathBuf,
+        id_base: NodeId,
+        parsing_ctx: &ParsingCtx,
+    ) {
+        let content = match parsing_ctx.files.get(&file_path) {
+            Some(file) => file.content.clone(),
+            None => return,
+        };
+
+        let mut deps = BTreeSet::new();
+        let mut paths = PathCollector::default();
+        let mut signature = SignatureHasher::default();
+
+        for top in &m.top_levels {
+            match top {
+                TopLevel::Mod(child_name, child) => {
+                    let child_mod = format!("{}::{}", name, child_name.name);
+
+                    // Not found, an error has already been reported
+                    if let Some(parsed) = parsing_ctx.modules.parsed.get(&child_mod) {
+                        self.visit(
+                            &child_mod,
+                            child,
+                            parsed.file_path.clone(),
+                            parsed.id_base,
+                            parsing_ctx,
+                        );
+                    }
+
+                    deps.insert(child_mod);
+                }
+                TopLevel::Use(u) => paths.visit_identifier_path(&u.path),
+                top => paths.visit_top_level(top),
+            }
+
+            signature.visit_top_level(top);
+        }
+
+        for path in paths.paths {
+            deps.extend(Self::use_path_to_modules(name, &path));
+        }
+
+        self.modules.insert(
+            name.to_string(),
+            ModuleFingerprint {
+                file_path,
+                source_hash: hash(&content),
+                id_base,
+                signature_hash: signature.hasher.finish(),
+                deps,
+                fingerprint: 0,
+                resolution_fingerprint: 0,
+            },
+        );
+    }
+
+    // `use a::b::c` might either import the item `c` from `a::b` or the module itself,
+    // so both are recorded and the ones that do not exist are ignored afterward.
+    fn use_path_to_modules(current: &str, path: &str) -> Vec<String> {
+        let segments = path
+            .split("::")
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+
+        let mut absolute = if segments.first() == Some(&"root") {
+            vec![]
+        } else {
+            current.split("::").collect::<Vec<_>>()
+        };
+
+        for segment in segments {
+            if segment == "super" {
+                absolute.pop();
+            } else {
+                absolute.push(segment);
+            }
+        }
+
+        let absolute = absolute.join("::");
+
+        let mut res = vec![absolute.clone()];
+
+        if let Some((parent, _)) = absolute.rsplit_once("::") {
+            res.push(parent.to_string());
+        }
+
+        res
+    }
+
+    pub fn transitive_deps(&self, name: &str) -> BTreeSet<String> {
+        let mut visited = BTreeSet::new();
+        let mut stack = vec![name.to_string()];
+
+        while let Some(current) = stack.pop() {
+            if let Some(module) = self.modules.get(&current) {
+                for dep in &module.deps {
+                    if self.modules.contains_key(dep) && visited.insert(dep.clone()) {
+                        stack.push(dep.clone());
+                    }
+                }
+            }
+        }
+
+        visited.remove(name);
+
+        visited
+    }
+
+    // The functions of a module only depend on the signatures of the other modules,
+    // but its resolutions point to their node ids, which move with any change.
+    fn compute_fingerprints(&mut self) {
+        let fingerprints = self
+            .modules
+            .iter()
+            .map(|(name, module)| {
+                let mut hasher = StableHasher::new();
+                let mut resolution_hasher = StableHasher::new();
+
+                module.source_hash.hash(&mut hasher);
+                (module.source_hash, module.id_base).hash(&mut resolution_hasher);
+
+                for dep in self.transitive_deps(name) {
+                    let dep_module = &self.modules[&dep];
+
+                    dep.hash(&mut hasher);
+                    dep_module.signature_hash.hash(&mut hasher);
+
+                    dep.hash(&mut resolution_hasher);
+                    (dep_module.source_hash, dep_module.id_base).hash(&mut resolution_hasher);
+                }
+
+                (name.clone(), hasher.finish(), resolution_hasher.finish())
+            })
+            .collect::<Vec<_>>();
+
+        for (name, fingerprint, resolution_fingerprint) in fingerprints {
+            let module = self.modules.get_mut(&name).unwrap();
+
+            module.fingerprint = fingerprint;
+            module.resolution_fingerprint = resolution_fingerprint;
+        }
+    }
+
+    // The files of the modules whose functions can be reused from the previous build
+    pub fn unchanged_files(
+        &self,
+        previous: &BTreeMap<String, ModuleFingerprint>,
+    ) -> HashSet<PathBuf> {
+        self.modules
+            .iter()
+            .filter(|(name, module)| {
+                previous
+                    .get(*name)
+                    .map(|prev| prev.fingerprint == module.fingerprint)
+                    .unwrap_or(false)
+            })
+            .map(|(_, module)| module.file_path.clone())
+            .collect()
+    }
+}
+
+// The paths to other modules in the items of a module, like `a::b::f`
+#[derive(Default)]
+struct PathCollector {
+    paths: Vec<String>,
+}
+
+impl<'a> Visitor<'a> for PathCollector {
+    fn visit_identifier_path(&mut self, path: &'a IdentifierPath) {
+        if path.path.len() > 1 {
+            self.paths.push(
+                path.path
+                    .iter()
+                    .map(|ident| ident.name.clone())
+                    .collect::<Vec<_>>()
+                    .join("::"),
+            );
+        }
+    }
+}
+
+// The declarations of a module that the other modules depend on, from the tree so that their
+// layout does not matter, and without the node ids that move with any change above them.
+// The bodies of the functions are left out, but not the methods of the implementations
+// and the traits, nor the values of the constants.
+#[derive(Default)]
+struct SignatureHasher {
+    hasher: StableHasher,
+}
+
+impl SignatureHasher {
+    fn add<T: Hash + ?Sized>(&mut self, t: &T) {
+        t.hash(&mut self.hasher);
+    }
+}
+
+impl<'a> Visitor<'a> for SignatureHasher {
+    fn visit_name(&mut self, name: &str) {
+        self.add(name);
+    }
+
+    fn visit_primitive<T>(&mut self, val: T)
+    where
+        T: Debug,
+    {
+        self.add(&format!("{:?}", val));
+    }
+
+    fn visit_top_level(&mut self, top: &'a TopLevel) {
+        self.add(&discriminant(top));
+
+        match top {
+            TopLevel::Function(f) => {
+                self.visit_identifier(&f.name);
+
+                walk_list!(self, visit_identifier, &f.arguments);
+
+                self.visit_func_type(&f.signature);
+            }
+            TopLevel::Clauses(c) => {
+                self.visit_identifier(&c.name);
+                self.visit_func_type(&c.signature);
+            }
+            TopLevel::Test(_) => (),
+            TopLevel::Trait(t) => {
+                walk_trait(self, t);
+
+                walk_list!(self, visit_function_decl, &t.default_impl);
+            }
+            TopLevel::Infix(op, precedence) => {
+                self.visit_operator(op);
+                self.add(precedence);
+            }
+            // A module of its own
+            TopLevel::Mod(name, _) => self.visit_identifier(name),
+            _ => walk_top_level(self, top),
+        }
+    }
+
+    fn visit_statement(&mut self, statement: &'a Statement) {
+        self.add(&discriminant(statement));
+
+        walk_statement(self, statement);
+    }
+
+    fn visit_else(&mut self, r#else: &'a Else) {
+        self.add(&discriminant(r#else));
+
+        walk_else(self, r#else);
+    }
+
+    fn visit_expression(&mut self, expr: &'a Expression) {
+        self.add(&discriminant(expr));
+
+        walk_expression(self, expr);
+    }
+
+    fn visit_unary_expr(&mut self, unary: &'a UnaryExpr) {
+        self.add(&discriminant(unary));
+
+        walk_unary_expr(self, unary);
+    }
+
+    fn visit_secondary_expr(&mut self, secondary: &'a SecondaryExpr) {
+        self.add(&discriminant(secondary));
+
+        walk_secondary_expr(self, secondary);
+    }
+
+    fn visit_operand(&mut self, operand: &'a Operand) {
+        self.add(&discriminant(operand));
+
+        walk_operand(self, operand);
+    }
+
+    fn visit_literal(&mut self, literal: &'a Literal) {
+        self.add(&discriminant(&literal.kind));
+
+        walk_literal(self, literal);
+    }
+
+    fn visit_native_operator(&mut self, operator: &'a NativeOperator) {
+        self.add(&discriminant(&operator.kind));
+    }
+
+    // The `Hash` of the types only tells their kind
+    fn visit_type(&mut self, t: &'a Type) {
+        self.add(&bincode::serialize(t).unwrap_or_default());
+    }
+}
@@ -0,0 +1,97 @@
+use std::hash::{Hash, Hasher};
+
+// The key of the reference implementation, so the hashes can be checked against its vectors
+const KEY: (u64, u64) = (0x0706_0504_0302_0100, 0x0f0e_0d0c_0b0a_0908);
+
+// SipHash-2-4 with a fixed key. The hashes are saved in the build cache, and the `DefaultHasher`
+// of the standard library is allowed to change from one release to another.
+#[derive(Debug, Clone)]
+pub struct StableHasher {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+    tail: u64, // The bytes that do not fill a word yet
+    tail_len: usize,
+    length: usize,
+}
+
+impl StableHasher {
+    pub fn new() -> Self {
+        Self {
+            v0: KEY.0 ^ 0x736f_6d65_7073_6575,
+            v1: KEY.1 ^ 0x646f_7261_6e64_6f6d,
+            v2: KEY.0 ^ 0x6c79_6765_6e65_7261,
+            v3: KEY.1 ^ 0x7465_6462_7974_6573,
+            tail: 0,
+            tail_len: 0,
+            length: 0,
+        }
+    }
+
+    fn round(&mut self) {
+        self.v0 = self.v0.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(13) ^ self.v0;
+        self.v0 = self.v0.rotate_left(32);
+        self.v2 = self.v2.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(16) ^ self.v2;
+        self.v0 = self.v0.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(21) ^ self.v0;
+        self.v2 = self.v2.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(17) ^ self.v2;
+        self.v2 = self.v2.rotate_left(32);
+    }
+
+    fn compress(&mut self, word: u64) {
+        self.v3 ^= word;
+        self.round();
+        self.round();
+        self.v0 ^= word;
+    }
+}
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for StableHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.tail |= (*byte as u64) << (8 * self.tail_len);
+            self.tail_len += 1;
+
+            if self.tail_len == 8 {
+                self.compress(self.tail);
+
+                self.tail = 0;
+                self.tail_len = 0;
+            }
+        }
+
+        self.length += bytes.len();
+    }
+
+    fn finish(&self) -> u64 {
+        let mut state = self.clone();
+
+        state.compress(((self.length as u64) << 56) | self.tail);
+
+        state.v2 ^= 0xff;
+
+        for _ in 0..4 {
+            state.round();
+        }
+
+        state.v0 ^ state.v1 ^ state.v2 ^ state.v3
+    }
+}
+
+pub fn hash<T: Hash + ?Sized>(t: &T) -> u64 {
+    let mut hasher = StableHasher::new();
+
+    t.hash(&mut hasher);
+
+    hasher.finish()
+}
@@ -0,0 +1,451 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{BuildCache, ModuleCache};
+use crate::{
+    parser::{ParsingCtx, SourceFile},
+    Config,
+};
+
+static MAIN: &str = "mod a
+mod c
+mod b
+
+use b::twice
+
+main: -> twice 2
+";
+
+static A: &str = "id_a: x ->
+  x
+
+unused_a: x -> x
+";
+
+// Depends on `a` with a `use`, and on `c` with a path
+static B: &str = "use super::a::id_a
+
+twice: x -> super::c::other (id_a x)
+";
+
+static C: &str = "other: x ->
+  x
+";
+
+fn project(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rock_cache_{}_{}", name, std::process::id()));
+
+    let _ = fs::remove_dir_all(&dir);
+
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::create_dir_all(dir.join("build")).unwrap();
+
+    for (file, content) in [("main", MAIN), ("a", A), ("b", B), ("c", C)] {
+        fs::write(dir.join("src").join(file.to_owned() + ".rk"), content).unwrap();
+    }
+
+    dir
+}
+
+fn config(dir: &Path) -> Config {
+    Config {
+        quiet: true,
+        build_folder: dir.join("build"),
+        ..Config::default()
+    }
+}
+
+fn entry(dir: &Path) -> SourceFile {
+    let mut file =
+        SourceFile::from_file(dir.join("src/main.rk").to_str().unwrap().to_string()).unwrap();
+
+    file.mod_path = PathBuf::from("root");
+
+    file
+}
+
+// Parses and resolves the project, with the modules of the previous build
+fn build(dir: &Path, previous: Option<&BuildCache>) -> ParsingCtx {
+    let config = config(dir);
+
+    let mut parsing_ctx = ParsingCtx::new(&config);
+
+    if let Some(previous) = previous {
+        parsing_ctx.modules = ModuleCache::new(previous.parsed.clone(), previous.resolved.clone());
+    }
+
+    parsing_ctx.add_file(&entry(dir));
+
+    crate::parse_str(&mut parsing_ctx, &config).unwrap();
+
+    parsing_ctx
+}
+
+fn cache(dir: &Path, parsing_ctx: &mut ParsingCtx) -> BuildCache {
+    BuildCache::new(&config(dir), parsing_ctx)
+}
+
+fn set(names: &[&str]) -> BTreeSet<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[cfg(test)]
+mod graph {
+    use super::*;
+
+    #[test]
+    fn modules_from_tree() {
+        let dir = project("graph_modules");
+
+        let modules = build(&dir, None).modules.graph.modules;
+
+        assert_eq!(
+            modules.keys().cloned().collect::<BTreeSet<_>>(),
+            set(&["root", "root::a", "root::b", "root::c"])
+        );
+
+        assert_eq!(modules["root::a"].file_path, dir.join("src/a.rk"));
+    }
+
+    #[test]
+    fn deps() {
+        let dir = project("graph_deps");
+
+        let graph = build(&dir, None).modules.graph;
+
+        assert!(graph.modules["root"].deps.contains("root::b"));
+        assert!(graph.modules["root::b"].deps.contains("root::a"));
+        assert!(graph.modules["root::b"].deps.contains("root::c"));
+        assert_eq!(graph.transitive_deps("root::a"), BTreeSet::new());
+        assert_eq!(
+            graph.transitive_deps("root::b"),
+            set(&["root::a", "root::c"])
+        );
+    }
+
+    // Declarations that do not start a line are part of the tree too
+    #[test]
+    fn indented_use() {
+        let dir = project("graph_indented");
+
+        fs::write(dir.join("src/c.rk"), "other: x ->\n  super::a::id_a x\n").unwrap();
+
+        let graph = build(&dir, None).modules.graph;
+
+        assert!(graph.modules["root::c"].deps.contains("root::a"));
+    }
+}
+
+#[cfg(test)]
+mod fingerprints {
+    use super::*;
+
+    #[test]
+    fn stable() {
+        let dir = project("fingerprints_stable");
+
+        assert_eq!(
+            build(&dir, None).modules.graph.modules,
+            build(&dir, None).modules.graph.modules
+        );
+    }
+
+    // The functions of `b` only depend on the signature of `a`, but its resolutions
+    // point to the nodes of `a`
+    #[test]
+    fn body_change() {
+        let dir = project("fingerprints_body");
+
+        let before = build(&dir, None).modules.graph.modules;
+
+        fs::write(dir.join("src/a.rk"), A.replace("  x", "  (x)")).unwrap();
+
+        let after = build(&dir, None).modules.graph.modules;
+
+        assert_ne!(before["root::a"].fingerprint, after["root::a"].fingerprint);
+        assert_eq!(before["root::b"].fingerprint, after["root::b"].fingerprint);
+        assert_ne!(
+            before["root::b"].resolution_fingerprint,
+            after["root::b"].resolution_fingerprint
+        );
+        assert_eq!(
+            before["root::c"].resolution_fingerprint,
+            after["root::c"].resolution_fingerprint
+        );
+    }
+
+    #[test]
+    fn signature_change() {
+        let dir = project("fingerprints_signature");
+
+        let before = build(&dir, None).modules.graph.modules;
+
+        fs::write(dir.join("src/a.rk"), A.replace("x ->\n  x", "y ->\n  y")).unwrap();
+
+        let after = build(&dir, None).modules.graph.modules;
+
+        assert_ne!(before["root::b"].fingerprint, after["root::b"].fingerprint);
+        assert_eq!(before["root::c"].fingerprint, after["root::c"].fingerprint);
+    }
+
+    // The signatures come from the tree, whatever their layout
+    #[test]
+    fn layout_change() {
+        let dir = project("fingerprints_layout");
+
+        let before = build(&dir, None).modules.graph.modules;
+
+        fs::write(
+            dir.join("src/a.rk"),
+            A.replace("id_a: x ->\n  x", "id_a: x -> x\n\n# Kept\n"),
+        )
+        .unwrap();
+
+        let after = build(&dir, None).modules.graph.modules;
+
+        assert_ne!(before["root::a"].fingerprint, after["root::a"].fingerprint);
+        assert_eq!(before["root::b"].fingerprint, after["root::b"].fingerprint);
+    }
+
+    // The methods span several lines, and their bodies are part of the signature
+    #[test]
+    fn impl_change() {
+        let dir = project("fingerprints_impl");
+
+        let with_impl = |method: &str| {
+            format!(
+                "{}\ntrait Twice\n  twice: @ => @\n\nimpl Twice Int64\n  {}\n",
+                A, method
+            )
+        };
+
+        fs::write(dir.join("src/a.rk"), with_impl("twice: x -> x")).unwrap();
+
+        let before = build(&dir, None).modules.graph.modules;
+
+        fs::write(dir.join("src/a.rk"), with_impl("twice: x -> ~IAdd x x")).unwrap();
+
+        let body = build(&dir, None).modules.graph.modules;
+
+        fs::write(dir.join("src/a.rk"), with_impl("twice: y -> y")).unwrap();
+
+        let arguments = build(&dir, None).modules.graph.modules;
+
+        assert_ne!(before["root::b"].fingerprint, body["root::b"].fingerprint);
+        assert_ne!(
+            before["root::b"].fingerprint,
+            arguments["root::b"].fingerprint
+        );
+        assert_eq!(before["root::c"].fingerprint, body["root::c"].fingerprint);
+    }
+}
+
+#[cfg(test)]
+mod stable_hasher {
+    use std::hash::Hasher;
+
+    use super::super::StableHasher;
+
+    // The first vectors of the reference implementation, that hashes the bytes `0..len`
+    #[test]
+    fn reference_vectors() {
+        let expected: [(usize, u64); 3] = [
+            (0, 0x726f_db47_dd0e_0e31),
+            (1, 0x74f8_39c5_93dc_67fd),
+            (8, 0x93f5_f579_9a93_2462),
+        ];
+
+        for (len, expected) in expected {
+            let mut hasher = StableHasher::new();
+
+            hasher.write(&(0..len as u8).collect::<Vec<_>>());
+
+            assert_eq!(hasher.finish(), expected, "{} bytes", len);
+        }
+    }
+}
+
+#[cfg(test)]
+mod reuse {
+    use super::*;
+
+    #[test]
+    fn unchanged() {
+        let dir = project("reuse_unchanged");
+
+        let mut first = build(&dir, None);
+        let previous = cache(&dir, &mut first);
+
+        let second = build(&dir, Some(&previous));
+
+        assert_eq!(
+            second.modules.reused_parses,
+            set(&["root::a", "root::b", "root::c"])
+        );
+        assert_eq!(
+            second.modules.reused_resolutions,
+            set(&["root", "root::a", "root::b", "root::c"])
+        );
+    }
+
+    // `b` and the root depend on `c`, not `a`
+    #[test]
+    fn changed_module() {
+        let dir = project("reuse_changed");
+
+        let mut first = build(&dir, None);
+        let previous = cache(&dir, &mut first);
+
+        fs::write(dir.join("src/c.rk"), "other: y -> y\n").unwrap();
+
+        let second = build(&dir, Some(&previous));
+
+        assert_eq!(second.modules.reused_parses, set(&["root::a", "root::b"]));
+        assert_eq!(second.modules.reused_resolutions, set(&["root::a"]));
+    }
+
+    #[test]
+    fn same_as_full_build() {
+        let dir = project("reuse_full");
+
+        let mut first = build(&dir, None);
+        let previous = cache(&dir, &mut first);
+
+        fs::write(dir.join("src/c.rk"), "other: y -> y\n").unwrap();
+
+        let mut incremental = build(&dir, Some(&previous));
+        let mut full = build(&dir, None);
+
+        let incremental = cache(&dir, &mut incremental);
+        let full = cache(&dir, &mut full);
+
+        assert_eq!(incremental.modules, full.modules);
+
+        for (name, module) in &full.resolved {
+            assert_eq!(incremental.resolved[name].resolutions, module.resolutions);
+        }
+    }
+
+    // The parsing of a submodule is taken with the one of its parent
+    #[test]
+    fn nested_module() {
+        let dir = project("reuse_nested");
+
+        fs::write(dir.join("src/c.rk"), "mod d\n\nother: x -> d::inner x\n").unwrap();
+        fs::write(dir.join("src/d.rk"), "inner: x -> x\n").unwrap();
+
+        let mut first = build(&dir, None);
+
+        assert_eq!(
+            first.modules.graph.modules["root::c::d"].file_path,
+            dir.join("src/d.rk")
+        );
+
+        let previous = cache(&dir, &mut first);
+
+        fs::write(dir.join("src/d.rk"), "inner: y -> y\n").unwrap();
+
+        let second = build(&dir, Some(&previous));
+
+        assert_eq!(second.modules.reused_parses, set(&["root::a", "root::b"]));
+    }
+}
+
+#[cfg(test)]
+mod functions {
+    use super::*;
+
+    fn generate(dir: &Path, previous: Option<&BuildCache>) -> (usize, BuildCache) {
+        let config = config(dir);
+
+        let mut parsing_ctx = ParsingCtx::new(&config);
+
+        if let Some(previous) = previous {
+            parsing_ctx.modules =
+                ModuleCache::new(previous.parsed.clone(), previous.resolved.clone());
+        }
+
+        parsing_ctx.add_file(&entry(dir));
+
+        let hir = crate::parse_str(&mut parsing_ctx, &config).unwrap();
+
+        let unchanged_files = previous
+            .map(|previous| parsing_ctx.modules.graph.unchanged_files(&previous.modules))
+            .unwrap_or_default();
+
+        let reused = crate::generate_ir(hir, &config, unchanged_files, &mut parsing_ctx).unwrap();
+
+        (reused, cache(dir, &mut parsing_ctx))
+    }
+
+    #[test]
+    fn reused() {
+        let dir = project("functions_reused");
+
+        let (reused, previous) = generate(&dir, None);
+
+        assert_eq!(reused, 0);
+
+        fs::write(dir.join("src/c.rk"), C.replace("  x", "  (x)")).unwrap();
+
+        let (reused, _) = generate(&dir, Some(&previous));
+
+        // Every function but `other`, as the other modules only depend on the signature of `c`
+        assert_eq!(reused, 3);
+    }
+}
+
+#[cfg(test)]
+mod build_cache {
+    use super::*;
+
+    #[test]
+    fn up_to_date() {
+        let dir = project("up_to_date");
+        let config = config(&dir);
+
+        crate::compile_str(&entry(&dir), &config).unwrap();
+
+        let cache = BuildCache::load(&config).unwrap();
+
+        assert!(cache.up_to_date_files(&entry(&dir), &config).is_some());
+
+        fs::write(dir.join("src/c.rk"), "other: y -> y\n").unwrap();
+
+        assert!(cache.up_to_date_files(&entry(&dir), &config).is_none());
+    }
+
+    // They are printed again by the builds that have nothing to do
+    #[test]
+    fn keeps_warnings() {
+        let dir = project("warnings");
+        let config = config(&dir);
+
+        crate::compile_str(&entry(&dir), &config).unwrap();
+
+        let cache = BuildCache::load(&config).unwrap();
+
+        assert_eq!(cache.diagnostics.list.len(), 1);
+        assert_eq!(
+            cache.diagnostics.list[0].span.file_path,
+            dir.join("src/a.rk")
+        );
+    }
+
+    #[test]
+    fn other_config() {
+        let dir = project("other_config");
+
+        crate::compile_str(&entry(&dir), &config(&dir)).unwrap();
+
+        let config = Config {
+            debug_info: true,
+            ..config(&dir)
+        };
+
+        assert!(BuildCache::load(&config).is_none());
+    }
+}
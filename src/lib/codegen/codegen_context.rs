@@ -1,4 +1,8 @@
-use std::convert::{TryFrom, TryInto};
+use std::{
+    collections::HashSet,
+    convert::{TryFrom, TryInto},
+    path::PathBuf,
+};
 
 use itertools::Itertools;
 
@@ -7,17 +11,18 @@ use inkwell::{
     builder::Builder,
     context::Context,
     module::Module,
-    passes::{PassManager, PassManagerBuilder},
-    targets::{InitializationConfig, Target},
     types::{BasicType, BasicTypeEnum},
     values::{BasicValue, BasicValueEnum, CallableValue, FunctionValue},
     AddressSpace, FloatPredicate, IntPredicate,
-    OptimizationLevel::Aggressive,
 };
 
+use super::debug_info::DebugInfo;
+
 use crate::{
+    diagnostics::{Diagnostic, Diagnostics},
     helpers::scopes::Scopes,
     hir::*,
+    parser::span::Span,
     ty::{PrimitiveType, Type},
 };
 
@@ -27,6 +32,12 @@ pub struct CodegenContext<'a> {
     pub module: Module<'a>,
     pub scopes: Scopes<HirId, BasicValueEnum<'a>>,
     pub cur_func: Option<FunctionValue<'a>>,
+    pub cached_module: Option<Module<'a>>,
+    pub unchanged_files: HashSet<PathBuf>,
+    pub reused: HashSet<String>,
+    pub runtime_consts: Vec<&'a Const>, // The ones that are initialized by the C `main`
+    pub debug_info: Option<DebugInfo<'a>>,
+    pub diagnostics: Diagnostics,
 }
 
 impl<'a> CodegenContext<'a> {
@@ -39,66 +50,45 @@ impl<'a> CodegenContext<'a> {
             hir,
             scopes: Scopes::new(),
             cur_func: None,
+            cached_module: None,
+            unchanged_files: HashSet::new(),
+            reused: HashSet::new(),
+            runtime_consts: vec![],
+            debug_info: None,
+            diagnostics: Diagnostics::default(),
         }
     }
 
-    pub fn optimize(&mut self) {
-        let config = InitializationConfig::default();
-
-        Target::initialize_native(&config).unwrap();
-
-        let pass_manager_builder = PassManagerBuilder::create();
-
-        pass_manager_builder.set_optimization_level(Aggressive);
-
-        let pass_manager = PassManager::create(());
-
-        pass_manager.add_promote_memory_to_register_pass();
-        // pass_manager.add_demote_memory_to_register_pass();
-        pass_manager.add_argument_promotion_pass();
-        pass_manager.add_always_inliner_pass();
-        pass_manager.add_gvn_pass();
-        pass_manager.add_new_gvn_pass();
-        pass_manager.add_function_attrs_pass();
-        pass_manager.add_prune_eh_pass();
-        pass_manager.add_constant_merge_pass();
-        pass_manager.add_scalarizer_pass();
-        pass_manager.add_merged_load_store_motion_pass();
-        pass_manager.add_instruction_combining_pass();
-        pass_manager.add_memcpy_optimize_pass();
-        pass_manager.add_partially_inline_lib_calls_pass();
-        pass_manager.add_lower_switch_pass();
-        pass_manager.add_reassociate_pass();
-        pass_manager.add_simplify_lib_calls_pass();
-        pass_manager.add_aggressive_inst_combiner_pass();
-        pass_manager.add_instruction_simplify_pass();
-        pass_manager.add_function_inlining_pass();
-        pass_manager.add_global_optimizer_pass();
-        pass_manager.add_dead_arg_elimination_pass();
-        pass_manager.add_strip_symbol_pass();
-        pass_manager.add_strip_dead_prototypes_pass();
-        pass_manager.add_internalize_pass(true);
-        pass_manager.add_sccp_pass();
-        pass_manager.add_aggressive_dce_pass();
-        pass_manager.add_global_dce_pass();
-        pass_manager.add_tail_call_elimination_pass();
-        pass_manager.add_basic_alias_analysis_pass();
-        pass_manager.add_licm_pass();
-        pass_manager.add_ind_var_simplify_pass();
-        pass_manager.add_loop_vectorize_pass();
-        pass_manager.add_loop_unswitch_pass();
-        pass_manager.add_loop_idiom_pass();
-        pass_manager.add_loop_rotate_pass();
-        pass_manager.add_loop_unroll_and_jam_pass();
-        pass_manager.add_loop_unroll_pass();
-        pass_manager.add_loop_deletion_pass();
-        pass_manager.add_cfg_simplification_pass();
-
-        pass_manager.add_verifier_pass();
-
-        pass_manager.run_on(&self.module);
-
-        pass_manager_builder.populate_module_pass_manager(&pass_manager);
+    // Reports an error on the span of `hir_id`, and fails the generation
+    pub fn codegen_error<T>(&mut self, hir_id: &HirId, msg: &str) -> Result<T, ()> {
+        let span = self
+            .hir
+            .get_hir_spans()
+            .get(hir_id)
+            .cloned()
+            .unwrap_or_else(Span::new_placeholder);
+
+        self.diagnostics
+            .push_error(Diagnostic::new_codegen_error(span, hir_id.clone(), msg));
+
+        Err(())
+    }
+
+    // Lowers the type of a node, that must be fully known at this point
+    pub fn lower_type_of(
+        &mut self,
+        hir_id: &HirId,
+        builder: &'a Builder,
+    ) -> Result<BasicTypeEnum<'a>, ()> {
+        let t = match self.hir.node_types.get(hir_id) {
+            Some(t) => t,
+            None => return self.codegen_error(hir_id, "Unknown type"),
+        };
+
+        match self.lower_type(t, builder) {
+            Ok(llvm_t) => Ok(llvm_t),
+            Err(_) => self.codegen_error(hir_id, &format!("Cannot lower type {}", t)),
+        }
     }
 
     pub fn lower_type(&mut self, t: &Type, builder: &'a Builder) -> Result<BasicTypeEnum<'a>, ()> {
@@ -114,9 +104,15 @@ impl<'a> CodegenContext<'a> {
                 .ptr_type(AddressSpace::Generic)
                 .into(),
             Type::Primitive(PrimitiveType::Array(inner, size)) => {
+                let size = if *size == PrimitiveType::RUNTIME_SIZE {
+                    0
+                } else {
+                    *size as u32
+                };
+
                 // assuming all types are equals
                 self.lower_type(inner, builder)?
-                    .array_type(*size as u32)
+                    .array_type(size)
                     .ptr_type(AddressSpace::Generic)
                     .into()
             }
@@ -146,19 +142,21 @@ impl<'a> CodegenContext<'a> {
 
                 fn_type.ptr_type(AddressSpace::Generic).into()
             }
-            Type::Struct(s) => self
-                .context
-                .struct_type(
-                    s.ordered_defs()
-                        .iter()
-                        .map(|(_k, b)| self.lower_type(&(*b), builder).unwrap())
-                        .collect::<Vec<_>>()
-                        .as_slice(),
-                    false,
-                )
-                .ptr_type(AddressSpace::Generic)
-                .into(),
-            _ => unimplemented!("Codegen: Cannot lower type {:#?}", t),
+            Type::Struct(s) => {
+                let mut fields = vec![];
+
+                for (_k, b) in s.ordered_defs() {
+                    fields.push(self.lower_type(&b, builder)?);
+                }
+
+                self.context
+                    .struct_type(fields.as_slice(), false)
+                    .ptr_type(AddressSpace::Generic)
+                    .into()
+            }
+            Type::Newtype(_, inner) => self.lower_type(inner, builder)?,
+            // Unresolved types, reported by the caller
+            _ => return Err(()),
         })
     }
 
@@ -168,10 +166,21 @@ impl<'a> CodegenContext<'a> {
                 TopLevelKind::Extern(p) => self.lower_prototype(p, builder)?,
                 TopLevelKind::Signature(_p) => (),
                 TopLevelKind::Function(f) => self.lower_function_decl(f, builder)?,
+                TopLevelKind::Const(_) | TopLevelKind::Newtype(_) => (),
             }
         }
 
+        self.lower_consts(root, builder)?;
+
+        self.reused = self.find_reusable_functions(root);
+
         for body in root.bodies.values() {
+            let f = root.get_function_by_hir_id(&body.fn_id).unwrap();
+
+            if self.reused.contains(&f.get_name().name) {
+                continue;
+            }
+
             self.lower_fn_body(body, builder)?;
         }
 
@@ -187,14 +196,24 @@ impl<'a> CodegenContext<'a> {
             let mut args = vec![];
 
             for arg in &p.signature.arguments {
-                args.push(self.lower_type(arg, builder)?.into());
+                match self.lower_type(arg, builder) {
+                    Ok(arg) => args.push(arg.into()),
+                    Err(_) => {
+                        return self.codegen_error(&p.hir_id, &format!("Cannot lower type {}", arg))
+                    }
+                }
             }
 
             let fn_type = if let Type::Primitive(PrimitiveType::Void) = *ret_t {
                 self.context.void_type().fn_type(args.as_slice(), false)
             } else {
-                self.lower_type(&ret_t, builder)?
-                    .fn_type(args.as_slice(), false)
+                match self.lower_type(&ret_t, builder) {
+                    Ok(ret) => ret.fn_type(args.as_slice(), false),
+                    Err(_) => {
+                        return self
+                            .codegen_error(&p.hir_id, &format!("Cannot lower type {}", ret_t))
+                    }
+                }
             };
 
             let fn_value = self.module.add_function(&p.name.name, fn_type, None);
@@ -222,36 +241,32 @@ impl<'a> CodegenContext<'a> {
         if self.module.get_function(&mangled_name).is_some() {
             return Ok(());
         }
-        // Check if any argument is not solved
-        if f.arguments
-            .iter()
-            .any(|arg| self.hir.node_types.get(&arg.name.hir_id).is_none())
-        {
-            panic!("SOME ARGUMENTS ARE NOT SOLVED");
-        }
-
-        let t = self.hir.node_types.get(&f.hir_id).unwrap();
+        let t = match self.hir.node_types.get(&f.hir_id) {
+            Some(t) => t,
+            None => return self.codegen_error(&f.name.hir_id, "Unknown type"),
+        };
 
         if let Type::Func(f_type) = t {
             let ret_t = f_type.ret.clone();
 
-            let args = f
-                .arguments
-                .iter()
-                .map(|arg| self.lower_argument_decl(arg, builder))
-                .collect::<Vec<_>>();
+            let mut args = vec![];
 
-            let mut args_ret = vec![];
-            for arg in args {
-                args_ret.push(arg?.into());
+            for arg in &f.arguments {
+                args.push(self.lower_argument_decl(arg, builder)?.into());
             }
-            let args = args_ret;
 
             let fn_type = if let Type::Primitive(PrimitiveType::Void) = *ret_t {
                 self.context.void_type().fn_type(args.as_slice(), false)
             } else {
-                self.lower_type(&ret_t, builder)?
-                    .fn_type(args.as_slice(), false)
+                match self.lower_type(&ret_t, builder) {
+                    Ok(ret) => ret.fn_type(args.as_slice(), false),
+                    Err(_) => {
+                        return self.codegen_error(
+                            &f.name.hir_id,
+                            &format!("Unknown return type of {}", f.name.name),
+                        )
+                    }
+                }
             };
 
             let fn_value = self.module.add_function(&mangled_name, fn_type, None);
@@ -272,7 +287,7 @@ impl<'a> CodegenContext<'a> {
                     .as_basic_value_enum(),
             );
         } else {
-            panic!("Not a function");
+            return self.codegen_error(&f.name.hir_id, &format!("Not a function: {}", t));
         }
 
         Ok(())
@@ -283,9 +298,7 @@ impl<'a> CodegenContext<'a> {
         arg: &ArgumentDecl,
         builder: &'a Builder,
     ) -> Result<BasicTypeEnum<'a>, ()> {
-        let t = self.hir.node_types.get(&arg.name.hir_id).unwrap();
-
-        self.lower_type(t, builder)
+        self.lower_type_of(&arg.name.hir_id, builder)
     }
 
     pub fn lower_fn_body(&mut self, fn_body: &'a FnBody, builder: &'a Builder) -> Result<(), ()> {
@@ -294,6 +307,8 @@ impl<'a> CodegenContext<'a> {
         if let Some(f) = self.module.get_function(&top_f.get_name()) {
             self.cur_func = Some(f);
 
+            self.debug_function(top_f, f, builder);
+
             let f_decl = self.hir.get_function_by_hir_id(&fn_body.fn_id).unwrap();
 
             for (i, arg) in f_decl.arguments.iter().enumerate() {
@@ -307,6 +322,10 @@ impl<'a> CodegenContext<'a> {
             let (last, _) = self.lower_body(&fn_body.body, "entry", builder)?;
 
             builder.build_return(Some(&last));
+
+            self.debug_arguments(f_decl, f, builder);
+
+            builder.unset_current_debug_location();
         } else {
             panic!("Cannot find function {:?}", top_f.get_name());
         }
@@ -337,7 +356,11 @@ impl<'a> CodegenContext<'a> {
         // FIXME: Add warning here for unreachable statements
 
         let stmt = stmts
-            .map(|stmt| self.lower_stmt(stmt, builder))
+            .map(|stmt| {
+                self.debug_location(&stmt.get_terminal_hir_id(), builder);
+
+                self.lower_stmt(stmt, builder)
+            })
             .last()
             .unwrap()?;
 
@@ -427,6 +450,13 @@ impl<'a> CodegenContext<'a> {
             AssignLeftSide::Identifier(id) => {
                 let value = self.lower_expression(&assign.value, builder)?;
 
+                let is_new = self
+                    .hir
+                    .resolutions
+                    .get(&id.get_hir_id())
+                    .and_then(|reso| self.scopes.get(reso))
+                    .is_none();
+
                 // FIXME: This is twisted
                 let val = self
                     .hir
@@ -453,6 +483,12 @@ impl<'a> CodegenContext<'a> {
                     .or(Some(value))
                     .unwrap();
 
+                if is_new && self.debug_info.is_some() {
+                    let storage = (val != value).then(|| val.into_pointer_value());
+
+                    self.debug_local(id, value, storage, builder);
+                }
+
                 self.scopes.add(id.get_hir_id(), val);
 
                 val
@@ -518,8 +554,15 @@ impl<'a> CodegenContext<'a> {
             ifs.chain(vec![])
         };
 
-        for (((value_a, block_a), (value_b, block_b)), if_) in
-            value_blocks.iter().circular_tuple_windows().zip(ifs)
+        // The blocks are told apart by their index, as the values of two branches can be
+        // the same constant
+        let last = value_blocks.len() - 1;
+
+        for (i, (((value_a, block_a), (_value_b, block_b)), if_)) in value_blocks
+            .iter()
+            .circular_tuple_windows()
+            .zip(ifs)
+            .enumerate()
         {
             let if_block = block_a.get_previous_basic_block().unwrap();
 
@@ -528,10 +571,10 @@ impl<'a> CodegenContext<'a> {
             if let Some(if_) = if_ {
                 let predicat = self.lower_expression(&if_.predicat, builder)?;
 
-                let if_block_b = if value_b == first_value {
+                let if_block_b = if i == last {
                     block_a.clone()
                 } else {
-                    if if_chain.else_body.is_some() && *value_b == value_blocks.last().unwrap().0 {
+                    if if_chain.else_body.is_some() && i + 1 == last {
                         block_b.clone()
                     } else {
                         block_b.get_previous_basic_block().unwrap()
@@ -594,6 +637,7 @@ impl<'a> CodegenContext<'a> {
 
                 val
             }
+            ExpressionKind::Ascription(a) => self.lower_expression(&a.expr, builder)?,
         })
     }
 
@@ -605,23 +649,23 @@ impl<'a> CodegenContext<'a> {
         let t = self.hir.node_types.get(&s.get_hir_id()).unwrap();
         let struct_t = t.as_struct_type();
 
-        let llvm_struct_t_ptr = self.lower_type(t, builder).unwrap().into_pointer_type();
+        let llvm_struct_t_ptr = self
+            .lower_type_of(&s.get_hir_id(), builder)?
+            .into_pointer_type();
         let llvm_struct_t = llvm_struct_t_ptr.get_element_type().into_struct_type();
 
-        let defs = struct_t
-            .ordered_defs()
-            .iter()
-            .map(|(k, _b)| {
-                let def = s
-                    .defs
-                    .iter()
-                    .find(|(k2, _b2)| k2.name == *k)
-                    .map(|(_k2, b2)| b2)
-                    .unwrap();
+        let mut defs = vec![];
 
-                self.lower_expression(def, builder).unwrap()
-            })
-            .collect::<Vec<_>>();
+        for (k, _b) in struct_t.ordered_defs() {
+            let def = match s.defs.iter().find(|(k2, _b2)| k2.name == k) {
+                Some((_k2, def)) => def,
+                None => {
+                    return self.codegen_error(&s.get_hir_id(), &format!("Missing field {}", k))
+                }
+            };
+
+            defs.push(self.lower_expression(def, builder)?);
+        }
 
         let ptr = builder.build_alloca(llvm_struct_t, "struct_ptr");
 
@@ -648,7 +692,11 @@ impl<'a> CodegenContext<'a> {
         let callable_value = match self.hir.get_top_level(f_id) {
             Some(top) => CallableValue::try_from(match &top.kind {
                 TopLevelKind::Extern(p) => self.module.get_function(&p.name.to_string()).unwrap(),
-                TopLevelKind::Signature(_p) => unimplemented!(),
+                TopLevelKind::Signature(_) | TopLevelKind::Const(_) => {
+                    return self.codegen_error(&terminal_hir_id, "Not a function")
+                }
+                // The newtypes have the representation of the type that they wrap
+                TopLevelKind::Newtype(_) => return self.lower_expression(&fc.args[0], builder),
                 TopLevelKind::Function(f) => {
                     self.module.get_function(&f.get_name().to_string()).unwrap()
                 }
@@ -754,6 +802,11 @@ impl<'a> CodegenContext<'a> {
         dot: &'a Dot,
         builder: &'a Builder,
     ) -> Result<BasicValueEnum<'a>, ()> {
+        // `.0` is the value that the newtype wraps, which is the newtype itself
+        if let Some(Type::Newtype(_, _)) = self.hir.node_types.get(&dot.op.get_hir_id()) {
+            return self.lower_expression(&dot.op, builder);
+        }
+
         let ptr = self.lower_dot_ptr(dot, builder)?.into_pointer_value();
 
         Ok(builder.build_load(ptr, "load_dot"))
@@ -793,16 +846,15 @@ impl<'a> CodegenContext<'a> {
             }
             LiteralKind::Array(arr) => {
                 let arr_type = self
-                    .lower_type(self.hir.node_types.get(&lit.hir_id).unwrap(), builder)
-                    .unwrap()
+                    .lower_type_of(&lit.hir_id, builder)?
                     .into_pointer_type()
                     .get_element_type()
                     .into_array_type();
 
                 let ptr = builder.build_alloca(arr_type, "array");
 
-                arr.values.iter().enumerate().for_each(|(i, expr)| {
-                    let expr = self.lower_expression(expr, builder).unwrap();
+                for (i, expr) in arr.values.iter().enumerate() {
+                    let expr = self.lower_expression(expr, builder)?;
 
                     let i64_type = self.context.i64_type();
 
@@ -814,7 +866,7 @@ impl<'a> CodegenContext<'a> {
                     };
 
                     builder.build_store(inner_ptr, expr);
-                });
+                }
 
                 ptr.as_basic_value_enum()
             }
@@ -841,6 +893,12 @@ impl<'a> CodegenContext<'a> {
     ) -> Result<BasicValueEnum<'a>, ()> {
         let reso = self.hir.resolutions.get(&id.hir_id).unwrap();
 
+        if let Some(c) = self.hir.get_const(&reso) {
+            let global = self.module.get_global(&c.symbol).unwrap();
+
+            return Ok(builder.build_load(global.as_pointer_value(), &id.name));
+        }
+
         let val = match self.scopes.get(reso.clone()) {
             None => {
                 self.module.print_to_stderr();
@@ -1039,8 +1097,23 @@ impl<'a> CodegenContext<'a> {
                 // FIXME: ignored right argument for now
                 // let right = self.lower_identifier(right, builder)?.into_int_value();
 
-                self.context
-                    .i64_type()
+                let i64_type = self.context.i64_type();
+
+                if arr_size == PrimitiveType::RUNTIME_SIZE {
+                    // The arguments of `main`, counted by the runtime
+                    let argc_f = self.module.get_function("rock_argc").unwrap_or_else(|| {
+                        self.module
+                            .add_function("rock_argc", i64_type.fn_type(&[], false), None)
+                    });
+
+                    return builder
+                        .build_call(argc_f, &[], "argc")
+                        .try_as_basic_value()
+                        .left()
+                        .ok_or(());
+                }
+
+                i64_type
                     .const_int(arr_size as u64, false)
                     .as_basic_value_enum()
             }
@@ -0,0 +1,262 @@
+use std::{collections::HashMap, rc::Rc};
+
+use inkwell::{
+    builder::Builder,
+    module::Linkage,
+    types::BasicTypeEnum,
+    values::{ArrayValue, BasicValue, BasicValueEnum, PointerValue},
+    AddressSpace,
+};
+
+use crate::{
+    hir::{visit::*, *},
+    interp::{Interpreter, Value},
+    ty::{PrimitiveType, Type},
+};
+
+use super::codegen_context::CodegenContext;
+
+impl<'a> CodegenContext<'a> {
+    // Declares a global for each constant. The ones that the interpreter can evaluate are
+    // initialized with their value, the others are computed by the C `main`.
+    pub fn lower_consts(&mut self, root: &'a Root, builder: &'a Builder) -> Result<(), ()> {
+        let mut sink = std::io::sink();
+        let mut interpreter = Interpreter::new_compile_time(root, &mut sink);
+
+        // The globals of the arrays, the structs and the strings, by address
+        let mut shared = HashMap::new();
+
+        for top_level in &root.top_levels {
+            let c = match &top_level.kind {
+                TopLevelKind::Const(c) => c,
+                _ => continue,
+            };
+
+            let t = self.lower_type_of(&c.hir_id, builder)?;
+
+            let global = self.module.add_global(t, None, &c.symbol);
+
+            let value = interpreter.eval_const(c).ok().and_then(|value| {
+                self.const_value(
+                    &value,
+                    root.node_types.get(&c.hir_id)?,
+                    &mut shared,
+                    builder,
+                )
+            });
+
+            match value {
+                Some(value) => {
+                    global.set_initializer(&value);
+                    global.set_constant(true);
+                }
+                None => {
+                    global.set_initializer(&t.const_zero());
+
+                    self.runtime_consts.push(c);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn const_value(
+        &mut self,
+        value: &Value,
+        t: &Type,
+        shared: &mut HashMap<usize, PointerValue<'a>>,
+        builder: &'a Builder,
+    ) -> Option<BasicValueEnum<'a>> {
+        Some(match (value, t) {
+            (Value::Int(i), Type::Primitive(PrimitiveType::Int8)) => {
+                self.context.i8_type().const_int(*i as u64, true).into()
+            }
+            (Value::Int(i), Type::Primitive(PrimitiveType::Int64)) => {
+                self.context.i64_type().const_int(*i as u64, true).into()
+            }
+            (Value::Float(f), Type::Primitive(PrimitiveType::Float64)) => {
+                self.context.f64_type().const_float(*f).into()
+            }
+            (Value::Bool(b), Type::Primitive(PrimitiveType::Bool)) => {
+                self.context.bool_type().const_int(*b as u64, false).into()
+            }
+            (Value::Char(c), Type::Primitive(PrimitiveType::Char)) => {
+                self.context.i8_type().const_int(*c as u64, false).into()
+            }
+            (Value::String(ptr), Type::Primitive(PrimitiveType::String)) => {
+                if let Some(global) = shared.get(&ptr.addr()) {
+                    return Some((*global).into());
+                }
+
+                let i8_type = self.context.i8_type();
+
+                let bytes = ptr
+                    .buffer()
+                    .iter()
+                    .map(|byte| i8_type.const_int(*byte as u64, false))
+                    .collect::<Vec<_>>();
+
+                // The strings of the constants can be written, like the other ones
+                let data = self.private_global(i8_type.const_array(&bytes));
+
+                let ptr_value = data.const_cast(i8_type.ptr_type(AddressSpace::Generic));
+
+                shared.insert(ptr.addr(), ptr_value);
+
+                ptr_value.into()
+            }
+            (Value::Array(values), Type::Primitive(PrimitiveType::Array(inner, size))) => {
+                let addr = Rc::as_ptr(values) as usize;
+
+                if let Some(global) = shared.get(&addr) {
+                    return Some((*global).into());
+                }
+
+                let values = values.borrow();
+
+                if values.len() != *size {
+                    return None;
+                }
+
+                let mut elems = vec![];
+
+                for value in values.iter() {
+                    elems.push(self.const_value(value, inner, shared, builder)?);
+                }
+
+                let elem_t = self.lower_type(inner, builder).ok()?;
+
+                let data = self.private_global(const_array(elem_t, &elems)?);
+
+                shared.insert(addr, data);
+
+                data.into()
+            }
+            (Value::Struct(fields), Type::Struct(struct_t)) => {
+                let addr = Rc::as_ptr(fields) as usize;
+
+                if let Some(global) = shared.get(&addr) {
+                    return Some((*global).into());
+                }
+
+                let fields = fields.borrow();
+
+                let mut values = vec![];
+
+                for (name, t) in struct_t.ordered_defs() {
+                    values.push(self.const_value(fields.get(&name)?, &t, shared, builder)?);
+                }
+
+                let data = self.private_global(self.context.const_struct(&values, false));
+
+                shared.insert(addr, data);
+
+                data.into()
+            }
+            (value, Type::Newtype(_, inner)) => self.const_value(value, inner, shared, builder)?,
+            _ => return None,
+        })
+    }
+
+    fn private_global<V: BasicValue<'a>>(&self, value: V) -> PointerValue<'a> {
+        let value = value.as_basic_value_enum();
+
+        let global = self.module.add_global(value.get_type(), None, "const_data");
+
+        global.set_linkage(Linkage::Private);
+        global.set_initializer(&value);
+
+        global.as_pointer_value()
+    }
+
+    // Stores the values of the constants that are only known at runtime, the ones that
+    // they use first
+    pub fn lower_runtime_consts(&mut self, builder: &'a Builder) -> Result<(), ()> {
+        let mut pending = std::mem::take(&mut self.runtime_consts);
+
+        while !pending.is_empty() {
+            let c = pending.remove(0);
+
+            self.lower_runtime_const(c, &mut pending, builder)?;
+        }
+
+        Ok(())
+    }
+
+    fn lower_runtime_const(
+        &mut self,
+        c: &'a Const,
+        pending: &mut Vec<&'a Const>,
+        builder: &'a Builder,
+    ) -> Result<(), ()> {
+        let mut used = UsedConsts {
+            hir: self.hir,
+            consts: vec![],
+        };
+
+        used.visit_expression(&c.value);
+
+        for used in used.consts {
+            if let Some(i) = pending
+                .iter()
+                .position(|pending| pending.hir_id == used.hir_id)
+            {
+                let used = pending.remove(i);
+
+                self.lower_runtime_const(used, pending, builder)?;
+            }
+        }
+
+        let value = self.lower_expression(&c.value, builder)?;
+
+        let global = self.module.get_global(&c.symbol).unwrap();
+
+        builder.build_store(global.as_pointer_value(), value);
+
+        Ok(())
+    }
+}
+
+fn const_array<'a>(t: BasicTypeEnum<'a>, values: &[BasicValueEnum<'a>]) -> Option<ArrayValue<'a>> {
+    Some(match t {
+        BasicTypeEnum::IntType(t) => t.const_array(
+            &values
+                .iter()
+                .map(|value| value.into_int_value())
+                .collect::<Vec<_>>(),
+        ),
+        BasicTypeEnum::FloatType(t) => t.const_array(
+            &values
+                .iter()
+                .map(|value| value.into_float_value())
+                .collect::<Vec<_>>(),
+        ),
+        BasicTypeEnum::PointerType(t) => t.const_array(
+            &values
+                .iter()
+                .map(|value| value.into_pointer_value())
+                .collect::<Vec<_>>(),
+        ),
+        _ => return None,
+    })
+}
+
+// The constants that an initializer refers to directly
+struct UsedConsts<'a> {
+    hir: &'a Root,
+    consts: Vec<&'a Const>,
+}
+
+impl<'a> Visitor<'a> for UsedConsts<'a> {
+    fn visit_identifier(&mut self, id: &'a Identifier) {
+        if let Some(c) = self
+            .hir
+            .resolutions
+            .get(&id.hir_id)
+            .and_then(|reso| self.hir.get_const(&reso))
+        {
+            self.consts.push(c);
+        }
+    }
+}
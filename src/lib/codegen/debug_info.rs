@@ -0,0 +1,489 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    debug_info::{
+        AsDIScope, DICompileUnit, DIExpression, DIFile, DIFlags, DIFlagsConstants, DILocalVariable,
+        DILocation, DISubprogram, DIType, DWARFEmissionKind, DWARFSourceLanguage, DebugInfoBuilder,
+    },
+    module::{FlagBehavior, Module},
+    targets::TargetData,
+    types::BasicType,
+    values::{BasicValueEnum, FunctionValue, PointerValue},
+    AddressSpace,
+};
+
+use crate::{
+    hir::*,
+    parser::{SourceFile, Span},
+    ty::{PrimitiveType, StructType, Type},
+};
+
+use super::codegen_context::CodegenContext;
+
+// DWARF base type encodings and expression operations
+const DW_ATE_ADDRESS: u32 = 0x01;
+const DW_ATE_BOOLEAN: u32 = 0x02;
+const DW_ATE_FLOAT: u32 = 0x04;
+const DW_ATE_SIGNED: u32 = 0x05;
+const DW_ATE_SIGNED_CHAR: u32 = 0x06;
+const DW_OP_DEREF: i64 = 0x06;
+
+struct DebugFile<'a> {
+    file: DIFile<'a>,
+    line_starts: Vec<usize>,
+    header_lines: usize, // Reported on the first line
+}
+
+pub struct DebugInfo<'a> {
+    builder: DebugInfoBuilder<'a>,
+    compile_unit: DICompileUnit<'a>,
+    files: HashMap<PathBuf, DebugFile<'a>>,
+    target_data: TargetData,
+    struct_types: HashMap<String, DIType<'a>>,
+    subprogram: Option<DISubprogram<'a>>,
+    optimized: bool,
+}
+
+impl<'a> DebugInfo<'a> {
+    pub fn new(
+        context: &'a Context,
+        module: &Module<'a>,
+        files: &HashMap<PathBuf, SourceFile>,
+        entry_point: &Path,
+        target_data: TargetData,
+        optimized: bool,
+    ) -> Self {
+        module.add_basic_value_flag(
+            "Debug Info Version",
+            FlagBehavior::Warning,
+            context.i32_type().const_int(3, false),
+        );
+        module.add_basic_value_flag(
+            "Dwarf Version",
+            FlagBehavior::Warning,
+            context.i32_type().const_int(4, false),
+        );
+
+        let directory = std::env::current_dir()
+            .map(|dir| dir.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let (builder, compile_unit) = module.create_debug_info_builder(
+            true,
+            DWARFSourceLanguage::C,
+            &entry_point.to_string_lossy(),
+            &directory,
+            concat!("rock ", env!("CARGO_PKG_VERSION")),
+            optimized,
+            "",
+            0,
+            "",
+            DWARFEmissionKind::Full,
+            0,
+            false,
+            false,
+            "",
+            "",
+        );
+
+        let files = files
+            .iter()
+            .map(|(path, source_file)| {
+                let line_starts = std::iter::once(0)
+                    .chain(source_file.content.match_indices('\n').map(|(i, _)| i + 1))
+                    .collect();
+
+                let debug_file = DebugFile {
+                    file: builder.create_file(&path.to_string_lossy(), &directory),
+                    line_starts,
+                    header_lines: source_file.header_lines(),
+                };
+
+                (path.clone(), debug_file)
+            })
+            .collect();
+
+        Self {
+            builder,
+            compile_unit,
+            files,
+            target_data,
+            struct_types: HashMap::new(),
+            subprogram: None,
+            optimized,
+        }
+    }
+
+    pub fn finalize(&self) {
+        self.builder.finalize();
+    }
+
+    // The file, line and column of the start of a span, in the original source
+    fn location(&self, span: &Span) -> Option<(DIFile<'a>, u32, u32)> {
+        let file = self.files.get(&span.file_path)?;
+
+        let line_idx = file
+            .line_starts
+            .partition_point(|start| *start <= span.start)
+            .checked_sub(1)?;
+
+        let line = line_idx.saturating_sub(file.header_lines) + 1;
+        let column = span.start - file.line_starts[line_idx] + 1;
+
+        Some((file.file, line as u32, column as u32))
+    }
+
+    fn basic_type(&self, name: &str, size_in_bits: u64, encoding: u32) -> Option<DIType<'a>> {
+        self.builder
+            .create_basic_type(name, size_in_bits, encoding, DIFlags::ZERO)
+            .ok()
+            .map(|t| t.as_type())
+    }
+}
+
+impl<'a> CodegenContext<'a> {
+    fn span_location(&self, hir_id: &HirId) -> Option<(DIFile<'a>, u32, u32)> {
+        let span = self
+            .hir
+            .hir_map
+            .get_node_id(hir_id)
+            .and_then(|node_id| self.hir.spans.get(&node_id))?;
+
+        self.debug_info.as_ref()?.location(span)
+    }
+
+    // Gives the function its source-level name and sets the location of its prologue
+    pub fn debug_function(
+        &mut self,
+        f: &FunctionDecl,
+        fn_value: FunctionValue<'a>,
+        builder: &'a Builder,
+    ) -> Option<()> {
+        let (file, line, _) = self.span_location(&f.hir_id)?;
+
+        let f_type = match self.hir.node_types.get(&f.hir_id)? {
+            Type::Func(f_type) => f_type.clone(),
+            _ => return None,
+        };
+
+        let ret = self.debug_type(&f_type.ret, builder);
+
+        let arguments = f_type
+            .arguments
+            .iter()
+            .filter_map(|arg| self.debug_type(arg, builder))
+            .collect::<Vec<_>>();
+
+        let debug_info = self.debug_info.as_mut()?;
+
+        let subroutine_type =
+            debug_info
+                .builder
+                .create_subroutine_type(file, ret, &arguments, DIFlags::ZERO);
+
+        let subprogram = debug_info.builder.create_function(
+            debug_info.compile_unit.as_debug_info_scope(),
+            &f.name.name,
+            Some(&f.get_name().name),
+            file,
+            line,
+            subroutine_type,
+            false,
+            true,
+            line,
+            DIFlags::ZERO,
+            debug_info.optimized,
+        );
+
+        fn_value.set_subprogram(subprogram);
+
+        debug_info.subprogram = Some(subprogram);
+
+        self.debug_location(&f.hir_id, builder);
+
+        Some(())
+    }
+
+    pub fn debug_location(&self, hir_id: &HirId, builder: &'a Builder) -> Option<()> {
+        let (_, line, column) = self.span_location(hir_id)?;
+
+        let debug_info = self.debug_info.as_ref()?;
+
+        let location = debug_info.builder.create_debug_location(
+            self.context,
+            line,
+            column,
+            debug_info.subprogram?.as_debug_info_scope(),
+            None,
+        );
+
+        builder.set_current_debug_location(self.context, location);
+
+        Some(())
+    }
+
+    // The arguments are copied on the stack so that they can be inspected
+    pub fn debug_arguments(
+        &mut self,
+        f: &FunctionDecl,
+        fn_value: FunctionValue<'a>,
+        builder: &'a Builder,
+    ) -> Option<()> {
+        let first_instruction = fn_value.get_first_basic_block()?.get_first_instruction()?;
+
+        let entry_builder = self.context.create_builder();
+
+        entry_builder.position_before(&first_instruction);
+
+        for (i, arg) in f.arguments.iter().enumerate() {
+            let param = fn_value.get_nth_param(i as u32)?;
+
+            let (variable, expression, location) = match self.debug_variable(
+                &arg.name.name,
+                &arg.name.hir_id,
+                Some(i as u32 + 1),
+                builder,
+            ) {
+                Some(variable) => variable,
+                None => continue,
+            };
+
+            let storage = entry_builder.build_alloca(param.get_type(), &arg.name.name);
+
+            entry_builder.build_store(storage, param);
+
+            self.debug_info
+                .as_ref()?
+                .builder
+                .insert_declare_before_instruction(
+                    storage,
+                    Some(variable),
+                    Some(expression),
+                    location,
+                    first_instruction,
+                );
+        }
+
+        Some(())
+    }
+
+    // `storage` is the stack slot of the variable when it has one. Otherwise the
+    // value is copied in a new one in the entry block.
+    pub fn debug_local(
+        &mut self,
+        id: &Identifier,
+        value: BasicValueEnum<'a>,
+        storage: Option<PointerValue<'a>>,
+        builder: &'a Builder,
+    ) -> Option<()> {
+        let (variable, expression, location) =
+            self.debug_variable(&id.name, &id.get_hir_id(), None, builder)?;
+
+        let storage = match storage {
+            Some(storage) => storage,
+            None => {
+                let storage = self.entry_alloca(value.get_type(), &id.name)?;
+
+                builder.build_store(storage, value);
+
+                storage
+            }
+        };
+
+        self.debug_info.as_ref()?.builder.insert_declare_at_end(
+            storage,
+            Some(variable),
+            Some(expression),
+            location,
+            builder.get_insert_block()?,
+        );
+
+        Some(())
+    }
+
+    fn entry_alloca<T: BasicType<'a>>(&self, t: T, name: &str) -> Option<PointerValue<'a>> {
+        let entry = self.cur_func?.get_first_basic_block()?;
+
+        let entry_builder = self.context.create_builder();
+
+        match entry.get_first_instruction() {
+            Some(instruction) => entry_builder.position_before(&instruction),
+            None => entry_builder.position_at_end(entry),
+        }
+
+        Some(entry_builder.build_alloca(t, name))
+    }
+
+    // Structs are behind a pointer, so their variables dereference it
+    fn debug_variable(
+        &mut self,
+        name: &str,
+        hir_id: &HirId,
+        arg_no: Option<u32>,
+        builder: &'a Builder,
+    ) -> Option<(DILocalVariable<'a>, DIExpression<'a>, DILocation<'a>)> {
+        let (file, line, column) = self.span_location(hir_id)?;
+
+        let t = self.hir.node_types.get(hir_id)?.clone();
+
+        let ty = self.debug_type(&t, builder)?;
+
+        let debug_info = self.debug_info.as_ref()?;
+
+        let scope = debug_info.subprogram?.as_debug_info_scope();
+
+        let variable = match arg_no {
+            Some(arg_no) => debug_info.builder.create_parameter_variable(
+                scope,
+                name,
+                arg_no,
+                file,
+                line,
+                ty,
+                true,
+                DIFlags::ZERO,
+            ),
+            None => debug_info.builder.create_auto_variable(
+                scope,
+                name,
+                file,
+                line,
+                ty,
+                true,
+                DIFlags::ZERO,
+                0,
+            ),
+        };
+
+        let is_struct = t.is_struct() || t.try_as_newtype_inner().is_some_and(Type::is_struct);
+
+        let expression = if is_struct {
+            debug_info.builder.create_expression(vec![DW_OP_DEREF])
+        } else {
+            debug_info.builder.create_expression(vec![])
+        };
+
+        let location =
+            debug_info
+                .builder
+                .create_debug_location(self.context, line, column, scope, None);
+
+        Some((variable, expression, location))
+    }
+
+    fn debug_type(&mut self, t: &Type, builder: &'a Builder) -> Option<DIType<'a>> {
+        let debug_info = self.debug_info.as_ref()?;
+
+        match t {
+            Type::Primitive(PrimitiveType::Int8) => debug_info.basic_type("Int8", 8, DW_ATE_SIGNED),
+            Type::Primitive(PrimitiveType::Int64) => {
+                debug_info.basic_type("Int64", 64, DW_ATE_SIGNED)
+            }
+            Type::Primitive(PrimitiveType::Float64) => {
+                debug_info.basic_type("Float64", 64, DW_ATE_FLOAT)
+            }
+            Type::Primitive(PrimitiveType::Bool) => {
+                debug_info.basic_type("Bool", 8, DW_ATE_BOOLEAN)
+            }
+            Type::Primitive(PrimitiveType::Char) => {
+                debug_info.basic_type("Char", 8, DW_ATE_SIGNED_CHAR)
+            }
+            Type::Primitive(PrimitiveType::Void) => None,
+            Type::Struct(s) => self.debug_struct_type(t, s, builder),
+            Type::Newtype(_, inner) => self.debug_type(inner, builder),
+            // Strings, arrays and functions are shown as their address
+            _ => {
+                let pointer_size = debug_info
+                    .target_data
+                    .get_bit_size(&self.context.i8_type().ptr_type(AddressSpace::Generic));
+
+                debug_info.basic_type(&t.to_string(), pointer_size, DW_ATE_ADDRESS)
+            }
+        }
+    }
+
+    fn debug_struct_type(
+        &mut self,
+        t: &Type,
+        s: &StructType,
+        builder: &'a Builder,
+    ) -> Option<DIType<'a>> {
+        if let Some(struct_type) = self.debug_info.as_ref()?.struct_types.get(&s.name) {
+            return Some(*struct_type);
+        }
+
+        let llvm_struct_t = self
+            .lower_type(t, builder)
+            .ok()?
+            .into_pointer_type()
+            .get_element_type()
+            .into_struct_type();
+
+        let mut members = vec![];
+
+        for (i, (name, field_t)) in s.ordered_defs().iter().enumerate() {
+            let llvm_field_t = llvm_struct_t.get_field_type_at_index(i as u32)?;
+
+            // Nested structs are pointers
+            let field_debug_t = if field_t.is_struct() {
+                let debug_info = self.debug_info.as_ref()?;
+
+                debug_info.basic_type(
+                    &field_t.to_string(),
+                    debug_info.target_data.get_bit_size(&llvm_field_t),
+                    DW_ATE_ADDRESS,
+                )?
+            } else {
+                self.debug_type(field_t, builder)?
+            };
+
+            let debug_info = self.debug_info.as_ref()?;
+
+            let member = debug_info.builder.create_member_type(
+                debug_info.compile_unit.as_debug_info_scope(),
+                name,
+                debug_info.compile_unit.get_file(),
+                0,
+                debug_info.target_data.get_bit_size(&llvm_field_t),
+                debug_info.target_data.get_abi_alignment(&llvm_field_t) * 8,
+                debug_info
+                    .target_data
+                    .offset_of_element(&llvm_struct_t, i as u32)?
+                    * 8,
+                DIFlags::ZERO,
+                field_debug_t,
+            );
+
+            members.push(member.as_type());
+        }
+
+        let debug_info = self.debug_info.as_mut()?;
+
+        let struct_type = debug_info
+            .builder
+            .create_struct_type(
+                debug_info.compile_unit.as_debug_info_scope(),
+                &s.name,
+                debug_info.compile_unit.get_file(),
+                0,
+                debug_info.target_data.get_bit_size(&llvm_struct_t),
+                debug_info.target_data.get_abi_alignment(&llvm_struct_t) * 8,
+                DIFlags::ZERO,
+                None,
+                &members,
+                0,
+                None,
+                &s.name,
+            )
+            .as_type();
+
+        debug_info.struct_types.insert(s.name.clone(), struct_type);
+
+        Some(struct_type)
+    }
+}
@@ -0,0 +1,41 @@
+use inkwell::targets::{FileType, TargetMachine};
+
+use crate::{config::EmitKind, Config};
+
+use super::codegen_context::CodegenContext;
+
+impl<'a> CodegenContext<'a> {
+    // Writes the requested outputs in the build folder. The bitcode is always
+    // written by `generate`, and linking is left to the caller from the object file.
+    pub fn emit(&self, config: &Config, target_machine: &TargetMachine) -> Result<(), String> {
+        for kind in &config.emit {
+            // Both share the same object file
+            if *kind == EmitKind::Link && config.emit.contains(&EmitKind::Obj) {
+                continue;
+            }
+
+            let path = config.build_folder.join(kind.file_name());
+
+            match kind {
+                EmitKind::LlvmBc => (),
+                EmitKind::LlvmIr => self
+                    .module
+                    .print_to_file(&path)
+                    .map_err(|e| format!("{}: {}", path.display(), e))?,
+                EmitKind::Obj | EmitKind::Link | EmitKind::Asm => {
+                    let file_type = if *kind == EmitKind::Asm {
+                        FileType::Assembly
+                    } else {
+                        FileType::Object
+                    };
+
+                    target_machine
+                        .write_to_file(&self.module, file_type, &path)
+                        .map_err(|e| format!("{}: {}", path.display(), e))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
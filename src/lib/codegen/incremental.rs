@@ -0,0 +1,187 @@
+use std::collections::HashSet;
+
+use either::Either;
+use inkwell::{
+    module::Module,
+    values::{BasicValueEnum, FunctionValue, InstructionOpcode, InstructionValue},
+};
+
+use crate::hir::{Root, CONST_PREFIX};
+
+use super::codegen_context::CodegenContext;
+
+impl<'a> CodegenContext<'a> {
+    // A function body can be taken from the previous build when its module and the
+    // signatures of its dependencies did not change, when its lowered type is the same,
+    // and when every function it refers to still exists with the same type.
+    pub fn find_reusable_functions(&self, root: &Root) -> HashSet<String> {
+        let cached = match &self.cached_module {
+            Some(cached) => cached,
+            None => return HashSet::new(),
+        };
+
+        root.bodies
+            .values()
+            .filter_map(|body| {
+                let f = root.get_function_by_hir_id(&body.fn_id)?;
+
+                let span = root
+                    .hir_map
+                    .get_node_id(&f.hir_id)
+                    .and_then(|node_id| root.spans.get(&node_id))?;
+
+                if !self.unchanged_files.contains(&span.file_path) {
+                    return None;
+                }
+
+                let name = f.get_name().name;
+
+                let new_f = self.module.get_function(&name)?;
+                let cached_f = cached.get_function(&name)?;
+
+                if cached_f.count_basic_blocks() == 0
+                    || cached_f.get_type() != new_f.get_type()
+                    || !self.references_are_available(cached, cached_f)
+                {
+                    return None;
+                }
+
+                Some(name)
+            })
+            .collect()
+    }
+
+    fn references_are_available(&self, cached: &Module<'a>, f: FunctionValue<'a>) -> bool {
+        for block in f.get_basic_blocks() {
+            let mut instruction = block.get_first_instruction();
+
+            while let Some(current) = instruction {
+                instruction = current.get_next_instruction();
+
+                if Self::is_debug_intrinsic_call(current) {
+                    continue;
+                }
+
+                for i in 0..current.get_num_operands() {
+                    if let Some(Either::Left(BasicValueEnum::PointerValue(ptr))) =
+                        current.get_operand(i)
+                    {
+                        let name = ptr.get_name().to_str().unwrap_or_default();
+
+                        if let Some(referenced) = cached.get_function(name) {
+                            match self.module.get_function(name) {
+                                Some(new_f) if new_f.get_type() == referenced.get_type() => (),
+                                _ => return false,
+                            }
+                        }
+
+                        if let Some(referenced) = cached
+                            .get_global(name)
+                            .filter(|_| name.starts_with(CONST_PREFIX))
+                        {
+                            match self.module.get_global(name) {
+                                Some(new_global)
+                                    if new_global.as_pointer_value().get_type()
+                                        == referenced.as_pointer_value().get_type() => {}
+                                _ => return false,
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
+    // Their operands are metadata, that cannot be read as values. The callee is the last operand.
+    fn is_debug_intrinsic_call(instruction: InstructionValue<'a>) -> bool {
+        if instruction.get_opcode() != InstructionOpcode::Call
+            || instruction.get_num_operands() == 0
+        {
+            return false;
+        }
+
+        match instruction.get_operand(instruction.get_num_operands() - 1) {
+            Some(Either::Left(BasicValueEnum::PointerValue(callee))) => callee
+                .get_name()
+                .to_str()
+                .map(|name| name.starts_with("llvm.dbg."))
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+
+    // Every function of the previous build that is not reused is turned into a
+    // declaration, so that linking only brings the reused bodies into the new module.
+    // The constants are declarations too, their values are the new ones.
+    pub fn link_reused_functions(&mut self) -> Result<usize, ()> {
+        let cached = match self.cached_module.take() {
+            Some(cached) if !self.reused.is_empty() => cached,
+            _ => return Ok(0),
+        };
+
+        let mut stale = vec![];
+        let mut function = cached.get_first_function();
+
+        while let Some(f) = function {
+            function = f.get_next_function();
+
+            let name = f.get_name().to_str().unwrap().to_string();
+
+            if f.count_basic_blocks() > 0 && !self.reused.contains(&name) {
+                stale.push((name, f));
+            }
+        }
+
+        for (name, f) in stale {
+            let placeholder = cached.add_function(&name, f.get_type(), None);
+
+            f.replace_all_uses_with(placeholder);
+
+            unsafe { f.delete() };
+
+            let declaration = cached.add_function(&name, placeholder.get_type(), None);
+
+            placeholder.replace_all_uses_with(declaration);
+
+            unsafe { placeholder.delete() };
+        }
+
+        let mut consts = vec![];
+        let mut global = cached.get_first_global();
+
+        while let Some(g) = global {
+            global = g.get_next_global();
+
+            let name = g.get_name().to_str().unwrap().to_string();
+
+            if let Some(value) = g.get_initializer() {
+                if name.starts_with(CONST_PREFIX) {
+                    consts.push((name, g, value.get_type()));
+                }
+            }
+        }
+
+        for (name, g, t) in consts {
+            let placeholder = cached.add_global(t, None, &name);
+
+            g.as_pointer_value()
+                .replace_all_uses_with(placeholder.as_pointer_value());
+
+            unsafe { g.delete() };
+
+            let declaration = cached.add_global(t, None, &name);
+
+            placeholder
+                .as_pointer_value()
+                .replace_all_uses_with(declaration.as_pointer_value());
+
+            unsafe { placeholder.delete() };
+        }
+
+        self.module.link_in_module(cached).map_err(|_| ())?;
+
+        Ok(self.reused.len())
+    }
+}
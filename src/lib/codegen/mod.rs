@@ -1,22 +1,112 @@
 mod codegen_context;
+mod consts;
+mod debug_info;
+mod emit;
+mod incremental;
+mod optimize;
+mod runtime;
+mod target;
+
+#[cfg(test)]
+mod tests;
+
+use std::{collections::HashSet, path::PathBuf};
 
 use codegen_context::*;
-use inkwell::context::Context;
+use inkwell::{context::Context, module::Module};
+pub use target::{available_targets, host_triple};
 
-use crate::{diagnostics::Diagnostic, hir::Root, Config};
+use crate::{
+    cache::CACHED_IR_FILE,
+    config::{CrateType, OptLevel},
+    diagnostics::Diagnostic,
+    hir::Root,
+    parser::ParsingCtx,
+    Config,
+};
 
-pub fn generate(config: &Config, hir: Root) -> Result<(), Diagnostic> {
+pub fn generate(
+    config: &Config,
+    hir: Root,
+    unchanged_files: HashSet<PathBuf>,
+    parsing_ctx: &mut ParsingCtx,
+) -> Result<usize, Diagnostic> {
     let context = Context::create();
     let builder = context.create_builder();
 
     let mut codegen_ctx = CodegenContext::new(&context, &hir);
 
-    if codegen_ctx.lower_hir(&hir, &builder).is_err() {
-        // FIXME: have a movable `Diagnostics`
-        // codegen_ctx.parsing_ctx.return_if_error()?;
-        panic!("GEN ERROR");
+    let target_machine = match target::create_target_machine(config) {
+        Ok(target_machine) => target_machine,
+        Err(msg) => {
+            parsing_ctx
+                .diagnostics
+                .push_error(Diagnostic::new_unsupported_target(msg));
+
+            parsing_ctx.return_if_error()?;
+
+            return Err(Diagnostic::new_empty());
+        }
+    };
+
+    codegen_ctx.module.set_triple(&target_machine.get_triple());
+    codegen_ctx
+        .module
+        .set_data_layout(&target_machine.get_target_data().get_data_layout());
+
+    if config.debug_info {
+        codegen_ctx.debug_info = Some(debug_info::DebugInfo::new(
+            &context,
+            &codegen_ctx.module,
+            &parsing_ctx.files,
+            &config.project_config.entry_file(),
+            target_machine.get_target_data(),
+            config.opt_level != OptLevel::O0,
+        ));
+    }
+
+    if !unchanged_files.is_empty() {
+        codegen_ctx.cached_module =
+            Module::parse_bitcode_from_path(config.build_folder.join(CACHED_IR_FILE), &context)
+                .ok();
+        codegen_ctx.unchanged_files = unchanged_files;
+    }
+
+    if codegen_ctx.lower_hir(&hir, &builder).is_err()
+        || codegen_ctx
+            .lower_runtime(&builder, config.crate_type == CrateType::Bin)
+            .is_err()
+    {
+        if codegen_ctx.diagnostics.list.is_empty() {
+            println!("Error: Cannot generate the code");
+
+            return Err(Diagnostic::new_empty());
+        }
+
+        parsing_ctx
+            .diagnostics
+            .append(std::mem::take(&mut codegen_ctx.diagnostics));
+
+        parsing_ctx.return_if_error()?;
+    }
+
+    if let Some(debug_info) = &codegen_ctx.debug_info {
+        debug_info.finalize();
     }
 
+    let reused = match codegen_ctx.link_reused_functions() {
+        Ok(reused) => reused,
+        Err(_) => {
+            parsing_ctx
+                .diagnostics
+                .push_error(Diagnostic::new_reuse_failed());
+
+            parsing_ctx.return_if_error()?;
+
+            return Err(Diagnostic::new_empty());
+        }
+    };
+
     match codegen_ctx.module.verify() {
         Ok(_) => (),
         Err(e) => {
@@ -28,20 +118,43 @@ pub fn generate(config: &Config, hir: Root) -> Result<(), Diagnostic> {
         }
     }
 
-    if !config.no_optimize {
-        codegen_ctx.optimize();
+    // The cache keeps the unoptimized IR, as inlined functions cannot be reused
+    codegen_ctx
+        .module
+        .write_bitcode_to_path(&config.build_folder.join(CACHED_IR_FILE));
+
+    if let Err(name) = codegen_ctx.optimize(config.opt_level, config.passes.as_deref()) {
+        parsing_ctx
+            .diagnostics
+            .push_error(Diagnostic::new_unknown_pass(name));
+
+        parsing_ctx.return_if_error()?;
     }
 
     if config.show_ir {
         codegen_ctx.module.print_to_stderr();
     }
 
-    if !codegen_ctx
-        .module
-        .write_bitcode_to_path(&config.build_folder.join("out.bc"))
-    {
-        panic!("CANNOT IR WRITE TO PATH");
+    let bitcode_path = config.build_folder.join("out.bc");
+
+    if !codegen_ctx.module.write_bitcode_to_path(&bitcode_path) {
+        parsing_ctx
+            .diagnostics
+            .push_error(Diagnostic::new_emit_failed(format!(
+                "{}: the bitcode cannot be written",
+                bitcode_path.display()
+            )));
+
+        parsing_ctx.return_if_error()?;
+    }
+
+    if let Err(msg) = codegen_ctx.emit(config, &target_machine) {
+        parsing_ctx
+            .diagnostics
+            .push_error(Diagnostic::new_emit_failed(msg));
+
+        parsing_ctx.return_if_error()?;
     }
 
-    Ok(())
+    Ok(reused)
 }
@@ -0,0 +1,135 @@
+use inkwell::{
+    module::Module,
+    passes::{PassManager, PassManagerBuilder},
+    OptimizationLevel,
+};
+
+use crate::config::OptLevel;
+
+use super::codegen_context::CodegenContext;
+
+impl<'a> CodegenContext<'a> {
+    // Runs LLVM's standard pipeline for the given level, or only the comma separated
+    // list of `passes` when given, in order. Fails with the name of an unknown pass.
+    pub fn optimize(&mut self, opt_level: OptLevel, passes: Option<&str>) -> Result<(), String> {
+        let pass_manager = PassManager::create(());
+
+        if let Some(passes) = passes {
+            for pass in passes
+                .split(',')
+                .map(str::trim)
+                .filter(|pass| !pass.is_empty())
+            {
+                add_pass(&pass_manager, pass)?;
+            }
+        } else {
+            if opt_level == OptLevel::O0 {
+                return Ok(());
+            }
+
+            let pass_manager_builder = Self::create_pass_manager_builder(opt_level);
+
+            let function_pass_manager = PassManager::create(&self.module);
+
+            pass_manager_builder.populate_function_pass_manager(&function_pass_manager);
+
+            function_pass_manager.initialize();
+
+            let mut function = self.module.get_first_function();
+
+            while let Some(f) = function {
+                function_pass_manager.run_on(&f);
+
+                function = f.get_next_function();
+            }
+
+            function_pass_manager.finalize();
+
+            pass_manager_builder.populate_module_pass_manager(&pass_manager);
+        }
+
+        pass_manager.add_verifier_pass();
+
+        pass_manager.run_on(&self.module);
+
+        Ok(())
+    }
+
+    // Same levels and inlining thresholds as clang
+    fn create_pass_manager_builder(opt_level: OptLevel) -> PassManagerBuilder {
+        let pass_manager_builder = PassManagerBuilder::create();
+
+        let (level, size_level, inline_threshold) = match opt_level {
+            OptLevel::O0 => (OptimizationLevel::None, 0, None),
+            OptLevel::O1 => (OptimizationLevel::Less, 0, None),
+            OptLevel::O2 => (OptimizationLevel::Default, 0, Some(225)),
+            OptLevel::O3 => (OptimizationLevel::Aggressive, 0, Some(250)),
+            OptLevel::Os => (OptimizationLevel::Default, 1, Some(75)),
+        };
+
+        pass_manager_builder.set_optimization_level(level);
+        pass_manager_builder.set_size_level(size_level);
+
+        if let Some(threshold) = inline_threshold {
+            pass_manager_builder.set_inliner_with_threshold(threshold);
+        }
+
+        pass_manager_builder
+    }
+}
+
+// The names are the ones of LLVM's `opt`
+fn add_pass(pass_manager: &PassManager<Module>, name: &str) -> Result<(), String> {
+    match name {
+        "adce" => pass_manager.add_aggressive_dce_pass(),
+        "aggressive-instcombine" => pass_manager.add_aggressive_inst_combiner_pass(),
+        "always-inline" => pass_manager.add_always_inliner_pass(),
+        "argpromotion" => pass_manager.add_argument_promotion_pass(),
+        "basic-aa" => pass_manager.add_basic_alias_analysis_pass(),
+        "bdce" => pass_manager.add_bit_tracking_dce_pass(),
+        "constmerge" => pass_manager.add_constant_merge_pass(),
+        "correlated-propagation" => pass_manager.add_correlated_value_propagation_pass(),
+        "deadargelim" => pass_manager.add_dead_arg_elimination_pass(),
+        "dse" => pass_manager.add_dead_store_elimination_pass(),
+        "early-cse" => pass_manager.add_early_cse_pass(),
+        "function-attrs" => pass_manager.add_function_attrs_pass(),
+        "globaldce" => pass_manager.add_global_dce_pass(),
+        "globalopt" => pass_manager.add_global_optimizer_pass(),
+        "gvn" => pass_manager.add_gvn_pass(),
+        "indvars" => pass_manager.add_ind_var_simplify_pass(),
+        "inline" => pass_manager.add_function_inlining_pass(),
+        "instcombine" => pass_manager.add_instruction_combining_pass(),
+        "instsimplify" => pass_manager.add_instruction_simplify_pass(),
+        "internalize" => pass_manager.add_internalize_pass(true),
+        "ipsccp" => pass_manager.add_ipsccp_pass(),
+        "jump-threading" => pass_manager.add_jump_threading_pass(),
+        "licm" => pass_manager.add_licm_pass(),
+        "loop-deletion" => pass_manager.add_loop_deletion_pass(),
+        "loop-idiom" => pass_manager.add_loop_idiom_pass(),
+        "loop-rotate" => pass_manager.add_loop_rotate_pass(),
+        "loop-unroll" => pass_manager.add_loop_unroll_pass(),
+        "loop-unroll-and-jam" => pass_manager.add_loop_unroll_and_jam_pass(),
+        "loop-unswitch" => pass_manager.add_loop_unswitch_pass(),
+        "loop-vectorize" => pass_manager.add_loop_vectorize_pass(),
+        "lowerswitch" => pass_manager.add_lower_switch_pass(),
+        "mem2reg" => pass_manager.add_promote_memory_to_register_pass(),
+        "memcpyopt" => pass_manager.add_memcpy_optimize_pass(),
+        "mldst-motion" => pass_manager.add_merged_load_store_motion_pass(),
+        "newgvn" => pass_manager.add_new_gvn_pass(),
+        "partially-inline-libcalls" => pass_manager.add_partially_inline_lib_calls_pass(),
+        "prune-eh" => pass_manager.add_prune_eh_pass(),
+        "reassociate" => pass_manager.add_reassociate_pass(),
+        "reg2mem" => pass_manager.add_demote_memory_to_register_pass(),
+        "scalarizer" => pass_manager.add_scalarizer_pass(),
+        "sccp" => pass_manager.add_sccp_pass(),
+        "simplifycfg" => pass_manager.add_cfg_simplification_pass(),
+        "slp-vectorizer" => pass_manager.add_slp_vectorize_pass(),
+        "sroa" => pass_manager.add_scalar_repl_aggregates_pass_ssa(),
+        "strip" => pass_manager.add_strip_symbol_pass(),
+        "strip-dead-prototypes" => pass_manager.add_strip_dead_prototypes_pass(),
+        "tailcallelim" => pass_manager.add_tail_call_elimination_pass(),
+        _ => return Err(name.to_string()),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use inkwell::{builder::Builder, module::Linkage, values::BasicValueEnum, AddressSpace};
+
+use crate::hir::ENTRY_POINT;
+
+use super::codegen_context::CodegenContext;
+
+impl<'a> CodegenContext<'a> {
+    // Generates the C `main(argc, argv)` that calls the Rock entry point, unless we
+    // build a library, and the few functions that the std needs to access the process environment
+    pub fn lower_runtime(&mut self, builder: &'a Builder, with_main: bool) -> Result<(), ()> {
+        let i32_type = self.context.i32_type();
+        let i64_type = self.context.i64_type();
+        let str_type = self.context.i8_type().ptr_type(AddressSpace::Generic);
+        let argv_type = str_type.ptr_type(AddressSpace::Generic);
+
+        let argc_global = self.module.add_global(i64_type, None, "rock.argc");
+        argc_global.set_linkage(Linkage::Internal);
+        argc_global.set_initializer(&i64_type.const_zero());
+
+        // rock_argc: -> Int64
+        let argc_f = self.module.get_function("rock_argc").unwrap_or_else(|| {
+            self.module
+                .add_function("rock_argc", i64_type.fn_type(&[], false), None)
+        });
+
+        builder.position_at_end(self.context.append_basic_block(argc_f, "entry"));
+
+        let argc = builder.build_load(argc_global.as_pointer_value(), "argc");
+
+        builder.build_return(Some(&argc));
+
+        // rock_getenv: String => String, with an empty string instead of a null pointer
+        let getenv = self.module.get_function("getenv").unwrap_or_else(|| {
+            self.module
+                .add_function("getenv", str_type.fn_type(&[str_type.into()], false), None)
+        });
+
+        let getenv_f = self.module.get_function("rock_getenv").unwrap_or_else(|| {
+            self.module.add_function(
+                "rock_getenv",
+                str_type.fn_type(&[str_type.into()], false),
+                None,
+            )
+        });
+
+        builder.position_at_end(self.context.append_basic_block(getenv_f, "entry"));
+
+        let name = getenv_f.get_nth_param(0).ok_or(())?;
+
+        let value = builder
+            .build_call(getenv, &[name.into()], "value")
+            .try_as_basic_value()
+            .left()
+            .ok_or(())?
+            .into_pointer_value();
+
+        let is_null = builder.build_is_null(value, "is_null");
+
+        let empty = builder
+            .build_global_string_ptr("", "empty")
+            .as_pointer_value();
+
+        let res = builder.build_select(is_null, empty, value, "res");
+
+        builder.build_return(Some(&res));
+
+        if !with_main {
+            // There is no `main` to initialize them
+            if let Some(c) = self.runtime_consts.first() {
+                return self.codegen_error(
+                    &c.name.hir_id,
+                    &format!(
+                        "The constant `{}` of a library must be evaluable at compile time",
+                        c.name.name
+                    ),
+                );
+            }
+
+            return Ok(());
+        }
+
+        // main: argc, argv -> exit code
+        let entry_point = self.module.get_function(ENTRY_POINT).ok_or(())?;
+
+        let main_f = self.module.add_function(
+            "main",
+            i32_type.fn_type(&[i32_type.into(), argv_type.into()], false),
+            None,
+        );
+
+        builder.position_at_end(self.context.append_basic_block(main_f, "entry"));
+
+        let argc = main_f.get_nth_param(0).ok_or(())?.into_int_value();
+        let argv = main_f.get_nth_param(1).ok_or(())?.into_pointer_value();
+
+        builder.build_store(
+            argc_global.as_pointer_value(),
+            builder.build_int_s_extend(argc, i64_type, "argc"),
+        );
+
+        self.cur_func = Some(main_f);
+
+        self.lower_runtime_consts(builder)?;
+
+        let args = match entry_point.get_first_param() {
+            Some(param) => vec![builder
+                .build_pointer_cast(argv, param.get_type().into_pointer_type(), "args")
+                .into()],
+            None => vec![],
+        };
+
+        let ret = builder
+            .build_call(entry_point, &args, "ret")
+            .try_as_basic_value()
+            .left();
+
+        // Pointers are returned as they used to be when `main` was directly the C one
+        let ret = match ret {
+            Some(BasicValueEnum::PointerValue(ptr)) => {
+                Some(builder.build_ptr_to_int(ptr, i64_type, "ret_ptr").into())
+            }
+            ret => ret,
+        };
+
+        let exit_code = match ret {
+            Some(BasicValueEnum::IntValue(ret)) if ret.get_type().get_bit_width() > 32 => {
+                builder.build_int_truncate(ret, i32_type, "exit_code")
+            }
+            Some(BasicValueEnum::IntValue(ret)) => {
+                builder.build_int_z_extend(ret, i32_type, "exit_code")
+            }
+            _ => i32_type.const_zero(),
+        };
+
+        builder.build_return(Some(&exit_code));
+
+        Ok(())
+    }
+}
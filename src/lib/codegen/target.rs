@@ -0,0 +1,74 @@
+use inkwell::{
+    targets::{CodeModel, InitializationConfig, RelocMode, Target, TargetMachine, TargetTriple},
+    OptimizationLevel,
+};
+
+use crate::{config::OptLevel, Config};
+
+// Builds for the host unless `config.target` gives another triple
+pub fn create_target_machine(config: &Config) -> Result<TargetMachine, String> {
+    let triple = match &config.target {
+        Some(target) => {
+            Target::initialize_all(&InitializationConfig::default());
+
+            TargetTriple::create(target)
+        }
+        None => {
+            Target::initialize_native(&InitializationConfig::default())?;
+
+            TargetMachine::get_default_triple()
+        }
+    };
+
+    let triple_name = triple.as_str().to_string_lossy().to_string();
+
+    let target = Target::from_triple(&triple)
+        .map_err(|e| format!("{}: {}", triple_name, e.to_string_lossy()))?;
+
+    let level = match config.opt_level {
+        OptLevel::O0 => OptimizationLevel::None,
+        OptLevel::O1 => OptimizationLevel::Less,
+        OptLevel::O2 | OptLevel::Os => OptimizationLevel::Default,
+        OptLevel::O3 => OptimizationLevel::Aggressive,
+    };
+
+    // Position independent code can be linked both in executables and shared libraries
+    target
+        .create_target_machine(
+            &triple,
+            "generic",
+            "",
+            level,
+            RelocMode::PIC,
+            CodeModel::Default,
+        )
+        .ok_or_else(|| format!("{}: no target machine can be created", triple_name))
+}
+
+pub fn host_triple() -> String {
+    TargetMachine::get_default_triple()
+        .as_str()
+        .to_string_lossy()
+        .to_string()
+}
+
+// The name and description of every target that the linked LLVM supports
+pub fn available_targets() -> Vec<(String, String)> {
+    Target::initialize_all(&InitializationConfig::default());
+
+    let mut targets = vec![];
+    let mut target = Target::get_first();
+
+    while let Some(current) = target {
+        targets.push((
+            current.get_name().to_string_lossy().to_string(),
+            current.get_description().to_string_lossy().to_string(),
+        ));
+
+        target = current.get_next();
+    }
+
+    targets.sort();
+
+    targets
+}
@@ -0,0 +1,343 @@
+use std::{collections::HashSet, fs, path::PathBuf};
+
+use crate::{
+    config::{BuildProfile, OptLevel},
+    diagnostics::Diagnostic,
+    parser::{ParsingCtx, SourceFile},
+    Config,
+};
+
+static MAIN: &str = "id: x -> x\n\nmain: -> id 2\n";
+
+fn build_folder(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rock_codegen_{}_{}", name, std::process::id()));
+
+    let _ = fs::remove_dir_all(&dir);
+
+    fs::create_dir_all(&dir).unwrap();
+
+    dir
+}
+
+// Generates the code of a program without the std, in the build folder of the config
+fn generate(source: &str, config: &Config) -> (Result<usize, Diagnostic>, ParsingCtx) {
+    let mut file = SourceFile::from_str("main.rk", source).unwrap();
+
+    file.mod_path = PathBuf::from("root");
+
+    let mut parsing_ctx = ParsingCtx::new(config);
+
+    parsing_ctx.add_file(&file);
+
+    let hir = crate::parse_str(&mut parsing_ctx, config).unwrap();
+
+    let res = crate::generate_ir(hir, config, HashSet::new(), &mut parsing_ctx);
+
+    (res, parsing_ctx)
+}
+
+fn config(name: &str) -> Config {
+    Config {
+        quiet: true,
+        build_folder: build_folder(name),
+        ..Config::default()
+    }
+}
+
+#[cfg(test)]
+mod optimize {
+    use super::*;
+
+    #[test]
+    fn opt_levels() {
+        for (level, opt_level) in [
+            ("0", OptLevel::O0),
+            ("1", OptLevel::O1),
+            ("2", OptLevel::O2),
+            ("3", OptLevel::O3),
+            ("s", OptLevel::Os),
+        ] {
+            assert_eq!(OptLevel::parse(level), Some(opt_level));
+            assert_eq!(opt_level.as_str(), format!("O{}", level));
+        }
+
+        assert_eq!(OptLevel::parse("4"), None);
+    }
+
+    #[test]
+    fn profiles() {
+        assert_eq!(BuildProfile::Debug.opt_level(), OptLevel::O0);
+        assert_eq!(BuildProfile::Release.opt_level(), OptLevel::O3);
+        assert_eq!(OptLevel::default(), OptLevel::O3);
+    }
+
+    // Every name of the table is a pass that can run
+    #[test]
+    fn known_passes() {
+        let passes = [
+            "adce",
+            "aggressive-instcombine",
+            "always-inline",
+            "argpromotion",
+            "basic-aa",
+            "bdce",
+            "constmerge",
+            "correlated-propagation",
+            "deadargelim",
+            "dse",
+            "early-cse",
+            "function-attrs",
+            "globaldce",
+            "globalopt",
+            "gvn",
+            "indvars",
+            "inline",
+            "instcombine",
+            "instsimplify",
+            "internalize",
+            "ipsccp",
+            "jump-threading",
+            "licm",
+            "loop-deletion",
+            "loop-idiom",
+            "loop-rotate",
+            "loop-unroll",
+            "loop-unroll-and-jam",
+            "loop-unswitch",
+            "loop-vectorize",
+            "lowerswitch",
+            "mem2reg",
+            "memcpyopt",
+            "mldst-motion",
+            "newgvn",
+            "partially-inline-libcalls",
+            "prune-eh",
+            "reassociate",
+            "reg2mem",
+            "scalarizer",
+            "sccp",
+            "simplifycfg",
+            "slp-vectorizer",
+            "sroa",
+            "strip",
+            "strip-dead-prototypes",
+            "tailcallelim",
+        ];
+
+        let config = Config {
+            passes: Some(passes.join(", ")),
+            ..config("known_passes")
+        };
+
+        let (res, parsing_ctx) = generate(MAIN, &config);
+
+        assert!(res.is_ok());
+        assert!(parsing_ctx.diagnostics.list.is_empty());
+    }
+
+    #[test]
+    fn unknown_pass() {
+        let config = Config {
+            passes: Some("mem2reg,inst-combine".to_string()),
+            ..config("unknown_pass")
+        };
+
+        let (res, parsing_ctx) = generate(MAIN, &config);
+
+        let kind = parsing_ctx.diagnostics.list[0].get_kind();
+
+        assert!(res.is_err());
+        assert_eq!(kind.code(), "R0030");
+        assert_eq!(kind.message(), "Unknown optimization pass: inst-combine");
+    }
+}
+
+#[cfg(test)]
+mod target {
+    use super::{super::target::*, *};
+
+    #[test]
+    fn available() {
+        let targets = available_targets();
+
+        let mut sorted = targets.clone();
+
+        sorted.sort();
+        sorted.dedup();
+
+        assert!(!targets.is_empty());
+        assert_eq!(targets, sorted);
+    }
+
+    #[test]
+    fn host() {
+        let target_machine = create_target_machine(&Config::default()).unwrap();
+
+        assert_eq!(
+            target_machine.get_triple().as_str().to_string_lossy(),
+            host_triple()
+        );
+    }
+
+    #[test]
+    fn other_target() {
+        let config = Config {
+            target: Some("aarch64-unknown-linux-gnu".to_string()),
+            ..Config::default()
+        };
+
+        let target_machine = create_target_machine(&config).unwrap();
+
+        assert_eq!(
+            target_machine.get_triple().as_str().to_string_lossy(),
+            "aarch64-unknown-linux-gnu"
+        );
+    }
+
+    #[test]
+    fn invalid_target() {
+        let config = Config {
+            target: Some("foo-bar-baz".to_string()),
+            ..Config::default()
+        };
+
+        let err = create_target_machine(&config).err().unwrap();
+
+        assert!(err.starts_with("foo-bar-baz: "), "{}", err);
+    }
+
+    #[test]
+    fn unsupported_target() {
+        let config = Config {
+            target: Some("foo-bar-baz".to_string()),
+            ..config("unsupported_target")
+        };
+
+        let (res, parsing_ctx) = generate(MAIN, &config);
+
+        let kind = parsing_ctx.diagnostics.list[0].get_kind();
+
+        assert!(res.is_err());
+        assert_eq!(kind.code(), "R0032");
+        assert!(
+            kind.message()
+                .starts_with("Unsupported target foo-bar-baz: "),
+            "{}",
+            kind.message()
+        );
+    }
+}
+
+#[cfg(test)]
+mod debug_info {
+    use super::*;
+    use crate::config::EmitKind;
+
+    // The textual IR of the program built with `-g`
+    fn debug_ir(name: &str, source: &str) -> String {
+        let mut config = Config {
+            debug_info: true,
+            opt_level: OptLevel::O0,
+            emit: vec![EmitKind::LlvmIr],
+            ..config(name)
+        };
+
+        // The compile unit is named after it
+        config.project_config.entry_point = PathBuf::from("main.rk");
+
+        let (res, _) = generate(source, &config);
+
+        assert!(res.is_ok());
+
+        fs::read_to_string(config.build_folder.join(EmitKind::LlvmIr.file_name())).unwrap()
+    }
+
+    #[test]
+    fn compile_unit() {
+        let ir = debug_ir("compile_unit", MAIN);
+
+        assert!(ir.contains("distinct !DICompileUnit("));
+        assert!(ir.contains("!DIFile(filename: \"main.rk\""));
+        assert!(ir.contains("!\"Debug Info Version\", i32 3"));
+    }
+
+    #[test]
+    fn subprograms() {
+        let ir = debug_ir("subprograms", MAIN);
+
+        assert!(ir.contains("!DISubprogram(name: \"id\""));
+        assert!(ir.contains("!DISubprogram(name: \"main\""));
+    }
+
+    // The lines are 1-based, `main` calls `id` on the third one
+    #[test]
+    fn line_locations() {
+        let ir = debug_ir("line_locations", MAIN);
+
+        assert!(ir.contains("!DILocation(line: 3,"));
+        assert!(ir.contains("!dbg !"));
+    }
+
+    #[test]
+    fn without_debug_info() {
+        let config = Config {
+            emit: vec![EmitKind::LlvmIr],
+            ..config("without_debug_info")
+        };
+
+        let (res, _) = generate(MAIN, &config);
+
+        assert!(res.is_ok());
+
+        let ir =
+            fs::read_to_string(config.build_folder.join(EmitKind::LlvmIr.file_name())).unwrap();
+
+        assert!(!ir.contains("DICompileUnit"));
+    }
+}
+
+#[cfg(test)]
+mod emit {
+    use super::*;
+    use crate::config::EmitKind;
+
+    // A folder is in the way of the emitted file
+    #[test]
+    fn cannot_write() {
+        let config = Config {
+            emit: vec![EmitKind::LlvmIr],
+            ..config("cannot_write")
+        };
+
+        let path = config.build_folder.join(EmitKind::LlvmIr.file_name());
+
+        fs::create_dir_all(&path).unwrap();
+
+        let (res, parsing_ctx) = generate(MAIN, &config);
+
+        let kind = parsing_ctx.diagnostics.list[0].get_kind();
+
+        assert!(res.is_err());
+        assert_eq!(kind.code(), "R0033");
+        assert!(
+            kind.message()
+                .starts_with(&format!("Cannot write {}: ", path.display())),
+            "{}",
+            kind.message()
+        );
+    }
+
+    #[test]
+    fn cannot_write_bitcode() {
+        let config = config("cannot_write_bitcode");
+
+        fs::create_dir_all(config.build_folder.join("out.bc")).unwrap();
+
+        let (res, parsing_ctx) = generate(MAIN, &config);
+
+        let kind = parsing_ctx.diagnostics.list[0].get_kind();
+
+        assert!(res.is_err());
+        assert_eq!(kind.code(), "R0033");
+    }
+}
@@ -1,6 +1,7 @@
 use ariadne::{Color, Label, Report, ReportKind, Source};
 use std::fmt::Display;
 
+use crate::helpers::edit_distance::find_best_match;
 use crate::parser::Parser;
 use crate::{diagnostics::DiagnosticType, parser::span::Span};
 use crate::{
@@ -10,24 +11,85 @@ use crate::{
 };
 use nom::error::{VerboseError, VerboseErrorKind};
 
-#[derive(Clone, Debug)]
+// A change of the code that the user may apply, like removing an unused item
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Suggestion {
+    pub message: String,
+    pub span: Span,
+    pub replacement: String,
+}
+
+impl Suggestion {
+    pub fn new_removal(message: &str, span: Span) -> Self {
+        Self {
+            message: message.to_string(),
+            span,
+            replacement: String::new(),
+        }
+    }
+
+    pub fn new_replacement(message: &str, span: Span, replacement: String) -> Self {
+        Self {
+            message: message.to_string(),
+            span,
+            replacement,
+        }
+    }
+
+    pub fn new_did_you_mean(span: Span, name: String) -> Self {
+        Self {
+            message: format!("Did you mean `{}`?", name),
+            span,
+            replacement: name,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Diagnostic {
     pub span: Span,
     kind: DiagnosticKind,
+    suggestions: Vec<Suggestion>,
 }
 
 impl Diagnostic {
     pub fn new(span: Span, kind: DiagnosticKind) -> Self {
-        Self { span, kind }
+        Self {
+            span,
+            kind,
+            suggestions: vec![],
+        }
     }
 
     pub fn new_empty() -> Self {
-        Self {
-            span: Span::new_placeholder(),
-            kind: DiagnosticKind::NoError,
+        Self::new(Span::new_placeholder(), DiagnosticKind::NoError)
+    }
+
+    pub fn with_suggestion(mut self, suggestion: Suggestion) -> Self {
+        self.suggestions.push(suggestion);
+
+        self
+    }
+
+    // Suggests the closest candidate in place of the span, when there is one
+    pub fn with_best_match<'a, I>(self, name: &str, candidates: I) -> Self
+    where
+        I: IntoIterator<Item = &'a String>,
+    {
+        match find_best_match(name, candidates) {
+            Some(best) => {
+                let span = self.span.clone();
+
+                self.with_suggestion(Suggestion::new_did_you_mean(span, best))
+            }
+            None => self,
         }
     }
 
+    pub fn suggestions(&self) -> &[Suggestion] {
+        &self.suggestions
+    }
+
     pub fn new_file_not_found(span: Span, path: String) -> Self {
         Self::new(span, DiagnosticKind::FileNotFound(path))
     }
@@ -44,10 +106,26 @@ impl Diagnostic {
         Self::new(span, DiagnosticKind::UnknownIdentifier)
     }
 
+    pub fn new_not_a_function(span: Span) -> Self {
+        Self::new(span, DiagnosticKind::NotAFunction)
+    }
+
     pub fn new_unused_function(span: Span) -> Self {
         Self::new(span, DiagnosticKind::UnusedFunction)
     }
 
+    pub fn new_unused_parameter(span: Span, name: String) -> Self {
+        Self::new(span, DiagnosticKind::UnusedParameter(name))
+    }
+
+    pub fn new_unused_variable(span: Span, name: String) -> Self {
+        Self::new(span, DiagnosticKind::UnusedVariable(name))
+    }
+
+    pub fn new_unused_import(span: Span, path: String) -> Self {
+        Self::new(span, DiagnosticKind::UnusedImport(path))
+    }
+
     pub fn new_module_not_found(span: Span, path: String) -> Self {
         Self::new(span, DiagnosticKind::ModuleNotFound(path))
     }
@@ -97,6 +175,37 @@ impl Diagnostic {
         Self::new(span, DiagnosticKind::SignatureMismatch(name, got, expected))
     }
 
+    pub fn new_unknown_lint(span: Span, name: String) -> Self {
+        Self::new(span, DiagnosticKind::UnknownLint(name))
+    }
+
+    pub fn new_unknown_pass(name: String) -> Self {
+        Self::new(Span::new_placeholder(), DiagnosticKind::UnknownPass(name))
+    }
+
+    pub fn new_link_error(msg: String) -> Self {
+        Self::new(Span::new_placeholder(), DiagnosticKind::LinkError(msg))
+    }
+
+    pub fn new_unsupported_target(msg: String) -> Self {
+        Self::new(
+            Span::new_placeholder(),
+            DiagnosticKind::UnsupportedTarget(msg),
+        )
+    }
+
+    pub fn new_emit_failed(msg: String) -> Self {
+        Self::new(Span::new_placeholder(), DiagnosticKind::EmitFailed(msg))
+    }
+
+    pub fn new_reuse_failed() -> Self {
+        Self::new(Span::new_placeholder(), DiagnosticKind::ReuseFailed)
+    }
+
+    pub fn new_unknown_type(span: Span, name: String) -> Self {
+        Self::new(span, DiagnosticKind::UnknownType(name))
+    }
+
     pub fn new_no_main() -> Self {
         Self::new(Span::new_placeholder(), DiagnosticKind::NoMain)
     }
@@ -105,12 +214,64 @@ impl Diagnostic {
         Self::new(span, DiagnosticKind::IsNotAPropertyOf(t, span2))
     }
 
+    pub fn new_missing_fields(span: Span, struct_name: String, fields: Vec<String>) -> Self {
+        Self::new(span, DiagnosticKind::MissingFields(struct_name, fields))
+    }
+
+    pub fn new_unknown_field(span: Span, field: String, struct_name: String) -> Self {
+        Self::new(span, DiagnosticKind::UnknownField(field, struct_name))
+    }
+
+    pub fn new_duplicated_field(span: Span, field: String, first: Span) -> Self {
+        Self::new(span, DiagnosticKind::DuplicatedField(field, first))
+    }
+
+    pub fn new_duplicated_function(span: Span, name: String, first: Span) -> Self {
+        Self::new(span, DiagnosticKind::DuplicatedFunction(name, first))
+    }
+
+    pub fn new_assign_to_const(span: Span, name: String) -> Self {
+        Self::new(span, DiagnosticKind::AssignToConst(name))
+    }
+
+    pub fn new_assign_to_newtype_value(span: Span, name: String) -> Self {
+        Self::new(span, DiagnosticKind::AssignToNewtypeValue(name))
+    }
+
+    pub fn new_unreachable_clause(span: Span) -> Self {
+        Self::new(span, DiagnosticKind::UnreachableClause)
+    }
+
+    pub fn new_non_exhaustive_clauses(span: Span, name: String) -> Self {
+        Self::new(span, DiagnosticKind::NonExhaustiveClauses(name))
+    }
+
+    pub fn new_runtime_error(span: Span, msg: &str) -> Self {
+        Self::new(span, DiagnosticKind::RuntimeError(msg.to_string()))
+    }
+
     pub fn new_type_conflict(span: Span, expected: Type, got: Type, in1: Type, in2: Type) -> Self {
         Self::new(span, DiagnosticKind::TypeConflict(expected, got, in1, in2))
     }
 
     pub fn print(&self, file: &SourceFile, diag_type: &DiagnosticType) {
-        self.kind.report_builder(file, &self.span, diag_type);
+        self.kind
+            .report_builder(file, &self.span, diag_type, &self.suggestions);
+    }
+
+    pub fn print_headline(&self, diag_type: &DiagnosticType) {
+        use colored::*;
+
+        let headline = match diag_type {
+            DiagnosticType::Error => format!("[{}] Error:", self.kind.code()).red(),
+            DiagnosticType::Warning => format!("[{}] Warning:", self.kind.code()).yellow(),
+        };
+
+        println!("{} {}", headline, self.kind.message());
+
+        for suggestion in &self.suggestions {
+            println!("  Help: {}", suggestion.message);
+        }
     }
 
     pub fn get_kind(&self) -> DiagnosticKind {
@@ -118,7 +279,7 @@ impl Diagnostic {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum DiagnosticKind {
     FileNotFound(String),
     UnexpectedToken,
@@ -126,7 +287,7 @@ pub enum DiagnosticKind {
     UnknownIdentifier,
     ModuleNotFound(String),
     NotAFunction,
-    UnusedParameter,
+    UnusedParameter(String),
     UnresolvedTraitCall {
         call_hir_id: HirId,
         given_sig: FuncType,
@@ -142,15 +303,189 @@ pub enum DiagnosticKind {
     OrphaneSignature(String),
     SignatureMismatch(String, FuncType, FuncType),
     NoMain,
-    NoError, //TODO: remove that
+    UnknownLint(String),
+    UnknownPass(String),
+    LinkError(String),
+    UnsupportedTarget(String),
+    EmitFailed(String),
+    ReuseFailed, // The functions of the previous build cannot be linked again
+    UnknownType(String),
+    DuplicatedFunction(String, Span), // name, first definition
+    UnusedVariable(String),
+    UnusedImport(String),
+    MissingFields(String, Vec<String>), // struct name, fields
+    UnknownField(String, String),       // field, struct name
+    DuplicatedField(String, Span),      // field, first occurrence
+    RuntimeError(String),               // Stops the interpreter
+    AssignToConst(String),
+    AssignToNewtypeValue(String), // newtype name
+    UnreachableClause,
+    NonExhaustiveClauses(String), // function name
+    NoError,                      //TODO: remove that
 }
 
 impl DiagnosticKind {
+    // The stable code of the kind, explained by `rock explain`
+    pub fn code(&self) -> &'static str {
+        match self {
+            DiagnosticKind::FileNotFound(_) => "R0001",
+            DiagnosticKind::UnexpectedToken => "R0002",
+            DiagnosticKind::SyntaxError(_) => "R0003",
+            DiagnosticKind::UnknownIdentifier => "R0004",
+            DiagnosticKind::ModuleNotFound(_) => "R0005",
+            DiagnosticKind::NotAFunction => "R0006",
+            DiagnosticKind::UnusedParameter(_) => "R0007",
+            DiagnosticKind::UnresolvedTraitCall { .. } => "R0008",
+            DiagnosticKind::UnusedFunction => "R0009",
+            DiagnosticKind::DuplicatedOperator => "R0010",
+            DiagnosticKind::TypeConflict(..) => "R0011",
+            DiagnosticKind::UnresolvedType(_) => "R0012",
+            DiagnosticKind::CodegenError(..) => "R0013",
+            DiagnosticKind::IsNotAPropertyOf(..) => "R0014",
+            DiagnosticKind::OutOfBounds(..) => "R0015",
+            DiagnosticKind::OrphaneSignature(_) => "R0016",
+            DiagnosticKind::SignatureMismatch(..) => "R0017",
+            DiagnosticKind::NoMain => "R0018",
+            DiagnosticKind::UnknownLint(_) => "R0019",
+            DiagnosticKind::UnusedVariable(_) => "R0020",
+            DiagnosticKind::UnusedImport(_) => "R0021",
+            DiagnosticKind::MissingFields(..) => "R0022",
+            DiagnosticKind::UnknownField(..) => "R0023",
+            DiagnosticKind::DuplicatedField(..) => "R0024",
+            DiagnosticKind::RuntimeError(_) => "R0025",
+            DiagnosticKind::AssignToConst(_) => "R0026",
+            DiagnosticKind::AssignToNewtypeValue(_) => "R0027",
+            DiagnosticKind::UnreachableClause => "R0028",
+            DiagnosticKind::NonExhaustiveClauses(_) => "R0029",
+            DiagnosticKind::UnknownPass(_) => "R0030",
+            DiagnosticKind::LinkError(_) => "R0031",
+            DiagnosticKind::UnsupportedTarget(_) => "R0032",
+            DiagnosticKind::EmitFailed(_) => "R0033",
+            DiagnosticKind::ReuseFailed => "R0034",
+            DiagnosticKind::UnknownType(_) => "R0035",
+            DiagnosticKind::DuplicatedFunction(..) => "R0036",
+            DiagnosticKind::NoError => "R0000", // Never reported
+        }
+    }
+
+    // The headline of the diagnostic
+    pub fn message(&self) -> String {
+        match self {
+            DiagnosticKind::FileNotFound(path) => format!("File not found: {}", path),
+            DiagnosticKind::UnexpectedToken => "Unexpected token".to_string(),
+            DiagnosticKind::SyntaxError(msg) => format!("Syntax error: {}", msg),
+            DiagnosticKind::UnknownIdentifier => "Unknown identifier".to_string(),
+            DiagnosticKind::ModuleNotFound(path) => format!("Module not found: {}", path),
+            DiagnosticKind::UnusedFunction => "Unused function".to_string(),
+            DiagnosticKind::UnusedParameter(name) => format!("Unused parameter: {}", name),
+            DiagnosticKind::UnusedVariable(name) => format!("Unused variable: {}", name),
+            DiagnosticKind::UnusedImport(path) => format!("Unused import: {}", path),
+            DiagnosticKind::UnresolvedTraitCall { .. } => format!("{}", self),
+            DiagnosticKind::UnresolvedType(t) => format!("Unresolved type: {}", t),
+            DiagnosticKind::CodegenError(_hir_id, msg) => format!("Codegen error: {}", msg),
+            DiagnosticKind::IsNotAPropertyOf(_t, _span2) => format!("{}", self),
+            DiagnosticKind::TypeConflict(_t1, _t2, _in1, _in2) => "Type conflict".to_string(),
+            DiagnosticKind::OutOfBounds(got, expected) => {
+                format!("Out of bounds: got {}, expected {}", got, expected)
+            }
+            DiagnosticKind::OrphaneSignature(name) => format!("Orpheline signature: {}", name),
+            DiagnosticKind::SignatureMismatch(name, _got, _expected) => {
+                format!("Signature mismatch: {}", name)
+            }
+            DiagnosticKind::NoMain => "No main function".to_string(),
+            DiagnosticKind::UnknownLint(name) => format!("Unknown lint: {}", name),
+            DiagnosticKind::UnknownPass(name) => format!("Unknown optimization pass: {}", name),
+            DiagnosticKind::LinkError(msg) => format!("Cannot link: {}", msg),
+            DiagnosticKind::UnsupportedTarget(msg) => format!("Unsupported target {}", msg),
+            DiagnosticKind::EmitFailed(msg) => format!("Cannot write {}", msg),
+            DiagnosticKind::ReuseFailed => {
+                "Cannot reuse the functions of the previous build".to_string()
+            }
+            DiagnosticKind::UnknownType(name) => format!("Unknown type `{}`", name),
+            DiagnosticKind::DuplicatedFunction(name, _first) => {
+                format!("Duplicated function: {}", name)
+            }
+            DiagnosticKind::MissingFields(struct_name, fields) => {
+                format!("Missing fields in {}: {}", struct_name, fields.join(", "))
+            }
+            DiagnosticKind::UnknownField(field, struct_name) => {
+                format!("Unknown field `{}` in {}", field, struct_name)
+            }
+            DiagnosticKind::DuplicatedField(field, _first) => {
+                format!("Duplicated field: {}", field)
+            }
+            DiagnosticKind::RuntimeError(msg) => format!("Runtime error: {}", msg),
+            DiagnosticKind::AssignToConst(name) => {
+                format!("Cannot assign twice to the constant `{}`", name)
+            }
+            DiagnosticKind::AssignToNewtypeValue(name) => {
+                format!("Cannot assign to the value of the newtype `{}`", name)
+            }
+            DiagnosticKind::UnreachableClause => "Unreachable clause".to_string(),
+            DiagnosticKind::NonExhaustiveClauses(name) => {
+                format!("Non-exhaustive clauses for `{}`", name)
+            }
+            DiagnosticKind::NoError => "No error".to_string(),
+            DiagnosticKind::DuplicatedOperator => "Duplicated operator".to_string(),
+            DiagnosticKind::NotAFunction => "Not a function".to_string(),
+        }
+    }
+
+    // The message attached to the span of the diagnostic
+    pub fn label(&self) -> String {
+        match self {
+            DiagnosticKind::UnresolvedTraitCall { .. } => "Unresolved trait call".to_string(),
+            _ => format!("{}", self),
+        }
+    }
+
+    // Other spans involved, with their own message
+    pub fn secondary_labels(&self) -> Vec<(Span, String)> {
+        match self {
+            DiagnosticKind::IsNotAPropertyOf(t, span2) => {
+                vec![(span2.clone(), format!("This is of type {:?}", t))]
+            }
+            DiagnosticKind::DuplicatedField(_field, first) => {
+                vec![(first.clone(), "First given here".to_string())]
+            }
+            DiagnosticKind::DuplicatedFunction(_name, first) => {
+                vec![(first.clone(), "First defined here".to_string())]
+            }
+            _ => vec![],
+        }
+    }
+
+    pub fn note(&self) -> Option<String> {
+        match self {
+            DiagnosticKind::UnresolvedTraitCall {
+                call_hir_id: _,
+                given_sig: _,
+                existing_impls,
+            } => Some(format!(
+                "Existing implementations ({}): {}{}",
+                existing_impls.len(),
+                existing_impls
+                    .iter()
+                    .take(3)
+                    .map(|t| format!("\n            - {:?}", t))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                if existing_impls.len() > 3 {
+                    "\n            - ..."
+                } else {
+                    ""
+                },
+            )),
+            _ => None,
+        }
+    }
+
     pub fn report_builder<'a>(
         &self,
         file: &SourceFile,
         span: &'a Span,
         diag_type: &DiagnosticType,
+        suggestions: &[Suggestion],
     ) {
         let filename = file.file_path.to_str().unwrap();
 
@@ -158,177 +493,52 @@ impl DiagnosticKind {
             DiagnosticType::Error => (ReportKind::Error, Color::Red),
             DiagnosticType::Warning => (ReportKind::Warning, Color::Yellow),
         };
-        let builder = Report::build(error_ty, filename, span.start);
+        let secondary_labels = self.secondary_labels();
+
+        // The source is printed as written, with its comments and without the header
+        let range = |span: &Span| {
+            let start = file.source_offset(span.start).unwrap_or(0);
+            let end = file.source_offset(span.end).unwrap_or(0);
+
+            start..end.max(start + 1)
+        };
 
-        let mut span = span.clone();
-        if span.start == span.end {
-            span.end += 1;
+        let mut builder = Report::build(error_ty, filename, range(span).start)
+            .with_code(self.code())
+            .with_message(self.message());
+
+        if let Some(note) = self.note() {
+            builder = builder.with_note(note);
         }
 
-        match self {
-            DiagnosticKind::FileNotFound(path) => builder
-                .with_message(format!("File not found: {}", path))
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
-            DiagnosticKind::UnexpectedToken => builder
-                .with_message("Unexpected token".to_string())
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
-            DiagnosticKind::SyntaxError(msg) => builder
-                .with_message(format!("Syntax error: {}", msg))
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
-            DiagnosticKind::UnknownIdentifier => builder
-                .with_message("Unknown identifier".to_string())
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
-            DiagnosticKind::ModuleNotFound(path) => builder
-                .with_message(format!("Module not found: {}", path))
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
-            DiagnosticKind::UnusedFunction => builder
-                .with_message("Unused function".to_string())
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
-            DiagnosticKind::UnusedParameter => builder
-                .with_message("Unused parameter".to_string())
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
-            DiagnosticKind::UnresolvedTraitCall {
-                call_hir_id: _,
-                given_sig: _,
-                existing_impls,
-            } => {
-                let note = &format!(
-                    "Existing implementations ({}): {}{}",
-                    existing_impls.len(),
-                    existing_impls
-                        .iter()
-                        .take(3)
-                        .map(|t| format!("\n            - {:?}", t))
-                        .collect::<Vec<String>>()
-                        .join(", "),
-                    if existing_impls.len() > 3 {
-                        "\n            - ..."
-                    } else {
-                        ""
-                    },
-                );
-                builder
-                    .with_message(format!("{}", self))
-                    .with_note(note)
-                    .with_label(
-                        Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                            .with_message("Unresolved trait call")
-                            .with_color(color),
-                    )
-            }
-            DiagnosticKind::UnresolvedType(t) => builder
-                .with_message(format!("Unresolved type: {}", t.to_string()))
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
-            DiagnosticKind::CodegenError(_hir_id, msg) => builder
-                .with_message(format!("Codegen error: {}", msg))
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
-            DiagnosticKind::IsNotAPropertyOf(t, span2) => builder
-                .with_message(format!("{}", self,))
-                .with_label(
-                    Label::new((span2.file_path.to_str().unwrap(), span2.start..span2.end))
-                        .with_message(format!("This is of type {:?}", t))
-                        .with_color(Color::Blue),
-                )
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
-            DiagnosticKind::TypeConflict(_t1, _t2, _in1, _in2) => {
-                // add spans here
-                builder.with_message("Type conflict").with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                )
-            }
-            DiagnosticKind::OutOfBounds(got, expected) => builder
-                .with_message(format!("Out of bounds: got {}, expected {}", got, expected))
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
-            DiagnosticKind::OrphaneSignature(name) => builder
-                .with_message(format!("Orpheline signature: {}", name))
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
-            DiagnosticKind::SignatureMismatch(name, _got, _expected) => builder
-                .with_message(format!("Signature mismatch: {}", name,))
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
-            DiagnosticKind::NoMain => builder
-                .with_message("No main function".to_string())
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
-            DiagnosticKind::NoError => builder.with_message("No error".to_string()).with_label(
-                Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                    .with_message(format!("{}", self))
-                    .with_color(color),
-            ),
-            DiagnosticKind::DuplicatedOperator => builder
-                .with_message("Duplicated operator".to_string())
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
-            DiagnosticKind::NotAFunction => builder
-                .with_message("Not a function".to_string())
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
+        // A report has a single help, the suggestions are the alternatives to choose from
+        if !suggestions.is_empty() {
+            builder = builder.with_help(
+                suggestions
+                    .iter()
+                    .map(|suggestion| suggestion.message.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n      Or: "),
+            );
         }
-        .finish()
-        .print((filename, Source::from(file.content.clone())))
-        .unwrap();
+
+        for (span2, msg) in &secondary_labels {
+            builder = builder.with_label(
+                Label::new((span2.file_path.to_str().unwrap(), range(span2)))
+                    .with_message(msg)
+                    .with_color(Color::Blue),
+            );
+        }
+
+        builder
+            .with_label(
+                Label::new((span.file_path.to_str().unwrap(), range(span)))
+                    .with_message(self.label())
+                    .with_color(color),
+            )
+            .finish()
+            .print((filename, Source::from(file.source.clone())))
+            .unwrap();
     }
 }
 
@@ -369,7 +579,9 @@ impl Display for DiagnosticKind {
                 got, expected
             ),
             DiagnosticKind::NotAFunction => "NotAFunction".to_string(),
-            DiagnosticKind::UnusedParameter => "UnusedParameter".to_string(),
+            DiagnosticKind::UnusedParameter(_name) => "UnusedParameter".to_string(),
+            DiagnosticKind::UnusedVariable(_name) => "UnusedVariable".to_string(),
+            DiagnosticKind::UnusedImport(_path) => "UnusedImport".to_string(),
             DiagnosticKind::UnusedFunction => "UnusedFunction".to_string(),
             DiagnosticKind::OrphaneSignature(_name) => "OrphelineSignature".to_string(),
             DiagnosticKind::SignatureMismatch(_name, got, expected) => {
@@ -382,6 +594,30 @@ impl Display for DiagnosticKind {
                 )
             }
             DiagnosticKind::NoMain => "NoMain".to_string(),
+            DiagnosticKind::UnknownLint(_name) => "UnknownLint".to_string(),
+            DiagnosticKind::UnknownPass(_name) => "UnknownPass".to_string(),
+            DiagnosticKind::LinkError(msg) => format!("Cannot link: {}", msg),
+            DiagnosticKind::UnsupportedTarget(msg) => format!("Unsupported target {}", msg),
+            DiagnosticKind::EmitFailed(msg) => format!("Cannot write {}", msg),
+            DiagnosticKind::ReuseFailed => "ReuseFailed".to_string(),
+            DiagnosticKind::UnknownType(name) => format!("No type `{}` in scope", name),
+            DiagnosticKind::DuplicatedFunction(_name, _first) => "Defined again here".to_string(),
+            DiagnosticKind::MissingFields(_struct_name, fields) => {
+                format!("Missing {}", fields.join(", "))
+            }
+            DiagnosticKind::UnknownField(_field, struct_name) => {
+                format!("Not a field of {}", struct_name)
+            }
+            DiagnosticKind::DuplicatedField(_field, _first) => "Given again here".to_string(),
+            DiagnosticKind::RuntimeError(msg) => msg.clone(),
+            DiagnosticKind::AssignToConst(_name) => "Assigned here".to_string(),
+            DiagnosticKind::AssignToNewtypeValue(_name) => "Assigned here".to_string(),
+            DiagnosticKind::UnreachableClause => {
+                "The previous clauses match every argument of this one".to_string()
+            }
+            DiagnosticKind::NonExhaustiveClauses(_name) => {
+                "Some arguments match none of the clauses".to_string()
+            }
             DiagnosticKind::NoError => "NoError".to_string(),
             DiagnosticKind::IsNotAPropertyOf(t, _span2) => {
                 format!("Not a property of {:?}", t)
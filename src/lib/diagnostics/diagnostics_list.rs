@@ -1,4 +1,7 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
 
 use nom::error::VerboseError;
 
@@ -6,19 +9,28 @@ use crate::parser::{Parser, SourceFile};
 
 use super::Diagnostic;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DiagnosticType {
     Warning,
     Error,
 }
 
+impl DiagnosticType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagnosticType::Warning => "warning",
+            DiagnosticType::Error => "error",
+        }
+    }
+}
+
 impl Default for DiagnosticType {
     fn default() -> Self {
         DiagnosticType::Error
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Diagnostics {
     pub list: Vec<Diagnostic>,
     pub list_types: Vec<DiagnosticType>,
@@ -46,6 +58,12 @@ impl Diagnostics {
         for (i, diag) in self.list.iter().enumerate() {
             let input = match files.get(&diag.span.file_path) {
                 Some(input) => input,
+                // Not tied to any file, like a missing `main`
+                None if diag.span.file_path.as_os_str().is_empty() => {
+                    diag.print_headline(self.list_types.get(i).unwrap());
+
+                    continue;
+                }
                 None => {
                     println!("DIAG FILE {:#?}", diag.span.file_path);
                     warn!("Diagnostic has been silenced because the file is not found");
@@ -67,28 +85,36 @@ impl Diagnostics {
 
 impl<'a> From<VerboseError<Parser<'a>>> for Diagnostics {
     fn from(err: VerboseError<Parser<'a>>) -> Self {
-        let mut diags = err
-            .errors
-            .clone()
-            .into_iter()
-            .take(1)
-            .map(Diagnostic::from)
-            .collect::<Vec<_>>();
-
-        let diags2 = err
-            .errors
-            .into_iter()
-            .take(1)
-            .map(|(input, _kind)| input.extra.diagnostics().list)
-            .flatten()
-            .collect::<Vec<_>>();
-
-        diags.extend(diags2);
-
         let mut list = Diagnostics::default();
 
-        for diag in diags {
-            list.push_error(diag);
+        let mut seen = HashSet::new();
+
+        // Every failing input holds the diagnostics recovered before it, with their own type
+        for (input, _kind) in &err.errors {
+            let recovered = input.extra.diagnostics();
+
+            for (diag, diag_type) in recovered.list.into_iter().zip(recovered.list_types) {
+                let key = (
+                    diag.span.file_path.clone(),
+                    diag.span.start,
+                    diag.span.end,
+                    diag.get_kind().message(),
+                );
+
+                if !seen.insert(key) {
+                    continue;
+                }
+
+                match diag_type {
+                    DiagnosticType::Error => list.push_error(diag),
+                    DiagnosticType::Warning => list.push_warning(diag),
+                }
+            }
+        }
+
+        // The failure itself, the other errors are the contexts it went through
+        if let Some(first) = err.errors.into_iter().next() {
+            list.push_error(Diagnostic::from(first));
         }
 
         list
@@ -0,0 +1,625 @@
+// Extended explanations of the diagnostics, shown by `rock explain <code>`.
+// The codes are stable: a code is never reused for another kind of diagnostic.
+
+#[derive(Debug, Clone, Copy)]
+pub struct Explanation {
+    pub code: &'static str,
+    pub title: &'static str,
+    pub text: &'static str,
+}
+
+pub fn explain(code: &str) -> Option<&'static Explanation> {
+    EXPLANATIONS
+        .iter()
+        .find(|explanation| explanation.code.eq_ignore_ascii_case(code))
+}
+
+pub static EXPLANATIONS: &[Explanation] = &[
+    Explanation {
+        code: "R0001",
+        title: "File not found",
+        text: r#"A source file could not be read.
+
+The entry point of a project is `src/main.rk`, relative to the folder that
+contains its `Rock.toml`. This error is emitted when it is missing:
+
+    my_project/
+      Rock.toml
+      src/
+        mian.rk
+
+Rename the file to `src/main.rk`, or create it.
+"#,
+    },
+    Explanation {
+        code: "R0002",
+        title: "Unexpected token",
+        text: r#"The parser found a token that cannot appear at this position.
+
+Check the line pointed by the diagnostic for a misplaced character, and see
+R0003 for the other syntax errors.
+"#,
+    },
+    Explanation {
+        code: "R0003",
+        title: "Syntax error",
+        text: r#"The code does not follow the grammar of Rock.
+
+Erroneous code example:
+
+    main: -> 2 +* )
+
+The parser resumes on the next top level item or the next statement, so every
+syntax error of the project is reported before the compilation stops.
+
+Fix the expression:
+
+    main: -> 2 + 2
+"#,
+    },
+    Explanation {
+        code: "R0004",
+        title: "Unknown identifier",
+        text: r#"A name is used but is not declared in the current scope.
+
+Erroneous code example:
+
+    main: -> foo 2
+
+Declare it, or bring it into scope with a `use`:
+
+    foo: x -> x
+
+    main: -> foo 2
+"#,
+    },
+    Explanation {
+        code: "R0005",
+        title: "Module not found",
+        text: r#"A `mod` or a `use` refers to a module that does not exist.
+
+Erroneous code example, with no `src/utils.rk` file:
+
+    mod utils
+
+    main: -> 0
+
+A module `utils` declared in `src/main.rk` is read from `src/utils.rk`.
+Create the file, or fix the name of the module.
+"#,
+    },
+    Explanation {
+        code: "R0006",
+        title: "Not a function",
+        text: r#"A value that is not a function is called with arguments.
+
+Erroneous code example:
+
+    main: ->
+      let a = 2
+      a 3
+
+Only functions can be applied to arguments. Remove the arguments, or call a
+function instead.
+"#,
+    },
+    Explanation {
+        code: "R0007",
+        title: "Unused parameter",
+        text: r#"A parameter of a function is never used in its body.
+
+Example:
+
+    f: a, b -> a
+
+    main: -> f 1, 2
+
+Remove the parameter if it is not needed. A parameter that must stay, to
+match the signature of a trait for example, can be prefixed with `_`:
+
+    f: a, _b -> a
+
+The warning can also be silenced with the `unused-parameter` lint.
+"#,
+    },
+    Explanation {
+        code: "R0008",
+        title: "Unresolved trait call",
+        text: r#"A trait method is called with argument types that have no implementation.
+
+Erroneous code example:
+
+    trait Double
+      double: @ => @
+
+    impl Double Int64
+      double: x -> x + x
+
+    use Double::(*)
+
+    main: -> double 2.2
+
+`double` is only implemented for `Int64`, but it is called with a `Float64`.
+The note of the diagnostic lists the existing implementations. Implement the
+trait for the type:
+
+    impl Double Float64
+      double: x -> x + x
+"#,
+    },
+    Explanation {
+        code: "R0009",
+        title: "Unused function",
+        text: r#"A function is never called from `main`.
+
+Example:
+
+    unused: x -> x
+
+    main: -> 0
+
+Only the functions reachable from `main` are type checked and generated.
+Remove the function, or call it. The warning can also be silenced with the
+`unused-function` lint:
+
+    @[allow(unused-function)]
+    unused: x -> x
+"#,
+    },
+    Explanation {
+        code: "R0010",
+        title: "Duplicated operator",
+        text: r#"An infix operator is declared more than once.
+
+Erroneous code example:
+
+    infix + 4
+    infix + 5
+
+An operator has a single precedence. Remove one of the declarations.
+"#,
+    },
+    Explanation {
+        code: "R0011",
+        title: "Type conflict",
+        text: r#"An expression has a different type than the one expected at its position.
+
+Erroneous code example:
+
+    struct Foo
+      toto: Int64
+
+    main: ->
+      let foo = Foo
+        toto: 10.2
+      foo.toto
+
+The field `toto` is declared as an `Int64`, but it is given a `Float64`.
+Give a value of the expected type:
+
+    main: ->
+      let foo = Foo
+        toto: 10
+      foo.toto
+"#,
+    },
+    Explanation {
+        code: "R0012",
+        title: "Unresolved type",
+        text: r#"The type of an expression could not be inferred.
+
+Every type must be known after the inference. This happens when nothing
+constrains a value, for example an empty array that is never filled. Use the
+value in a way that fixes its type.
+"#,
+    },
+    Explanation {
+        code: "R0013",
+        title: "Codegen error",
+        text: r#"The compiler failed to generate the code of an expression.
+
+This is a bug in the compiler. Please report it with the code that triggers it.
+"#,
+    },
+    Explanation {
+        code: "R0014",
+        title: "Not a property",
+        text: r#"A field or a method is accessed on a type that does not have it.
+
+Erroneous code example:
+
+    main: ->
+      let a = 2
+      a.foo
+
+`Int64` has no field nor method `foo`. Check the name, or implement a trait
+that provides the method for this type.
+"#,
+    },
+    Explanation {
+        code: "R0015",
+        title: "Out of bounds",
+        text: r#"An array is indexed past its end.
+
+Erroneous code example:
+
+    main: ->
+      let arr = [1, 2, 3]
+      arr[5]
+
+The indices of an array of length 3 go from 0 to 2.
+"#,
+    },
+    Explanation {
+        code: "R0016",
+        title: "Orphan signature",
+        text: r#"A function signature has no function definition.
+
+Erroneous code example:
+
+    f: Int64 => Int64
+
+    main: -> 0
+
+Define the function right after its signature:
+
+    f: Int64 => Int64
+    f: x -> x
+
+    main: -> f 2
+"#,
+    },
+    Explanation {
+        code: "R0017",
+        title: "Signature mismatch",
+        text: r#"A function does not match its declared signature.
+
+Erroneous code example:
+
+    f: Int64 => Float64
+    f: a -> a
+
+    main: -> f 2.2
+
+`f` is declared to take an `Int64` and to return a `Float64`, but it returns
+its argument and is called with a `Float64`. Fix the signature:
+
+    f: Float64 => Float64
+    f: a -> a
+
+    main: -> f 2.2
+"#,
+    },
+    Explanation {
+        code: "R0018",
+        title: "No main function",
+        text: r#"The entry point of the project has no `main` function.
+
+Erroneous code example:
+
+    foo: -> 0
+
+The types are inferred from `main`, so every project needs one, even the
+libraries:
+
+    main: -> 0
+"#,
+    },
+    Explanation {
+        code: "R0019",
+        title: "Unknown lint",
+        text: r#"A lint annotation names a lint that does not exist.
+
+Erroneous code example:
+
+    @[allow(unused-fn)]
+    helper: x -> x
+
+    main: -> 0
+
+The lints are `unused-function`, `unused-parameter`, `unused-variable`,
+`unused-import` and `unreachable-clause`, and the `warnings` group that contains
+every lint that warns.
+Fix the name:
+
+    @[allow(unused-function)]
+    helper: x -> x
+
+    main: -> 0
+"#,
+    },
+    Explanation {
+        code: "R0020",
+        title: "Unused variable",
+        text: r#"A variable declared with `let` is never read.
+
+Example:
+
+    main: ->
+      let a = 2
+      let b = 3
+      a
+
+Remove the declaration if it is not needed. The warning can also be silenced
+with the `unused-variable` lint.
+"#,
+    },
+    Explanation {
+        code: "R0021",
+        title: "Unused import",
+        text: r#"Nothing that a `use` brings into scope is used.
+
+Example:
+
+    mod foo
+
+    use foo::bar
+
+    main: -> 0
+
+Remove the `use`. The warning can also be silenced with the `unused-import`
+lint:
+
+    @[allow(unused-import)]
+    use foo::bar
+"#,
+    },
+    Explanation {
+        code: "R0022",
+        title: "Missing fields",
+        text: r#"A struct constructor does not give a value to every field of the struct.
+
+Erroneous code example:
+
+    struct Point
+      x: Int64
+      y: Int64
+
+    main: ->
+      let p = Point
+        x: 1
+      p.x
+
+Every field must be initialized. Give the missing ones:
+
+    main: ->
+      let p = Point
+        x: 1
+        y: 2
+      p.x
+"#,
+    },
+    Explanation {
+        code: "R0023",
+        title: "Unknown field",
+        text: r#"A struct constructor gives a field that the struct does not declare.
+
+Erroneous code example:
+
+    struct Point
+      x: Int64
+
+    main: ->
+      let p = Point
+        x: 1
+        z: 2
+      p.x
+
+Remove the field, or fix its name. The closest field is suggested when there
+is one.
+"#,
+    },
+    Explanation {
+        code: "R0024",
+        title: "Duplicated field",
+        text: r#"A struct constructor gives the same field more than once.
+
+Erroneous code example:
+
+    struct Point
+      x: Int64
+
+    main: ->
+      let p = Point
+        x: 1
+        x: 2
+      p.x
+
+Keep a single value for the field.
+"#,
+    },
+    Explanation {
+        code: "R0025",
+        title: "Runtime error",
+        text: r#"The interpreter of `rock run --interp` stopped on an operation that has no
+defined result, like a division by zero or an index out of the bounds of an array,
+or on a call to an extern that it cannot run.
+
+Erroneous code example:
+
+    main: ->
+      let a = 1
+      let b = 0
+      ~IDiv a b
+
+The compiled program would crash or behave unpredictably on the same code.
+"#,
+    },
+    Explanation {
+        code: "R0026",
+        title: "Cannot assign twice to a constant",
+        text: r#"A constant is bound once, to the value of its initializer, and cannot be
+assigned again.
+
+Erroneous code example:
+
+    const max_len: Int64 = 4096
+
+    main: ->
+      max_len = 8192
+      max_len
+
+Use `let` to declare a new variable that shadows the constant instead. The
+elements of a constant array and the fields of a constant struct can still be
+assigned.
+"#,
+    },
+    Explanation {
+        code: "R0027",
+        title: "Cannot assign to the value of a newtype",
+        text: r#"A newtype has the same representation as the type that it wraps, so its
+`.0` is the newtype itself and not a field that can be assigned.
+
+Erroneous code example:
+
+    newtype UserId = Int64
+
+    main: ->
+      let id = UserId 1
+      id.0 = 2
+      id.0
+
+Build a new value instead, like `id = UserId 2`.
+"#,
+    },
+    Explanation {
+        code: "R0028",
+        title: "Unreachable clause",
+        text: r#"The previous clauses of a function match every argument that this clause
+matches, so it is never chosen. The clauses are tried in order, and a clause
+with a `| guard` never hides the next ones, as its guard may be false.
+
+Example:
+
+    fact: n -> n * fact (n - 1)
+    fact: 0 -> 1
+
+    main: -> fact 5
+
+Put the most specific clauses first:
+
+    fact: 0 -> 1
+    fact: n -> n * fact (n - 1)
+
+The warning can also be silenced with the `unreachable-clause` lint.
+"#,
+    },
+    Explanation {
+        code: "R0029",
+        title: "Non-exhaustive clauses",
+        text: r#"Some arguments of a function defined by clauses match none of them, and the
+function would have no value to return.
+
+Erroneous code example:
+
+    is_zero: 0 -> true
+
+    main: -> is_zero 1
+
+The guards are not taken into account, as they may all be false. End with a
+clause that binds every argument:
+
+    is_zero: 0 -> true
+    is_zero: _ -> false
+"#,
+    },
+    Explanation {
+        code: "R0030",
+        title: "Unknown optimization pass",
+        text: r#"The `--passes` option names a pass that does not exist.
+
+Erroneous example:
+
+    rock build --passes mem2reg,inst-combine
+
+The passes have the names of LLVM's `opt`, like `mem2reg`, `instcombine`,
+`simplifycfg`, `gvn` or `inline`. Fix the name:
+
+    rock build --passes mem2reg,instcombine
+"#,
+    },
+    Explanation {
+        code: "R0031",
+        title: "Cannot link",
+        text: r#"The object file was generated, but the linker could not make the executable
+or the library out of it.
+
+The executables and the shared libraries are linked with `clang`, and the
+static libraries are archived with `ar`. Check that they are installed and in
+the `PATH`, and that the linker supports the `--target` of the build.
+"#,
+    },
+    Explanation {
+        code: "R0032",
+        title: "Unsupported target",
+        text: r#"The `--target` option gives a triple that the LLVM linked with the compiler
+cannot generate code for.
+
+Erroneous example:
+
+    rock build --target foo-bar-baz
+
+The targets that can be used are listed by `rock targets`, like
+`x86_64-unknown-linux-gnu` or `aarch64-unknown-linux-gnu`.
+"#,
+    },
+    Explanation {
+        code: "R0033",
+        title: "Cannot write an output",
+        text: r#"The code was generated, but one of the outputs asked by `--emit` could not be
+written in the build folder.
+
+Check that the build folder can be written and that the disk is not full.
+"#,
+    },
+    Explanation {
+        code: "R0034",
+        title: "Cannot reuse the previous build",
+        text: r#"The incremental build could not link the functions of the unchanged modules,
+kept from the previous build, with the newly generated ones.
+
+The cache of the build folder is likely stale or corrupted. Remove the build
+folder to start again from a clean build.
+"#,
+    },
+    Explanation {
+        code: "R0035",
+        title: "Unknown type",
+        text: r#"A type is neither a primitive type, a struct, a trait, nor a type alias or a
+newtype of the module.
+
+Erroneous code example:
+
+    mod units
+
+    total: Meters => Int64
+
+The aliases and the newtypes of another module must be imported, like the
+functions:
+
+    mod units
+
+    use units::Meters
+
+    total: Meters => Int64
+"#,
+    },
+    Explanation {
+        code: "R0036",
+        title: "Duplicated function",
+        text: r#"A module defines the same function more than once.
+
+Erroneous code example:
+
+    answer: -> 41
+
+    answer: -> 42
+
+Only the functions with several clauses, that match literals or have a guard,
+are defined more than once:
+
+    fact: 0 -> 1
+    fact: n -> n * fact (n - 1)
+"#,
+    },
+];
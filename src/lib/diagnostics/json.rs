@@ -0,0 +1,140 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use regex::Regex;
+
+use crate::parser::{SourceFile, Span};
+
+use super::{Diagnostic, DiagnosticType, Diagnostics};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonSpan {
+    pub file: String,
+    pub byte_start: usize,
+    pub byte_end: usize,
+    // The positions are unknown when the file of the span has not been loaded
+    pub line_start: Option<usize>,
+    pub column_start: Option<usize>,
+    pub line_end: Option<usize>,
+    pub column_end: Option<usize>,
+}
+
+impl JsonSpan {
+    pub fn new(span: &Span, files: &HashMap<PathBuf, SourceFile>) -> Self {
+        let file = files.get(&span.file_path);
+
+        let (start, end) = match file {
+            Some(file) => (
+                Some(file.line_col(span.start)),
+                Some(file.line_col(span.end)),
+            ),
+            None => (None, None),
+        };
+
+        // The offsets in the file as written, without the header prepended to its content
+        let byte = |offset: usize| {
+            file.map(|file| file.source_offset(offset).unwrap_or(0))
+                .unwrap_or(offset)
+        };
+
+        Self {
+            file: span.file_path.to_string_lossy().to_string(),
+            byte_start: byte(span.start),
+            byte_end: byte(span.end),
+            line_start: start.map(|(line, _)| line),
+            column_start: start.map(|(_, col)| col),
+            line_end: end.map(|(line, _)| line),
+            column_end: end.map(|(_, col)| col),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonLabel {
+    pub span: JsonSpan,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonSuggestion {
+    pub message: String,
+    pub span: JsonSpan,
+    // Empty for a removal
+    pub replacement: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonDiagnostic {
+    pub severity: String,
+    pub code: String,
+    pub message: String,
+    pub span: JsonSpan,
+    pub label: String,
+    pub secondary_labels: Vec<JsonLabel>,
+    pub note: Option<String>,
+    pub suggestions: Vec<JsonSuggestion>,
+}
+
+impl JsonDiagnostic {
+    pub fn new(
+        diag: &Diagnostic,
+        diag_type: &DiagnosticType,
+        files: &HashMap<PathBuf, SourceFile>,
+    ) -> Self {
+        let kind = diag.get_kind();
+
+        Self {
+            severity: diag_type.as_str().to_string(),
+            code: kind.code().to_string(),
+            message: strip_colors(&kind.message()),
+            span: JsonSpan::new(&diag.span, files),
+            label: strip_colors(&kind.label()),
+            secondary_labels: kind
+                .secondary_labels()
+                .iter()
+                .map(|(span, message)| JsonLabel {
+                    span: JsonSpan::new(span, files),
+                    message: strip_colors(message),
+                })
+                .collect(),
+            note: kind.note(),
+            suggestions: diag
+                .suggestions()
+                .iter()
+                .map(|suggestion| JsonSuggestion {
+                    message: suggestion.message.clone(),
+                    span: JsonSpan::new(&suggestion.span, files),
+                    replacement: suggestion.replacement.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    // One line of the `--message-format=json` output
+    pub fn to_json_line(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+impl Diagnostics {
+    pub fn to_json(&self, files: &HashMap<PathBuf, SourceFile>) -> Vec<JsonDiagnostic> {
+        self.list
+            .iter()
+            .zip(self.list_types.iter())
+            .map(|(diag, diag_type)| JsonDiagnostic::new(diag, diag_type, files))
+            .collect()
+    }
+
+    pub fn print_json(&self, files: &HashMap<PathBuf, SourceFile>) {
+        for diag in self.to_json(files) {
+            println!("{}", diag.to_json_line());
+        }
+    }
+}
+
+// Some messages are colored for the terminal
+pub(crate) fn strip_colors(s: &str) -> String {
+    Regex::new(r"\x1b\[[0-9;]*m")
+        .unwrap()
+        .replace_all(s, "")
+        .to_string()
+}
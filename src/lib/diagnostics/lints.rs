@@ -0,0 +1,126 @@
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+};
+
+// The diagnostics whose level can be chosen by the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Lint {
+    UnusedFunction,
+    UnusedParameter,
+    UnusedVariable,
+    UnusedImport,
+    UnreachableClause,
+}
+
+impl Lint {
+    pub fn all() -> &'static [Lint] {
+        &[
+            Lint::UnusedFunction,
+            Lint::UnusedParameter,
+            Lint::UnusedVariable,
+            Lint::UnusedImport,
+            Lint::UnreachableClause,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::UnusedFunction => "unused-function",
+            Lint::UnusedParameter => "unused-parameter",
+            Lint::UnusedVariable => "unused-variable",
+            Lint::UnusedImport => "unused-import",
+            Lint::UnreachableClause => "unreachable-clause",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::all().iter().find(|lint| lint.name() == name).copied()
+    }
+
+    pub fn default_level(&self) -> LintLevel {
+        match self {
+            Lint::UnusedFunction
+            | Lint::UnusedParameter
+            | Lint::UnusedVariable
+            | Lint::UnusedImport
+            | Lint::UnreachableClause => LintLevel::Warn,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl LintLevel {
+    pub fn parse(level: &str) -> Option<Self> {
+        match level {
+            "allow" => Some(Self::Allow),
+            "warn" => Some(Self::Warn),
+            "deny" => Some(Self::Deny),
+            _ => None,
+        }
+    }
+}
+
+// The `warnings` group applies to every lint that would otherwise warn
+pub static WARNINGS_GROUP: &str = "warnings";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LintLevels {
+    lints: HashMap<Lint, LintLevel>,
+    warnings: Option<LintLevel>,
+}
+
+impl LintLevels {
+    pub fn set(&mut self, name: &str, level: LintLevel) -> Result<(), String> {
+        if name == WARNINGS_GROUP {
+            self.warnings = Some(level);
+        } else {
+            let lint = Lint::parse(name).ok_or_else(|| format!("Unknown lint: {}", name))?;
+
+            self.lints.insert(lint, level);
+        }
+
+        Ok(())
+    }
+
+    // The levels of `other` win over ours
+    pub fn extend(&mut self, other: &LintLevels) {
+        self.lints.extend(other.lints.clone());
+        self.warnings = other.warnings.or(self.warnings);
+    }
+
+    pub fn level(&self, lint: Lint) -> LintLevel {
+        let level = self
+            .lints
+            .get(&lint)
+            .copied()
+            .unwrap_or_else(|| lint.default_level());
+
+        match level {
+            LintLevel::Warn => self.warnings.unwrap_or(LintLevel::Warn),
+            level => level,
+        }
+    }
+}
+
+// Sorted by name, as the iteration order of the map is random
+impl Hash for LintLevels {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut lints = self
+            .lints
+            .iter()
+            .map(|(lint, level)| (lint.name(), *level))
+            .collect::<Vec<_>>();
+
+        lints.sort_by_key(|(name, _)| *name);
+
+        lints.hash(state);
+        self.warnings.hash(state);
+    }
+}
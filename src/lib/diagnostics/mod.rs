@@ -1,5 +1,14 @@
 mod diagnostic;
 mod diagnostics_list;
+mod explain;
+mod json;
+mod lints;
 
 pub use diagnostic::*;
 pub use diagnostics_list::*;
+pub use explain::*;
+pub use json::*;
+pub use lints::*;
+
+#[cfg(test)]
+mod tests;
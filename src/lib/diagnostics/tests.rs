@@ -0,0 +1,240 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::PathBuf,
+};
+
+use super::*;
+use crate::{
+    parser::{ParsingCtx, SourceFile},
+    ty::Type,
+    Config,
+};
+
+// The diagnostics of a program without the std, with the files they point to
+fn check(file: SourceFile) -> (Diagnostics, HashMap<PathBuf, SourceFile>) {
+    let config = Config {
+        quiet: true,
+        ..Config::default()
+    };
+
+    let mut file = file;
+
+    file.mod_path = PathBuf::from("root");
+
+    let mut parsing_ctx = ParsingCtx::new(&config);
+
+    parsing_ctx.add_file(&file);
+
+    let _ = crate::parse_str(&mut parsing_ctx, &config);
+
+    (parsing_ctx.diagnostics, parsing_ctx.files)
+}
+
+// The suggestions of every diagnostic
+fn suggestions(source: &str) -> Vec<String> {
+    check(SourceFile::from_str("main.rk", source).unwrap())
+        .0
+        .list
+        .iter()
+        .flat_map(|diag| diag.suggestions().iter().map(|s| s.message.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod explain {
+    use super::*;
+
+    #[test]
+    fn unique_codes() {
+        let codes = EXPLANATIONS
+            .iter()
+            .map(|explanation| explanation.code)
+            .collect::<HashSet<_>>();
+
+        assert_eq!(codes.len(), EXPLANATIONS.len());
+    }
+
+    #[test]
+    fn find_code() {
+        let explanation = explain("r0011").unwrap();
+
+        assert_eq!(explanation.code, "R0011");
+
+        let kind = DiagnosticKind::TypeConflict(
+            Type::int64(),
+            Type::float64(),
+            Type::int64(),
+            Type::float64(),
+        );
+
+        assert_eq!(explanation.code, kind.code());
+    }
+
+    #[test]
+    fn unknown_code() {
+        assert!(explain("R9999").is_none());
+    }
+}
+
+#[cfg(test)]
+mod lint_levels {
+    use super::*;
+
+    #[test]
+    fn default_level() {
+        let levels = LintLevels::default();
+
+        assert_eq!(levels.level(Lint::UnusedFunction), LintLevel::Warn);
+    }
+
+    #[test]
+    fn deny_warnings() {
+        let mut levels = LintLevels::default();
+
+        levels.set(WARNINGS_GROUP, LintLevel::Deny).unwrap();
+
+        assert_eq!(levels.level(Lint::UnusedFunction), LintLevel::Deny);
+
+        levels.set("unused-function", LintLevel::Allow).unwrap();
+
+        assert_eq!(levels.level(Lint::UnusedFunction), LintLevel::Allow);
+    }
+
+    #[test]
+    fn item_overrides_project() {
+        let mut project = LintLevels::default();
+        let mut item = LintLevels::default();
+
+        project.set("unused-function", LintLevel::Deny).unwrap();
+        item.set("unused-function", LintLevel::Allow).unwrap();
+
+        project.extend(&item);
+
+        assert_eq!(project.level(Lint::UnusedFunction), LintLevel::Allow);
+    }
+
+    #[test]
+    fn unknown_lint() {
+        assert!(LintLevels::default()
+            .set("unused-fn", LintLevel::Allow)
+            .is_err());
+    }
+}
+
+#[cfg(test)]
+mod suggestions {
+    use super::*;
+
+    #[test]
+    fn identifier() {
+        let source = "counter: x -> x\nmain: -> countr 2\n";
+
+        assert_eq!(suggestions(source), vec!["Did you mean `counter`?"]);
+    }
+
+    // The operators are not identifiers, `-` is not a typo of `n`
+    #[test]
+    fn operator() {
+        let source = "f: n -> n - 1\nmain: -> f 2\n";
+
+        assert_eq!(suggestions(source), Vec::<String>::new());
+    }
+
+    #[test]
+    fn short_name() {
+        let source = "a: x -> x\nmain: -> b 2\n";
+
+        assert_eq!(suggestions(source), Vec::<String>::new());
+    }
+
+    // `xpos` is as close, but it is already given
+    #[test]
+    fn missing_field() {
+        let source = "struct P\n  xpos: Int64\n  ypos: Int64\n\nmain: ->\n  let p = P\n    xpos: 1\n    zpos: 2\n  p.xpos\n";
+
+        assert_eq!(suggestions(source), vec!["Did you mean `ypos`?"]);
+    }
+
+    #[test]
+    fn property() {
+        let source = "struct P\n  xpos: Int64\n\nmain: ->\n  let p = P\n    xpos: 1\n  p.xpso\n";
+
+        assert_eq!(suggestions(source), vec!["Did you mean `xpos`?"]);
+    }
+
+    #[test]
+    fn module_in_use() {
+        let dir = std::env::temp_dir().join(format!("rock_suggest_use_{}", std::process::id()));
+
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("utils.rk"), "helper: x -> x\n").unwrap();
+
+        let source = "mod utils\n\nuse utilz::helper\n\nmain: -> helper 2\n";
+        let file = SourceFile::from_str(dir.join("main.rk").to_str().unwrap(), source).unwrap();
+
+        let (diagnostics, _) = check(file);
+
+        assert_eq!(
+            diagnostics.list[0].suggestions()[0].message,
+            "Did you mean `utils`?"
+        );
+    }
+
+    // The files next to the declaring one are the candidates
+    #[test]
+    fn module_file() {
+        let dir = std::env::temp_dir().join(format!("rock_suggest_mod_{}", std::process::id()));
+
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("utils.rk"), "helper: x -> x\n").unwrap();
+
+        let source = "mod utilz\n\nmain: -> 0\n";
+        let file = SourceFile::from_str(dir.join("main.rk").to_str().unwrap(), source).unwrap();
+
+        let (diagnostics, _) = check(file);
+
+        assert_eq!(
+            diagnostics.list[0].suggestions()[0].message,
+            "Did you mean `utils`?"
+        );
+    }
+
+    #[test]
+    fn json() {
+        let source = "counter: x -> x\nmain: -> countr 2\n";
+
+        let (diagnostics, files) = check(SourceFile::from_str("main.rk", source).unwrap());
+
+        let json = serde_json::to_value(&diagnostics.to_json(&files)[0]).unwrap();
+
+        assert_eq!(
+            json["suggestions"],
+            serde_json::json!([{
+                "message": "Did you mean `counter`?",
+                "span": json["span"],
+                "replacement": "counter",
+            }])
+        );
+    }
+}
+
+#[cfg(test)]
+mod struct_ctor {
+    use super::*;
+
+    #[test]
+    fn missing_fields() {
+        let source = "struct Point\n  x: Int64\n  y: Int64\n  z: Int64\n\nmain: ->\n  let p = Point\n    x: 1\n  p.x\n";
+
+        let (diagnostics, _) = check(SourceFile::from_str("main.rk", source).unwrap());
+
+        let diag = &diagnostics.list[0];
+        let start = source.find("= Point").unwrap() + 2;
+
+        assert_eq!((diag.span.start, diag.span.end), (start, start + 5));
+        assert_eq!(diag.get_kind().label(), "Missing y, z");
+    }
+}
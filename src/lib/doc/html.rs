@@ -0,0 +1,173 @@
+// The HTML pages are converted from the markdown ones. Only what the pages and the doc
+// comments use is supported: headings, paragraphs, lists, code blocks, code spans, links
+// and the anchors of the items.
+
+pub fn page(title: &str, markdown: &str) -> String {
+    format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{}</title>
+<style>
+body {{ font-family: sans-serif; max-width: 50em; margin: auto; padding: 1em; }}
+code, pre {{ background: #f4f4f4; }}
+pre {{ padding: 0.5em; }}
+h3, h4 {{ border-bottom: 1px solid #ddd; }}
+</style>
+</head>
+<body>
+{}</body>
+</html>
+",
+        escape(title),
+        to_html(markdown)
+    )
+}
+
+pub fn to_html(markdown: &str) -> String {
+    let mut out = String::new();
+
+    let mut paragraph: Vec<&str> = vec![];
+    let mut list: Vec<&str> = vec![];
+    let mut code: Option<Vec<&str>> = None;
+
+    for line in markdown.lines() {
+        if let Some(lines) = &mut code {
+            if line.trim_start().starts_with("```") {
+                out += &format!("<pre><code>{}</code></pre>\n", escape(&lines.join("\n")));
+
+                code = None;
+            } else {
+                lines.push(line);
+            }
+
+            continue;
+        }
+
+        let trimmed = line.trim();
+
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+
+        let is_heading = (1..=6).contains(&level) && trimmed[level..].starts_with(' ');
+
+        let item = trimmed.strip_prefix("- ");
+
+        if trimmed.is_empty() || is_heading || item.is_some() || trimmed.starts_with("```") {
+            flush_paragraph(&mut out, &mut paragraph);
+        }
+
+        if item.is_none() {
+            flush_list(&mut out, &mut list);
+        }
+
+        if trimmed.starts_with("```") {
+            code = Some(vec![]);
+        } else if is_heading {
+            out += &format!(
+                "<h{}>{}</h{}>\n",
+                level,
+                inline(trimmed[level..].trim()),
+                level
+            );
+        } else if let Some(item) = item {
+            list.push(item);
+        } else if !trimmed.is_empty() {
+            paragraph.push(trimmed);
+        }
+    }
+
+    // An unterminated code block ends with the page
+    if let Some(lines) = code {
+        out += &format!("<pre><code>{}</code></pre>\n", escape(&lines.join("\n")));
+    }
+
+    flush_paragraph(&mut out, &mut paragraph);
+    flush_list(&mut out, &mut list);
+
+    out
+}
+
+fn flush_paragraph(out: &mut String, paragraph: &mut Vec<&str>) {
+    if !paragraph.is_empty() {
+        *out += &format!("<p>{}</p>\n", inline(&paragraph.join(" ")));
+
+        paragraph.clear();
+    }
+}
+
+fn flush_list(out: &mut String, list: &mut Vec<&str>) {
+    if !list.is_empty() {
+        *out += "<ul>\n";
+
+        for item in list.iter() {
+            *out += &format!("<li>{}</li>\n", inline(item));
+        }
+
+        *out += "</ul>\n";
+
+        list.clear();
+    }
+}
+
+// The code spans, the links and the anchors, the rest is escaped
+fn inline(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(code) = rest.strip_prefix('`') {
+            if let Some(end) = code.find('`') {
+                out += &format!("<code>{}</code>", escape(&code[..end]));
+
+                rest = &code[end + 1..];
+
+                continue;
+            }
+        }
+
+        if let Some(link) = rest.strip_prefix('[') {
+            if let Some((text, href, len)) = parse_link(link) {
+                out += &format!("<a href=\"{}\">{}</a>", escape(href), inline(text));
+
+                rest = &link[len..];
+
+                continue;
+            }
+        }
+
+        if rest.starts_with("<a id=\"") {
+            if let Some(end) = rest.find("</a>") {
+                out += &rest[..end + 4];
+
+                rest = &rest[end + 4..];
+
+                continue;
+            }
+        }
+
+        out += &escape(&c.to_string());
+
+        rest = &rest[c.len_utf8()..];
+    }
+
+    out
+}
+
+// `text](href)`, after the opening bracket, with the length of what it covers
+fn parse_link(s: &str) -> Option<(&str, &str, usize)> {
+    let text_end = s.find("](")?;
+
+    let href_len = s[text_end + 2..].find(')')?;
+
+    let href = &s[text_end + 2..text_end + 2 + href_len];
+
+    Some((&s[..text_end], href, text_end + 2 + href_len + 1))
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
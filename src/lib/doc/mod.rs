@@ -0,0 +1,591 @@
+// Generates the documentation of a project from the `##` comments of its items, with a
+// page per module. The signatures are the declared ones, or the inferred types of the
+// functions that are used.
+
+mod html;
+
+#[cfg(test)]
+mod tests;
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    ast::{tree::*, NodeId},
+    diagnostics::Diagnostics,
+    lsp::Analysis,
+    parser,
+    ty::{FuncType, PrimitiveType, Type},
+    Config,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocFormat {
+    Markdown,
+    Html,
+}
+
+impl DocFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "markdown" => Some(Self::Markdown),
+            "html" => Some(Self::Html),
+            _ => None,
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+        }
+    }
+}
+
+// Writes the pages in the `doc` folder of the build folder, and returns the path of the
+// one of the root module. The errors that stop the analysis are printed and returned.
+pub fn generate(config: &Config, format: DocFormat) -> Result<PathBuf, Diagnostics> {
+    let pages = render(&config.project_config.entry_file(), config, format)?;
+
+    let folder = config.build_folder.join("doc");
+
+    fs::create_dir_all(&folder).unwrap();
+
+    for (file_name, content) in &pages {
+        fs::write(folder.join(file_name), content).unwrap();
+    }
+
+    Ok(folder.join(&pages[0].0))
+}
+
+// The file name and the content of each page, the root module first
+pub fn render(
+    entry: &Path,
+    config: &Config,
+    format: DocFormat,
+) -> Result<Vec<(String, String)>, Diagnostics> {
+    // The analysis fails without a main, the docs of a library are still generated
+    let quiet_config = Config {
+        quiet: true,
+        ..config.clone()
+    };
+
+    let analysis = Analysis::new(entry, &quiet_config);
+
+    let ast = match &analysis.ast {
+        Some(ast) => ast,
+        None => {
+            if !config.quiet {
+                analysis.diagnostics.print(&analysis.files);
+            }
+
+            return Err(analysis.diagnostics);
+        }
+    };
+
+    let name = match config.project_config.name.as_str() {
+        "" => "root".to_string(),
+        name => name.to_string(),
+    };
+
+    let mut ctx = DocCtx::new(&analysis, ast, format);
+
+    let mut mods = vec![];
+
+    ctx.project_mods(&ast.r#mod, vec![], None, &mut mods);
+
+    for (path, r#mod, _) in &mods {
+        ctx.add_links(path, r#mod);
+    }
+
+    Ok(mods
+        .iter()
+        .map(|(path, r#mod, decl)| {
+            let title = if path.is_empty() {
+                format!("Crate `{}`", name)
+            } else {
+                format!("Module `{}`", path.join("::"))
+            };
+
+            let markdown = ctx.page(&title, path, r#mod, *decl);
+
+            let content = match format {
+                DocFormat::Markdown => markdown,
+                DocFormat::Html => html::page(&title.replace('`', ""), &markdown),
+            };
+
+            (ctx.file_name(path), content)
+        })
+        .collect())
+}
+
+struct DocCtx<'a> {
+    analysis: &'a Analysis,
+    ast: &'a Root,
+    format: DocFormat,
+    links: HashMap<String, String>, // The structs and traits of the project, to their anchor
+}
+
+impl<'a> DocCtx<'a> {
+    fn new(analysis: &'a Analysis, ast: &'a Root, format: DocFormat) -> Self {
+        Self {
+            analysis,
+            ast,
+            format,
+            links: HashMap::new(),
+        }
+    }
+
+    fn file_name(&self, path: &[String]) -> String {
+        if path.is_empty() {
+            format!("index.{}", self.format.extension())
+        } else {
+            format!("{}.{}", path.join("."), self.format.extension())
+        }
+    }
+
+    // The items of the std, and the ones of the header that imports it, are not documented
+    fn is_project_item(&self, node_id: NodeId) -> bool {
+        let span = match self.ast.spans.get(&node_id) {
+            Some(span) => span,
+            None => return false,
+        };
+
+        !parser::is_stdlib_file(span.file_path.to_str().unwrap_or_default())
+            && self
+                .analysis
+                .files
+                .get(&span.file_path)
+                .and_then(|file| file.source_offset(span.start))
+                .is_some()
+    }
+
+    // Every module of the project with its path, and the node id of its declaration
+    fn project_mods(
+        &self,
+        r#mod: &'a Mod,
+        path: Vec<String>,
+        decl: Option<NodeId>,
+        mods: &mut Vec<(Vec<String>, &'a Mod, Option<NodeId>)>,
+    ) {
+        mods.push((path.clone(), r#mod, decl));
+
+        for top_level in &r#mod.top_levels {
+            if let TopLevel::Mod(name, r#mod) = top_level {
+                if self.is_project_item(name.node_id) {
+                    let mut path = path.clone();
+
+                    path.push(name.name.clone());
+
+                    self.project_mods(r#mod, path, Some(name.node_id), mods);
+                }
+            }
+        }
+    }
+
+    fn add_links(&mut self, path: &[String], r#mod: &Mod) {
+        let file_name = self.file_name(path);
+
+        for top_level in &r#mod.top_levels {
+            let (kind, name, node_id) = match top_level {
+                TopLevel::Struct(s) => ("struct", s.name.name.clone(), s.name.node_id),
+                TopLevel::Trait(t) => ("trait", t.name.get_name(), t.node_id),
+                TopLevel::Newtype(n) => ("type", n.name.name.clone(), n.node_id),
+                _ => continue,
+            };
+
+            if self.is_project_item(node_id) {
+                let href = format!("{}#{}.{}", file_name, kind, name);
+
+                self.links.insert(name, href);
+            }
+        }
+    }
+
+    fn page(&self, title: &str, path: &[String], r#mod: &Mod, decl: Option<NodeId>) -> String {
+        let mut out = format!("# {}\n\n", title);
+
+        if let Some(doc) = decl.and_then(|node_id| self.ast.docs.get(&node_id)) {
+            out += &format!("{}\n\n", doc);
+        }
+
+        let mut mods = String::new();
+        let mut consts = String::new();
+        let mut types = String::new();
+        let mut structs = String::new();
+        let mut traits = String::new();
+        let mut functions = String::new();
+        let mut externs = String::new();
+        let mut impls = String::new();
+
+        for top_level in &r#mod.top_levels {
+            match top_level {
+                TopLevel::Mod(name, _) if self.is_project_item(name.node_id) => {
+                    let mut sub_path = path.to_vec();
+
+                    sub_path.push(name.name.clone());
+
+                    let summary = self
+                        .ast
+                        .docs
+                        .get(&name.node_id)
+                        .and_then(|doc| doc.lines().next())
+                        .map(|line| format!(": {}", line))
+                        .unwrap_or_default();
+
+                    mods += &format!(
+                        "- [`{}`]({}){}\n",
+                        name.name,
+                        self.file_name(&sub_path),
+                        summary
+                    );
+                }
+                TopLevel::Struct(s) if self.is_project_item(s.name.node_id) => {
+                    structs += &self.r#struct(s)
+                }
+                TopLevel::Trait(t) if self.is_project_item(t.node_id) => traits += &self.r#trait(t),
+                TopLevel::Function(f) if self.is_project_item(f.node_id) => {
+                    functions += &self.function(3, "fn", "", f, self.declared(r#mod, &f.name))
+                }
+                TopLevel::Clauses(c) if self.is_project_item(c.node_id) => {
+                    functions += &self.function_item(
+                        3,
+                        "fn.",
+                        &c.name,
+                        c.node_id,
+                        c.signature.arguments.len(),
+                        self.declared(r#mod, &c.name),
+                    )
+                }
+                TopLevel::Extern(p) if self.is_project_item(p.node_id) => {
+                    externs += &self.item(
+                        3,
+                        &format!("fn.{}", p.name.name),
+                        &self.signature(&p.name.name, &p.signature),
+                        p.node_id,
+                    )
+                }
+                TopLevel::Impl(i) if self.is_project_item(i.node_id) => impls += &self.r#impl(i),
+                TopLevel::Const(c) if self.is_project_item(c.node_id) => {
+                    consts += &self.item(
+                        3,
+                        &format!("const.{}", c.name.name),
+                        &format!("{}: {}", c.name.name, type_name(&c.ty)),
+                        c.node_id,
+                    )
+                }
+                TopLevel::TypeAlias(a) if self.is_project_item(a.name.node_id) => {
+                    types += &self.item(
+                        3,
+                        &format!("type.{}", a.name.name),
+                        &format!("{} = {}", a.name.name, type_name(&a.ty)),
+                        a.name.node_id,
+                    )
+                }
+                TopLevel::Newtype(n) if self.is_project_item(n.node_id) => {
+                    types += &self.newtype(n)
+                }
+                _ => (),
+            }
+        }
+
+        for (title, section) in [
+            ("Modules", mods),
+            ("Constants", consts),
+            ("Types", types),
+            ("Structs", structs),
+            ("Traits", traits),
+            ("Functions", functions),
+            ("Externs", externs),
+            ("Implementations", impls),
+        ] {
+            if !section.is_empty() {
+                out += &format!("## {}\n\n{}", title, section);
+
+                if title == "Modules" {
+                    out += "\n";
+                }
+            }
+        }
+
+        out
+    }
+
+    fn r#struct(&self, s: &StructDecl) -> String {
+        let mut out = self.item(
+            3,
+            &format!("struct.{}", s.name.name),
+            &s.name.name,
+            s.name.node_id,
+        );
+
+        for field in &s.defs {
+            out += &self.item(
+                4,
+                &format!("field.{}.{}", s.name.name, field.name.name),
+                &format!("{}: {}", field.name.name, type_name(&field.signature.ret)),
+                field.node_id,
+            );
+        }
+
+        let traits = self
+            .ast
+            .trait_solver
+            .implemented_trait
+            .get(&s.name.name)
+            .map(|traits| {
+                traits
+                    .iter()
+                    .filter(|t| **t != s.name.name)
+                    .cloned()
+                    .collect::<BTreeSet<_>>()
+            })
+            .unwrap_or_default();
+
+        out + &self.names_line("Implements", &traits)
+    }
+
+    fn newtype(&self, n: &Newtype) -> String {
+        let mut out = self.item(3, &format!("type.{}", n.name.name), &n.name.name, n.node_id);
+
+        out += &format!("Wraps {}\n\n", self.code(&type_name(&n.ty)));
+
+        let traits = self
+            .ast
+            .trait_solver
+            .implemented_trait
+            .get(&n.name.name)
+            .map(|traits| {
+                traits
+                    .iter()
+                    .filter(|t| **t != n.name.name)
+                    .cloned()
+                    .collect::<BTreeSet<_>>()
+            })
+            .unwrap_or_default();
+
+        out + &self.names_line("Implements", &traits)
+    }
+
+    fn r#trait(&self, t: &Trait) -> String {
+        let name = t.name.get_name();
+
+        let mut out = self.item(3, &format!("trait.{}", name), &name, t.node_id);
+
+        for proto in &t.defs {
+            out += &self.item(
+                4,
+                &format!("method.{}.{}", name, proto.name.name),
+                &self.signature(&proto.name.name, &proto.signature),
+                proto.node_id,
+            );
+        }
+
+        for f in &t.default_impl {
+            out += &self.function(4, "method", &format!("{}.", name), f, None);
+        }
+
+        let implementors = self
+            .ast
+            .trait_solver
+            .implemented_trait
+            .iter()
+            .filter(|(implementor, traits)| **implementor != name && traits.contains(&name))
+            .map(|(implementor, _)| implementor.clone())
+            .collect::<BTreeSet<_>>();
+
+        out + &self.names_line("Implemented by", &implementors)
+    }
+
+    fn r#impl(&self, i: &Impl) -> String {
+        let title = std::iter::once(&i.name)
+            .chain(i.types.iter())
+            .map(Type::get_name)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let anchor = format!("impl.{}", title.replace(' ', "."));
+
+        let mut out = self.item(3, &anchor, &format!("impl {}", title), i.node_id);
+
+        let prefix = format!("{}.", i.types.first().unwrap_or(&i.name).get_name());
+
+        for f in &i.defs {
+            out += &self.function(4, "method", &prefix, f, None);
+        }
+
+        out
+    }
+
+    // The signature of a function is the declared one, or its inferred type when it has a
+    // single instance. The generic ones list their instances.
+    fn function(
+        &self,
+        level: usize,
+        kind: &str,
+        prefix: &str,
+        f: &FunctionDecl,
+        declared: Option<&FuncType>,
+    ) -> String {
+        self.function_item(
+            level,
+            &format!("{}.{}", kind, prefix),
+            &f.name,
+            f.node_id,
+            f.arguments.len(),
+            declared,
+        )
+    }
+
+    // `anchor_prefix` is like `fn.` or `method.Point.`
+    fn function_item(
+        &self,
+        level: usize,
+        anchor_prefix: &str,
+        name: &Identifier,
+        node_id: NodeId,
+        arity: usize,
+        declared: Option<&FuncType>,
+    ) -> String {
+        let instances = self.analysis.instances(node_id);
+
+        let signature = match (declared, instances.as_slice()) {
+            (Some(declared), _) => declared.clone(),
+            (None, [instance]) => instance.clone(),
+            (None, _) => FuncType::from_args_nb(arity),
+        };
+
+        let mut out = self.item(
+            level,
+            &format!("{}{}", anchor_prefix, name.name),
+            &self.signature(&name.name, &signature),
+            node_id,
+        );
+
+        if declared.is_none() && instances.len() > 1 {
+            let instances = instances
+                .iter()
+                .map(|instance| self.code(&func_type_name(instance)))
+                .collect::<Vec<_>>();
+
+            out += &format!("Used as {}\n\n", instances.join(", "));
+        }
+
+        out
+    }
+
+    fn declared(&self, r#mod: &'a Mod, name: &Identifier) -> Option<&'a FuncType> {
+        r#mod
+            .top_levels
+            .iter()
+            .find_map(|top_level| match top_level {
+                TopLevel::FnSignature(p) if p.name.name == name.name => Some(&p.signature),
+                _ => None,
+            })
+    }
+
+    fn signature(&self, name: &str, signature: &FuncType) -> String {
+        format!("{}: {}", name, func_type_name(signature))
+    }
+
+    // A heading with an anchor and the doc comment of the item
+    fn item(&self, level: usize, anchor: &str, title: &str, node_id: NodeId) -> String {
+        // The heading of a struct or a trait is not a link to itself
+        let title = match self.links.get(title) {
+            Some(href) if href.ends_with(&format!("#{}", anchor)) => format!("`{}`", title),
+            _ => self.code(title),
+        };
+
+        let mut out = format!(
+            "{} <a id=\"{}\"></a>{}\n\n",
+            "#".repeat(level),
+            anchor,
+            title
+        );
+
+        if let Some(doc) = self.ast.docs.get(&node_id) {
+            out += &format!("{}\n\n", doc);
+        }
+
+        out
+    }
+
+    fn names_line(&self, label: &str, names: &BTreeSet<String>) -> String {
+        if names.is_empty() {
+            return String::new();
+        }
+
+        let names = names.iter().map(|name| self.code(name)).collect::<Vec<_>>();
+
+        format!("{} {}\n\n", label, names.join(", "))
+    }
+
+    // Some code, where the names of the structs and traits of the project are links
+    fn code(&self, code: &str) -> String {
+        let mut out = String::new();
+        let mut plain = String::new();
+
+        for word in words(code) {
+            match self.links.get(word) {
+                Some(href) => {
+                    if !plain.is_empty() {
+                        out += &format!("`{}`", plain);
+
+                        plain.clear();
+                    }
+
+                    out += &format!("[`{}`]({})", word, href);
+                }
+                None => plain += word,
+            }
+        }
+
+        if !plain.is_empty() {
+            out += &format!("`{}`", plain);
+        }
+
+        out
+    }
+}
+
+// The identifiers and what is between them
+fn words(s: &str) -> Vec<&str> {
+    let mut words = vec![];
+    let mut start = 0;
+
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+
+    for (i, c) in s.char_indices() {
+        let next = s[i + c.len_utf8()..].chars().next();
+
+        if next.map(is_ident) != Some(is_ident(c)) {
+            words.push(&s[start..i + c.len_utf8()]);
+
+            start = i + c.len_utf8();
+        }
+    }
+
+    words
+}
+
+// In the syntax of the signatures, like `Int64 => [String] => Bool`
+fn type_name(t: &Type) -> String {
+    match t {
+        Type::Func(f) => format!("({})", func_type_name(f)),
+        Type::Primitive(PrimitiveType::Array(t, _)) => format!("[{}]", type_name(t)),
+        Type::ForAll(name) => name.clone(),
+        t => t.get_name(),
+    }
+}
+
+fn func_type_name(f: &FuncType) -> String {
+    f.arguments
+        .iter()
+        .chain(std::iter::once(&*f.ret))
+        .map(type_name)
+        .collect::<Vec<_>>()
+        .join(" => ")
+}
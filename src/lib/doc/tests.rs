@@ -0,0 +1,162 @@
+use std::{fs, path::PathBuf};
+
+use super::{html, render, DocFormat};
+use crate::Config;
+
+const MAIN: &str = "## Geometry
+mod shapes
+
+use shapes::(*)
+
+## Makes a square
+make: side -> Square side: side
+
+id: x -> x
+
+main: ->
+  id \"a\"
+  id 2
+  (make 2).area!
+";
+
+const SHAPES: &str = "## Something with an area
+trait Shape
+  ## Its area
+  @area: Int64
+
+## A square
+##
+## ```
+## assert_eq (Square side: 2).side, 2
+## ```
+struct Square
+  ## The length of a side
+  side: Int64
+
+impl Shape Square
+  @area: -> @side * @side
+";
+
+fn project(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rock_doc_{}_{}", name, std::process::id()));
+
+    let _ = fs::remove_dir_all(&dir);
+
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(dir.join("src/main.rk"), MAIN).unwrap();
+    fs::write(dir.join("src/shapes.rk"), SHAPES).unwrap();
+
+    dir.join("src/main.rk")
+}
+
+fn config() -> Config {
+    Config {
+        std: true,
+        quiet: true,
+        ..Config::default()
+    }
+}
+
+#[cfg(test)]
+mod pages {
+    use super::*;
+
+    #[test]
+    fn one_per_module() {
+        let pages = render(&project("pages"), &config(), DocFormat::Markdown).unwrap();
+
+        let names = pages
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, vec!["index.md", "shapes.md"]);
+        assert!(pages[0].1.contains("- [`shapes`](shapes.md): Geometry\n"));
+    }
+
+    #[test]
+    fn inferred_signatures() {
+        let pages = render(&project("signatures"), &config(), DocFormat::Markdown).unwrap();
+
+        let index = &pages[0].1;
+
+        assert!(index.contains(
+            "### <a id=\"fn.make\"></a>`make: Int64 => `[`Square`](shapes.md#struct.Square)\n\nMakes a square\n"
+        ));
+
+        // A generic function lists its instances
+        assert!(index.contains(
+            "### <a id=\"fn.id\"></a>`id: a => b`\n\nUsed as `Int64 => Int64`, `String => String`\n"
+        ));
+    }
+
+    #[test]
+    fn items_and_implementors() {
+        let pages = render(&project("items"), &config(), DocFormat::Markdown).unwrap();
+
+        let shapes = &pages[1].1;
+
+        assert!(shapes.contains("### <a id=\"struct.Square\"></a>`Square`\n\nA square\n"));
+        assert!(shapes.contains(
+            "#### <a id=\"field.Square.side\"></a>`side: Int64`\n\nThe length of a side\n"
+        ));
+        assert!(shapes.contains("Implements [`Shape`](shapes.md#trait.Shape)\n"));
+        assert!(
+            shapes.contains("### <a id=\"trait.Shape\"></a>`Shape`\n\nSomething with an area\n")
+        );
+        assert!(shapes.contains("Implemented by [`Square`](shapes.md#struct.Square)\n"));
+        assert!(shapes.contains("#### <a id=\"method.Square.area\"></a>`area: `[`Square`](shapes.md#struct.Square)` => Int64`"));
+    }
+
+    #[test]
+    fn html() {
+        let pages = render(&project("html"), &config(), DocFormat::Html).unwrap();
+
+        assert_eq!(pages[1].0, "shapes.html");
+        assert!(pages[1].1.starts_with("<!DOCTYPE html>"));
+        assert!(pages[1]
+            .1
+            .contains("<a href=\"shapes.html#trait.Shape\"><code>Shape</code></a>"));
+    }
+}
+
+#[cfg(test)]
+mod to_html {
+    use super::*;
+
+    #[test]
+    fn blocks() {
+        let markdown = "# Title
+
+Some `code` and
+a [link](a.html#b).
+
+- one
+- two
+
+```rock
+x < 2
+```
+";
+
+        assert_eq!(
+            html::to_html(markdown),
+            "<h1>Title</h1>
+<p>Some <code>code</code> and a <a href=\"a.html#b\">link</a>.</p>
+<ul>
+<li>one</li>
+<li>two</li>
+</ul>
+<pre><code>x &lt; 2</code></pre>
+"
+        );
+    }
+
+    #[test]
+    fn anchors() {
+        assert_eq!(
+            html::to_html("### <a id=\"fn.f\"></a>`f: Int64`"),
+            "<h3><a id=\"fn.f\"></a><code>f: Int64</code></h3>\n"
+        );
+    }
+}
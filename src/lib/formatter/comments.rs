@@ -0,0 +1,162 @@
+use std::collections::VecDeque;
+
+use crate::parser::lexer::{self, Token};
+
+// A comment, or a lint annotation as they are not kept in the ast either
+#[derive(Debug, Clone)]
+pub struct Comment {
+    pub line: usize,
+    pub indent: usize,
+    pub text: String,
+    pub trailing: bool, // After some code on the same line
+}
+
+// The comments of the original source, in order, and the layout of its lines that the
+// printer needs to place them back
+#[derive(Debug, Default)]
+pub struct Comments {
+    pending: VecDeque<Comment>,
+    code_indents: Vec<Option<usize>>, // The indentation of each line holding some code
+    blanks: Vec<bool>,
+    annotations: Vec<usize>,
+}
+
+impl Comments {
+    pub fn new(source: &str) -> Self {
+        let mut comments = Self::default();
+
+        let mut tokens = lexer::tokenize(source)
+            .into_iter()
+            .filter(Token::is_comment)
+            .peekable();
+
+        let mut line_start = 0;
+        let mut comment_end = 0; // A block comment can span several lines
+
+        for (i, raw) in lexer::strip_comments(source)
+            .split_inclusive('\n')
+            .enumerate()
+        {
+            let line = i + 1;
+            let line_end = line_start + raw.len();
+            let in_comment = line_start < comment_end;
+
+            let mut texts = vec![];
+            let mut comment_start = line_start;
+
+            while let Some(token) = tokens.next_if(|token| token.start < line_end) {
+                if texts.is_empty() {
+                    comment_start = token.start;
+                }
+
+                texts.push(token.text(source).trim_end());
+
+                comment_end = token.end;
+            }
+
+            let comment = (!texts.is_empty()).then(|| texts.join(" "));
+
+            let code = raw.trim();
+            let indent = match code.is_empty() {
+                true => comment_start - line_start,
+                false => raw.len() - raw.trim_start().len(),
+            };
+
+            comments
+                .blanks
+                .push(code.is_empty() && comment.is_none() && !in_comment);
+            comments
+                .code_indents
+                .push(if code.is_empty() { None } else { Some(indent) });
+
+            if indent == 0 && code.starts_with("@[") {
+                let text = match comment {
+                    Some(comment) => format!("{} {}", code, comment),
+                    None => code.to_string(),
+                };
+
+                comments.push(line, 0, text, false);
+                comments.annotations.push(line);
+            } else if let Some(comment) = comment {
+                comments.push(line, indent, comment, !code.is_empty());
+            }
+
+            line_start = line_end;
+        }
+
+        comments
+    }
+
+    fn push(&mut self, line: usize, indent: usize, text: String, trailing: bool) {
+        self.pending.push_back(Comment {
+            line,
+            indent,
+            text,
+            trailing,
+        });
+    }
+
+    pub fn is_blank(&self, line: usize) -> bool {
+        line > 0 && self.blanks.get(line - 1).copied().unwrap_or(false)
+    }
+
+    pub fn front(&self) -> Option<&Comment> {
+        self.pending.front()
+    }
+
+    pub fn pop_front(&mut self) -> Option<Comment> {
+        self.pending.pop_front()
+    }
+
+    pub fn has_before(&self, line: usize) -> bool {
+        self.front().is_some_and(|comment| comment.line < line)
+    }
+
+    pub fn take_before(&mut self, line: usize) -> Vec<Comment> {
+        let mut taken = vec![];
+
+        while self.has_before(line) {
+            taken.extend(self.pop_front());
+        }
+
+        taken
+    }
+
+    pub fn take_trailing(&mut self, line: usize) -> Option<String> {
+        let idx = self
+            .pending
+            .iter()
+            .position(|comment| comment.trailing && comment.line == line)?;
+
+        self.pending.remove(idx).map(|comment| comment.text)
+    }
+
+    pub fn take_rest(&mut self) -> Vec<Comment> {
+        self.pending.drain(..).collect()
+    }
+
+    // Whether a comment ends the block indented by `indent`, whose last code is on the
+    // line `from`: nothing in between leaves the block, and the code after it does.
+    pub fn ends_block(&self, comment: &Comment, indent: usize, from: usize) -> bool {
+        let code_indent = |line: usize| self.code_indents.get(line - 1).copied().flatten();
+
+        let inside = (from + 1..comment.line)
+            .filter_map(code_indent)
+            .all(|line_indent| line_indent >= indent);
+
+        let next = (comment.line + 1..=self.code_indents.len()).find_map(code_indent);
+
+        comment.indent >= indent && inside && next.map_or(true, |next| next < indent)
+    }
+
+    // The closest line before `line` with some unindented code, that is not an annotation
+    pub fn header_line(&self, line: usize) -> usize {
+        (1..line)
+            .rev()
+            .find(|l| {
+                self.code_indents.get(l - 1).copied().flatten() == Some(0)
+                    && !self.annotations.contains(l)
+            })
+            .unwrap_or(line)
+    }
+}
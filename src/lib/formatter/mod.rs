@@ -0,0 +1,37 @@
+// Prints a syntax tree back to the source, in a canonical layout that keeps the comments
+// and the blank lines. The files are parsed alone, their modules are not loaded.
+
+mod comments;
+mod printer;
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    diagnostics::Diagnostics,
+    parser::{self, ParsingCtx, SourceFile},
+    Config,
+};
+
+use printer::Printer;
+
+// The errors of the files that do not parse are printed and returned
+pub fn format_str(path: &str, source: &str, config: &Config) -> Result<String, Diagnostics> {
+    let mut parsing_ctx = ParsingCtx::new(config);
+
+    let file = match SourceFile::from_str(path, source) {
+        Ok(file) => file,
+        Err(diagnostic) => {
+            parsing_ctx.diagnostics.push_error(diagnostic);
+            parsing_ctx.print_diagnostics();
+
+            return Err(parsing_ctx.diagnostics);
+        }
+    };
+
+    parsing_ctx.add_file(&file);
+
+    let ast = parser::parse_file(&mut parsing_ctx)?;
+
+    Ok(Printer::new(&ast, &file).print(&ast))
+}
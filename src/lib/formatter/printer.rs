@@ -0,0 +1,858 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::{visit::*, *},
+    parser::{SourceFile, Span},
+    ty::{FuncType, PrimitiveType, Type},
+};
+
+use super::comments::{Comment, Comments};
+
+const INDENT: usize = 2;
+const MAX_WIDTH: usize = 80;
+const MAX_CTOR_WIDTH: usize = 60; // Past that, the struct constructors span several lines
+
+// Where an expression is printed, as the parser does not accept the same forms everywhere
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Ctx {
+    Block, // A statement, that can span several lines
+    Line,
+    Nested, // Inside a list separated by commas, that it must not continue
+    Last,   // The last element of such a list
+}
+
+pub struct Printer<'a> {
+    spans: &'a HashMap<NodeId, Span>,
+    line_starts: Vec<usize>,
+    comments: Comments,
+    out: Vec<String>,
+    last_line: usize, // The original line of the last printed node
+}
+
+impl<'a> Printer<'a> {
+    pub fn new(ast: &'a Root, file: &SourceFile) -> Self {
+        Self {
+            spans: &ast.spans,
+            line_starts: std::iter::once(0)
+                .chain(file.content.match_indices('\n').map(|(i, _)| i + 1))
+                .collect(),
+            comments: Comments::new(&file.source),
+            out: vec![],
+            last_line: 0,
+        }
+    }
+
+    pub fn print(mut self, root: &Root) -> String {
+        for top_level in &root.r#mod.top_levels {
+            self.top_level(top_level);
+        }
+
+        for comment in self.comments.take_rest() {
+            self.comment(0, comment);
+        }
+
+        while self.out.last().is_some_and(|line| line.is_empty()) {
+            self.out.pop();
+        }
+
+        self.out.join("\n") + "\n"
+    }
+
+    // Layout
+
+    // The content has the same lines as the source, as the files are parsed without header
+    fn original_line(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|start| *start <= offset)
+    }
+
+    fn node_line(&self, node_id: NodeId) -> usize {
+        match self.spans.get(&node_id) {
+            Some(span) => self.original_line(span.start),
+            None => self.last_line,
+        }
+    }
+
+    fn statement_line(&self, stmt: &Statement) -> usize {
+        let mut first = FirstOffset {
+            spans: self.spans,
+            offset: None,
+        };
+
+        first.visit_statement(stmt);
+
+        match first.offset {
+            Some(offset) => self.original_line(offset),
+            None => self.last_line,
+        }
+    }
+
+    // Prints the comments before a node starting on `line`, and keeps a blank line
+    // before it if there was one
+    fn leading(&mut self, indent: usize, line: usize) {
+        for comment in self.comments.take_before(line) {
+            self.comment(indent, comment);
+        }
+
+        self.blank_line(indent, line);
+
+        self.last_line = line;
+    }
+
+    fn blank_line(&mut self, indent: usize, line: usize) {
+        if !self.comments.is_blank(line.saturating_sub(1)) {
+            return;
+        }
+
+        // Not twice, nor at the start of a block
+        let after_code = self
+            .out
+            .last()
+            .is_some_and(|last| !last.is_empty() && last.len() - last.trim_start().len() >= indent);
+
+        if after_code {
+            self.out.push(String::new());
+        }
+    }
+
+    fn comment(&mut self, indent: usize, comment: Comment) {
+        self.blank_line(indent, comment.line);
+
+        self.line(indent, &comment.text);
+    }
+
+    // The continuation lines of `text` are already indented
+    fn line(&mut self, indent: usize, text: &str) {
+        self.out.push(" ".repeat(indent) + text);
+    }
+
+    fn trailing(&mut self, line: usize) {
+        if let Some(comment) = self.comments.take_trailing(line) {
+            self.out
+                .last_mut()
+                .unwrap()
+                .push_str(&format!(" {}", comment));
+        }
+    }
+
+    // The comments after the last statement of a block stay in it
+    fn close_block(&mut self, indent: usize) {
+        while let Some(comment) = self.comments.front() {
+            if !self.comments.ends_block(comment, indent, self.last_line) {
+                break;
+            }
+
+            let comment = self.comments.pop_front().unwrap();
+
+            self.last_line = comment.line;
+
+            self.comment(indent, comment);
+        }
+    }
+
+    // Top levels
+
+    fn top_level(&mut self, top_level: &TopLevel) {
+        match top_level {
+            TopLevel::Extern(p) => {
+                self.leading(0, self.node_line(p.node_id));
+                self.line(0, &format!("extern {}", prototype(p, false)));
+                self.trailing(self.last_line);
+            }
+            TopLevel::FnSignature(p) => {
+                self.leading(0, self.node_line(p.node_id));
+                self.line(0, &prototype(p, false));
+                self.trailing(self.last_line);
+            }
+            TopLevel::Function(f) => self.function(f, 0, false),
+            TopLevel::Clauses(c) => {
+                for clause in &c.clauses {
+                    self.clause(clause);
+                }
+            }
+            TopLevel::Test(t) => {
+                let header = format!(
+                    "test \"{}\": ->",
+                    t.name.chars().map(escape).collect::<String>()
+                );
+
+                let stmts = t.func.body.stmts.iter().collect::<Vec<_>>();
+
+                self.function_body(t.func.node_id, &header, &stmts, 0);
+            }
+            TopLevel::Const(c) => {
+                self.leading(0, self.node_line(c.node_id));
+                self.line(
+                    0,
+                    &format!(
+                        "const {}: {} = {}",
+                        c.name.name,
+                        type_(&c.ty),
+                        self.expression(&c.value, Ctx::Block, 0)
+                    ),
+                );
+                self.trailing(self.last_line);
+            }
+            TopLevel::TypeAlias(t) => {
+                self.leading(0, self.node_line(t.name.node_id));
+                self.line(0, &format!("type {} = {}", t.name.name, type_(&t.ty)));
+                self.trailing(self.last_line);
+            }
+            TopLevel::Newtype(n) => {
+                self.leading(0, self.node_line(n.node_id));
+                self.line(0, &format!("newtype {} = {}", n.name.name, type_(&n.ty)));
+                self.trailing(self.last_line);
+            }
+            TopLevel::Trait(t) => self.trait_(t),
+            TopLevel::Impl(i) => {
+                self.leading(0, self.node_line(i.node_id));
+
+                let types = i
+                    .types
+                    .iter()
+                    .map(|t| format!(" {}", type_(t)))
+                    .collect::<String>();
+
+                self.line(0, &format!("impl {}{}", type_(&i.name), types));
+                self.trailing(self.last_line);
+
+                for f in &i.defs {
+                    self.function(f, INDENT, true);
+                }
+
+                self.close_block(INDENT);
+            }
+            TopLevel::Struct(s) => {
+                self.leading(0, self.node_line(s.name.node_id));
+                self.line(0, &format!("struct {}", s.name.name));
+                self.trailing(self.last_line);
+
+                for p in &s.defs {
+                    self.leading(INDENT, self.node_line(p.node_id));
+                    self.line(INDENT, &prototype(p, false));
+                    self.trailing(self.last_line);
+                }
+
+                self.close_block(INDENT);
+            }
+            TopLevel::Mod(name, _) => {
+                self.leading(0, self.node_line(name.node_id));
+                self.line(0, &format!("mod {}", name.name));
+                self.trailing(self.last_line);
+            }
+            TopLevel::Use(u) => {
+                self.leading(0, self.node_line(u.node_id));
+                self.line(0, &format!("use {}", path(&u.path)));
+                self.trailing(self.last_line);
+            }
+            TopLevel::Infix(op, precedence) => {
+                self.leading(0, self.node_line(op.0.node_id));
+                self.line(0, &format!("infix {} {}", op.0.name, precedence));
+                self.trailing(self.last_line);
+            }
+        }
+    }
+
+    fn trait_(&mut self, t: &Trait) {
+        // The prototypes and the default methods are kept apart, in their own order
+        let mut defs = t
+            .defs
+            .iter()
+            .map(|p| (p.node_id, Some(p), None))
+            .chain(t.default_impl.iter().map(|f| (f.node_id, None, Some(f))))
+            .collect::<Vec<_>>();
+
+        defs.sort_by_key(|(node_id, _, _)| *node_id);
+
+        let first_line = defs
+            .first()
+            .map(|(node_id, _, _)| self.node_line(*node_id))
+            .unwrap_or(self.last_line + 1);
+
+        self.leading(0, self.comments.header_line(first_line));
+        self.line(0, &format!("trait {}", type_(&t.name)));
+        self.trailing(self.last_line);
+
+        for (node_id, p, f) in defs {
+            match (p, f) {
+                (Some(p), _) => {
+                    self.leading(INDENT, self.node_line(node_id));
+                    self.line(INDENT, &prototype(p, true));
+                    self.trailing(self.last_line);
+                }
+                (_, Some(f)) => self.function(f, INDENT, true),
+                _ => (),
+            }
+        }
+
+        self.close_block(INDENT);
+    }
+
+    // The methods of the traits and implementations are printed with the `@` sugar
+    fn function(&mut self, f: &FunctionDecl, indent: usize, in_impl: bool) {
+        let is_self = in_impl && f.arguments.first().is_some_and(|arg| arg.name == "self");
+
+        let mut stmts = f.body.stmts.iter().collect::<Vec<_>>();
+
+        let returns_self = is_self && stmts.last().is_some_and(|stmt| is_return_self(stmt));
+
+        if returns_self {
+            stmts.pop();
+        }
+
+        let arrow = if returns_self { "@->" } else { "->" };
+
+        let header = if is_self {
+            let args = f.arguments[1..]
+                .iter()
+                .map(|arg| format!(" {}", arg.name))
+                .collect::<String>();
+
+            format!("@{}:{} {}", f.name.name, args, arrow)
+        } else if f.arguments.is_empty() {
+            format!("{}: {}", f.name.name, arrow)
+        } else {
+            let args = f
+                .arguments
+                .iter()
+                .map(|arg| arg.name.clone())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            format!("{}: {} {}", f.name.name, args, arrow)
+        };
+
+        self.function_body(f.node_id, &header, &stmts, indent);
+    }
+
+    fn clause(&mut self, clause: &Clause) {
+        let patterns = clause
+            .patterns
+            .iter()
+            .map(|pattern| match pattern {
+                Pattern::Identifier(id) => id.name.clone(),
+                Pattern::Literal(lit, _) => self.literal(lit, 0),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let guard = clause
+            .guard
+            .as_ref()
+            .map(|guard| format!(" | {}", self.expression(guard, Ctx::Line, 0)))
+            .unwrap_or_default();
+
+        let header = format!("{}: {}{} ->", clause.name.name, patterns, guard);
+
+        let stmts = clause.body.stmts.iter().collect::<Vec<_>>();
+
+        self.function_body(clause.node_id, &header, &stmts, 0);
+    }
+
+    // The body on the line of the header when it fits, or indented below it
+    fn function_body(
+        &mut self,
+        node_id: NodeId,
+        header: &str,
+        stmts: &[&Statement],
+        indent: usize,
+    ) {
+        let line = self.node_line(node_id);
+
+        self.leading(indent, line);
+
+        match self.inline_body(stmts, indent, header.len() + 1) {
+            Some((text, stmt_line)) => {
+                self.line(indent, &format!("{} {}", header, text));
+                self.trailing(line);
+                self.trailing(stmt_line);
+
+                self.last_line = stmt_line;
+            }
+            None => {
+                self.line(indent, header);
+                self.trailing(line);
+
+                self.body(stmts, indent + INDENT);
+            }
+        }
+    }
+
+    // Statements
+
+    fn body(&mut self, stmts: &[&Statement], indent: usize) {
+        for stmt in stmts {
+            self.statement(stmt, indent);
+        }
+
+        self.close_block(indent);
+    }
+
+    // The text of a body that fits on the line of its header, of `width` columns
+    fn inline_body(
+        &self,
+        stmts: &[&Statement],
+        indent: usize,
+        width: usize,
+    ) -> Option<(String, usize)> {
+        let stmt = match stmts {
+            [stmt] => stmt,
+            _ => return None,
+        };
+
+        let inline = match stmt {
+            Statement::Expression(_) => true,
+            Statement::Assign(assign) => !assign.is_let,
+            _ => false,
+        };
+
+        let line = self.statement_line(stmt);
+
+        if !inline || self.comments.has_before(line) {
+            return None;
+        }
+
+        let text = self.simple_statement(stmt, indent);
+
+        if text.contains('\n') || indent + width + text.len() > MAX_WIDTH {
+            return None;
+        }
+
+        Some((text, line))
+    }
+
+    fn statement(&mut self, stmt: &Statement, indent: usize) {
+        let line = self.statement_line(stmt);
+
+        self.leading(indent, line);
+
+        match stmt {
+            Statement::If(if_) => self.if_(if_, indent, ""),
+            Statement::For(For::In(for_in)) => {
+                self.line(
+                    indent,
+                    &format!(
+                        "for {} in {}",
+                        for_in.value.name,
+                        self.expression(&for_in.expr, Ctx::Line, indent)
+                    ),
+                );
+                self.trailing(line);
+
+                self.body(
+                    &for_in.body.stmts.iter().collect::<Vec<_>>(),
+                    indent + INDENT,
+                );
+            }
+            Statement::For(For::While(while_)) => {
+                self.line(
+                    indent,
+                    &format!(
+                        "while {}",
+                        self.expression(&while_.predicat, Ctx::Line, indent)
+                    ),
+                );
+                self.trailing(line);
+
+                self.body(
+                    &while_.body.stmts.iter().collect::<Vec<_>>(),
+                    indent + INDENT,
+                );
+            }
+            _ => {
+                let text = self.simple_statement(stmt, indent);
+
+                self.line(indent, &text);
+
+                // A comment after the first line would cut the statement
+                if !text.contains('\n') {
+                    self.trailing(line);
+                }
+            }
+        }
+    }
+
+    // The `then` and `else` lines must directly follow the previous ones, so no comment
+    // nor blank line is printed before them
+    fn if_(&mut self, if_: &If, indent: usize, prefix: &str) {
+        let line = self.node_line(if_.node_id);
+
+        self.line(
+            indent,
+            &format!(
+                "{}if {}",
+                prefix,
+                self.expression(&if_.predicat, Ctx::Line, indent)
+            ),
+        );
+        self.trailing(line);
+
+        self.last_line = line;
+
+        self.branch(&if_.body, indent, "then");
+
+        match if_.else_.as_deref() {
+            Some(Else::If(else_if)) => self.if_(else_if, indent, "else "),
+            Some(Else::Body(body)) => {
+                let stmts = body.stmts.iter().collect::<Vec<_>>();
+
+                match self.inline_body(&stmts, indent, "else ".len()) {
+                    Some((text, stmt_line)) => {
+                        self.line(indent, &format!("else {}", text));
+                        self.trailing(stmt_line);
+
+                        self.last_line = stmt_line;
+                    }
+                    None => {
+                        self.line(indent, "else");
+
+                        self.body(&stmts, indent + INDENT);
+                    }
+                }
+            }
+            None => (),
+        }
+    }
+
+    fn branch(&mut self, body: &Body, indent: usize, keyword: &str) {
+        let stmts = body.stmts.iter().collect::<Vec<_>>();
+
+        match self.inline_body(&stmts, indent, keyword.len() + 1) {
+            Some((text, stmt_line)) => {
+                self.line(indent, &format!("{} {}", keyword, text));
+                self.trailing(stmt_line);
+
+                self.last_line = stmt_line;
+            }
+            None => self.body(&stmts, indent + INDENT),
+        }
+    }
+
+    fn simple_statement(&self, stmt: &Statement, indent: usize) -> String {
+        match stmt {
+            Statement::Expression(expr) => self.expression(expr, Ctx::Block, indent),
+            Statement::Assign(assign) => {
+                let name = match &assign.name {
+                    AssignLeftSide::Identifier(expr)
+                    | AssignLeftSide::Indice(expr)
+                    | AssignLeftSide::Dot(expr) => self.expression(expr, Ctx::Line, indent),
+                };
+
+                let ty = match &assign.ty {
+                    Some(ty) => format!(": {}", type_(&ty.ty)),
+                    None => String::new(),
+                };
+
+                format!(
+                    "{}{}{} = {}",
+                    if assign.is_let { "let " } else { "" },
+                    name,
+                    ty,
+                    self.expression(&assign.value, Ctx::Block, indent)
+                )
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    // Expressions
+
+    fn expression(&self, expr: &Expression, ctx: Ctx, indent: usize) -> String {
+        match expr {
+            Expression::BinopExpr(left, op, right) => {
+                let ctx = if ctx == Ctx::Block { Ctx::Line } else { ctx };
+
+                format!(
+                    "{} {} {}",
+                    self.unary(left, ctx, indent),
+                    op.0.name,
+                    self.expression(right, ctx, indent)
+                )
+            }
+            Expression::UnaryExpr(unary) => self.unary(unary, ctx, indent),
+            Expression::NativeOperation(op, left, right) => {
+                format!("~{:?} {} {}", op.kind, left.name, right.name)
+            }
+            Expression::StructCtor(ctor) => self.struct_ctor(ctor, ctx, indent),
+            Expression::Return(expr) => format!("return {}", self.expression(expr, ctx, indent)),
+            Expression::Ascription(expr, ty) => {
+                format!("{} : {}", self.expression(expr, ctx, indent), type_(&ty.ty))
+            }
+        }
+    }
+
+    fn struct_ctor(&self, ctor: &StructCtor, ctx: Ctx, indent: usize) -> String {
+        if ctor.defs.is_empty() {
+            return ctor.name.name.clone();
+        }
+
+        let fields = ctor
+            .defs
+            .iter()
+            .enumerate()
+            .map(|(i, (name, value))| {
+                let ctx = if i + 1 == ctor.defs.len() {
+                    Ctx::Last
+                } else {
+                    Ctx::Nested
+                };
+
+                format!("{}: {}", name.name, self.expression(value, ctx, indent))
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let one_line = format!("{} {}", ctor.name.name, fields);
+
+        let has_ctor = ctor
+            .defs
+            .iter()
+            .any(|(_, value)| matches!(value, Expression::StructCtor(_)));
+
+        match ctx {
+            Ctx::Block if has_ctor || one_line.len() > MAX_CTOR_WIDTH => {
+                let field_indent = indent + INDENT;
+
+                ctor.defs
+                    .iter()
+                    .fold(ctor.name.name.clone(), |acc, (name, value)| {
+                        format!(
+                            "{}\n{}{}: {}",
+                            acc,
+                            " ".repeat(field_indent),
+                            name.name,
+                            self.expression(value, Ctx::Block, field_indent)
+                        )
+                    })
+            }
+            Ctx::Nested => format!("({})", one_line),
+            _ => one_line,
+        }
+    }
+
+    fn unary(&self, unary: &UnaryExpr, ctx: Ctx, indent: usize) -> String {
+        match unary {
+            UnaryExpr::PrimaryExpr(primary) => self.primary(primary, ctx, indent),
+            UnaryExpr::UnaryExpr(op, unary) => {
+                format!("{}{}", op.0.name, self.unary(unary, ctx, indent))
+            }
+        }
+    }
+
+    fn primary(&self, primary: &PrimaryExpr, ctx: Ctx, indent: usize) -> String {
+        let mut secondaries = primary.secondaries.as_deref().unwrap_or_default();
+
+        let mut text = match &primary.op {
+            Operand::Identifier(id) if id.path.len() == 1 && id.path[0].name == "self" => {
+                match secondaries.first() {
+                    Some(SecondaryExpr::Dot(field)) => {
+                        secondaries = &secondaries[1..];
+
+                        format!("@{}", field.name)
+                    }
+                    _ => "@".to_string(),
+                }
+            }
+            Operand::Identifier(id) => path(id),
+            Operand::Literal(literal) => self.literal(literal, indent),
+            Operand::Expression(expr) => {
+                format!("({})", self.expression(expr, Ctx::Line, indent))
+            }
+        };
+
+        for (i, secondary) in secondaries.iter().enumerate() {
+            match secondary {
+                SecondaryExpr::Dot(field) => {
+                    text += &format!(".{}", field.name);
+                }
+                SecondaryExpr::Indice(expr) => {
+                    text += &format!("[{}]", self.expression(expr, Ctx::Line, indent));
+                }
+                SecondaryExpr::Arguments(args) if args.is_empty() => text += "!",
+                SecondaryExpr::Arguments(args) => {
+                    let args = args
+                        .iter()
+                        .enumerate()
+                        .map(|(i, arg)| {
+                            let ctx = if i + 1 == args.len() {
+                                Ctx::Last
+                            } else {
+                                Ctx::Nested
+                            };
+
+                            self.unary(&arg.arg, ctx, indent)
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    // Without parenthesis, the arguments take everything up to the end
+                    if i + 1 == secondaries.len() && ctx != Ctx::Nested {
+                        text += &format!(" {}", args);
+                    } else {
+                        text += &format!("({})", args);
+                    }
+                }
+            }
+        }
+
+        text
+    }
+
+    fn literal(&self, literal: &Literal, indent: usize) -> String {
+        match &literal.kind {
+            LiteralKind::Bool(b) => b.to_string(),
+            LiteralKind::Number(n) => n.to_string(),
+            LiteralKind::Float(f) => {
+                let f = f.to_string();
+
+                if f.contains('.') {
+                    f
+                } else {
+                    f + ".0"
+                }
+            }
+            LiteralKind::Array(array) => {
+                let values = array
+                    .values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| {
+                        let ctx = if i + 1 == array.values.len() {
+                            Ctx::Last
+                        } else {
+                            Ctx::Nested
+                        };
+
+                        self.expression(value, ctx, indent)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                format!("[{}]", values)
+            }
+            LiteralKind::String(s) => format!("\"{}\"", s.chars().map(escape).collect::<String>()),
+            LiteralKind::Char(c) => format!("'{}'", escape(*c)),
+        }
+    }
+}
+
+fn is_return_self(stmt: &Statement) -> bool {
+    // The `self` appended by `@->` is the only primary without a node
+    match stmt {
+        Statement::Expression(expr) => match &**expr {
+            Expression::UnaryExpr(UnaryExpr::PrimaryExpr(primary)) => {
+                primary.node_id == 0
+                    && primary.secondaries.is_none()
+                    && matches!(&primary.op, Operand::Identifier(id) if id.path.len() == 1 && id.path[0].name == "self")
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn escape(c: char) -> String {
+    match c {
+        '\\' => "\\\\".to_string(),
+        '"' => "\\\"".to_string(),
+        '\'' => "\\'".to_string(),
+        '\n' => "\\n".to_string(),
+        '\r' => "\\r".to_string(),
+        '\0' => "\\0".to_string(),
+        c => c.to_string(),
+    }
+}
+
+fn path(path: &IdentifierPath) -> String {
+    path.path
+        .iter()
+        .map(|id| id.name.clone())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+// In the traits, the methods taking `@` first are printed with the `@` sugar, but not
+// the operators
+fn prototype(p: &Prototype, in_trait: bool) -> String {
+    let args = &p.signature.arguments;
+
+    let is_method = p.name.name.chars().all(|c| c.is_alphanumeric() || c == '_');
+
+    if in_trait && is_method && matches!(args.first(), Some(Type::ForAll(name)) if name == "@") {
+        let signature = FuncType::new(args[1..].to_vec(), (*p.signature.ret).clone());
+
+        format!("@{}: {}", p.name.name, signature_(&signature))
+    } else {
+        format!("{}: {}", p.name.name, signature_(&p.signature))
+    }
+}
+
+fn signature_(signature: &FuncType) -> String {
+    signature
+        .arguments
+        .iter()
+        .chain(std::iter::once(&*signature.ret))
+        .map(type_)
+        .collect::<Vec<_>>()
+        .join(" => ")
+}
+
+fn type_(t: &Type) -> String {
+    match t {
+        Type::Primitive(PrimitiveType::Array(inner, _)) => format!("[{}]", type_(inner)),
+        Type::Primitive(p) => p.get_name(),
+        Type::Func(f) => format!("({})", signature_(f)),
+        Type::Struct(s) => s.name.clone(),
+        Type::Trait(name) | Type::ForAll(name) | Type::Newtype(name, _) => name.clone(),
+        Type::Undefined(_) => t.get_name(),
+    }
+}
+
+// The start of the first node of a statement
+struct FirstOffset<'a> {
+    spans: &'a HashMap<NodeId, Span>,
+    offset: Option<usize>,
+}
+
+impl<'a> FirstOffset<'a> {
+    fn add(&mut self, node_id: NodeId) {
+        // Some desugared nodes are not in the source
+        if node_id == 0 || node_id == u64::MAX {
+            return;
+        }
+
+        if let Some(span) = self.spans.get(&node_id) {
+            self.offset = Some(
+                self.offset
+                    .map_or(span.start, |offset| offset.min(span.start)),
+            );
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for FirstOffset<'a> {
+    fn visit_identifier(&mut self, id: &'a Identifier) {
+        self.add(id.node_id);
+    }
+
+    fn visit_primary_expr(&mut self, primary: &'a PrimaryExpr) {
+        self.add(primary.node_id);
+
+        walk_primary_expr(self, primary);
+    }
+
+    fn visit_literal(&mut self, literal: &'a Literal) {
+        self.add(literal.node_id);
+
+        walk_literal(self, literal);
+    }
+
+    fn visit_if(&mut self, if_: &'a If) {
+        self.add(if_.node_id);
+
+        walk_if(self, if_);
+    }
+
+    fn visit_native_operator(&mut self, op: &'a NativeOperator) {
+        self.add(op.node_id);
+    }
+}
@@ -0,0 +1,295 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::format_str;
+use crate::Config;
+
+fn format(source: &str) -> String {
+    let config = Config {
+        quiet: true,
+        ..Config::default()
+    };
+
+    format_str("main.rk", source, &config).unwrap()
+}
+
+fn source_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+
+        if path.is_dir() {
+            source_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "rk") {
+            files.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod layout {
+    use super::*;
+
+    #[test]
+    fn indentation_and_spacing() {
+        let source = "f:   a,b   ->   a
+main: ->
+    let x = f 1,2
+    if x == 1
+        x
+    else
+        let y = 2
+        y
+";
+
+        assert_eq!(
+            format(source),
+            "f: a, b -> a
+main: ->
+  let x = f 1, 2
+  if x == 1
+  then x
+  else
+    let y = 2
+    y
+"
+        );
+    }
+
+    #[test]
+    fn struct_ctors() {
+        let source = include_str!("../testcases/basic/multi_style_struct_ctor/main.rk");
+
+        assert_eq!(
+            format(source),
+            "struct Foo
+  bar: Int64
+  bar2: String
+main: ->
+  let foo = Foo bar: 42, bar2: \"hello\"
+  let foo2 = Foo bar: 3, bar2: \"world\"
+  let foo3 = Foo bar: 4, bar2: \"world2\"
+  foo.bar
+"
+        );
+    }
+
+    #[test]
+    fn nested_struct_ctors() {
+        let source = "main: ->
+  let a = Foo
+    bar: Bar
+      baz: 1
+    qux: [(Bar baz: 2), Bar baz: 3]
+  let b = Foo bar: (Bar baz: 1), qux: []
+  a
+";
+
+        assert_eq!(
+            format(source),
+            "main: ->
+  let a = Foo
+    bar: Bar baz: 1
+    qux: [(Bar baz: 2), Bar baz: 3]
+  let b = Foo bar: (Bar baz: 1), qux: []
+  a
+"
+        );
+    }
+
+    #[test]
+    fn calls_and_self() {
+        let source = "impl Foo
+  @get: a @->
+    self.bar(a, f(b, c)).baz()
+    @qux[0]
+";
+
+        assert_eq!(
+            format(source),
+            "impl Foo
+  @get: a @->
+    @bar(a, f b, c).baz!
+    @qux[0]
+"
+        );
+    }
+
+    #[test]
+    fn consts() {
+        assert_eq!(
+            format("const   max_len :Int64=  4096\nconst names: [String] = [\"a\",\"b\"]\n"),
+            "const max_len: Int64 = 4096\nconst names: [String] = [\"a\", \"b\"]\n"
+        );
+    }
+
+    #[test]
+    fn ascriptions() {
+        assert_eq!(
+            format("main: ->\n  let x :Float64=  1.0\n  (x:Float64)\n"),
+            "main: ->\n  let x: Float64 = 1.0\n  (x : Float64)\n"
+        );
+    }
+
+    #[test]
+    fn type_aliases() {
+        assert_eq!(
+            format("type   Row=[Int64]\nnewtype UserId =  Int64\n\nsum:Row=>UserId\n"),
+            "type Row = [Int64]\nnewtype UserId = Int64\n\nsum: Row => UserId\n"
+        );
+    }
+
+    #[test]
+    fn clauses() {
+        assert_eq!(
+            format("fact:   0 ->   1\nfact: n->n * fact (n - 1)\nsign: n  |  n < 0->  2\n"),
+            "fact: 0 -> 1\nfact: n -> n * fact (n - 1)\nsign: n | n < 0 -> 2\n"
+        );
+    }
+
+    #[test]
+    fn tests() {
+        let source = "test   \"adds \\\"numbers\\\"\"  :   ->
+    let x = 1 + 1
+    assert_eq x, 2
+";
+
+        assert_eq!(
+            format(source),
+            "test \"adds \\\"numbers\\\"\": ->
+  let x = 1 + 1
+  assert_eq x, 2
+"
+        );
+    }
+}
+
+#[cfg(test)]
+mod comments {
+    use super::*;
+
+    #[test]
+    fn are_kept() {
+        let source = "# Header comment
+
+# About Point
+struct Point   # trailing on struct
+  x: Int64 # the x
+  # before y
+  y: Int64
+
+@[allow(unused-function)]
+helper:   a,b   ->   a   # inline body comment
+
+main: ->
+  # first
+  let p = Point x: 1, y: 2   # make it
+
+
+  for i in [1, 2]
+    foo i
+    # end of for
+  p.x # last
+  # end of main
+
+# trailing file comment
+";
+
+        assert_eq!(
+            format(source),
+            "# Header comment
+
+# About Point
+struct Point # trailing on struct
+  x: Int64 # the x
+  # before y
+  y: Int64
+
+@[allow(unused-function)]
+helper: a, b -> a # inline body comment
+
+main: ->
+  # first
+  let p = Point x: 1, y: 2 # make it
+
+  for i in [1, 2]
+    foo i
+    # end of for
+  p.x # last
+  # end of main
+
+# trailing file comment
+"
+        );
+    }
+
+    #[test]
+    fn not_in_strings() {
+        let source = "/* About f
+   on two lines */
+f: ->   \"issue #12\"   # the issue
+";
+
+        assert_eq!(
+            format(source),
+            "/* About f
+   on two lines */
+f: -> \"issue #12\" # the issue
+"
+        );
+    }
+
+    #[test]
+    fn keep_bodies_on_their_own_lines() {
+        let source = "main: ->
+  # Some comment
+  42
+";
+
+        assert_eq!(format(source), source);
+    }
+}
+
+#[cfg(test)]
+mod testcases {
+    use super::*;
+
+    // The files that do not parse, on purpose, are skipped
+    #[test]
+    fn are_formatted_idempotently() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+        let mut files = vec![];
+
+        source_files(&root.join("src/lib/testcases"), &mut files);
+        source_files(&root.join("std/src"), &mut files);
+
+        let config = Config {
+            quiet: true,
+            ..Config::default()
+        };
+
+        let mut formatted_nb = 0;
+
+        for path in files {
+            let source = fs::read_to_string(&path).unwrap();
+
+            let formatted = match format_str(path.to_str().unwrap(), &source, &config) {
+                Ok(formatted) => formatted,
+                Err(_) => continue,
+            };
+
+            assert_eq!(
+                format_str(path.to_str().unwrap(), &formatted, &config).unwrap(),
+                formatted,
+                "{} is not formatted idempotently",
+                path.display()
+            );
+
+            formatted_nb += 1;
+        }
+
+        assert!(formatted_nb > 80);
+    }
+}
@@ -1,4 +1,13 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::diagnostics::{LintLevel, LintLevels};
+
+pub static MANIFEST_FILE: &str = "Rock.toml";
 
 #[derive(Debug, Clone)]
 pub enum PackageType {
@@ -12,15 +21,242 @@ impl Default for PackageType {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OptLevel {
+    O0,
+    O1,
+    O2,
+    O3,
+    Os,
+}
+
+impl OptLevel {
+    pub fn parse(level: &str) -> Option<Self> {
+        match level {
+            "0" => Some(Self::O0),
+            "1" => Some(Self::O1),
+            "2" => Some(Self::O2),
+            "3" => Some(Self::O3),
+            "s" => Some(Self::Os),
+            _ => None,
+        }
+    }
+
+    // The name of the level in LLVM's standard pipelines and in clang's flags
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::O0 => "O0",
+            Self::O1 => "O1",
+            Self::O2 => "O2",
+            Self::O3 => "O3",
+            Self::Os => "Os",
+        }
+    }
+}
+
+impl Default for OptLevel {
+    fn default() -> Self {
+        BuildProfile::default().opt_level()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BuildProfile {
+    Debug,
+    #[default]
+    Release,
+}
+
+impl BuildProfile {
+    pub fn opt_level(&self) -> OptLevel {
+        match self {
+            Self::Debug => OptLevel::O0,
+            Self::Release => OptLevel::O3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EmitKind {
+    Obj,
+    Asm,
+    LlvmIr,
+    LlvmBc,
+    Link,
+}
+
+impl EmitKind {
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "obj" => Some(Self::Obj),
+            "asm" => Some(Self::Asm),
+            "llvm-ir" => Some(Self::LlvmIr),
+            "llvm-bc" => Some(Self::LlvmBc),
+            "link" => Some(Self::Link),
+            _ => None,
+        }
+    }
+
+    // The file written in the build folder, linking uses the object one
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Self::Obj | Self::Link => "out.o",
+            Self::Asm => "out.s",
+            Self::LlvmIr => "out.ll",
+            Self::LlvmBc => "out.bc",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CrateType {
+    #[default]
+    Bin,
+    StaticLib,
+    Cdylib,
+}
+
+impl CrateType {
+    pub fn parse(crate_type: &str) -> Option<Self> {
+        match crate_type {
+            "bin" => Some(Self::Bin),
+            "staticlib" => Some(Self::StaticLib),
+            "cdylib" => Some(Self::Cdylib),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MessageFormat {
+    #[default]
+    Human,
+    Json, // One JSON object per line
+}
+
+impl MessageFormat {
+    pub fn parse(format: &str) -> Option<Self> {
+        match format {
+            "human" => Some(Self::Human),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct ProjectConfig {
     pub name: String,
     pub base_path: PathBuf,
     pub package_type: PackageType,
+    pub profile: BuildProfile,
+    pub lints: LintLevels,
     pub externs: HashMap<String, PathBuf>, // Packages name and MetaData path
     pub entry_point: PathBuf,
 }
 
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    package: ManifestPackage,
+    lints: Option<HashMap<String, String>>, // name => level
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestPackage {
+    name: String,
+    #[serde(rename = "type")]
+    package_type: Option<String>,
+    profile: Option<String>,
+}
+
+impl ProjectConfig {
+    // Walks up from `from` until a folder containing a `Rock.toml` is found
+    pub fn find_manifest(from: &Path) -> Option<PathBuf> {
+        from.ancestors()
+            .map(|dir| dir.join(MANIFEST_FILE))
+            .find(|manifest| manifest.is_file())
+    }
+
+    pub fn from_manifest(manifest_path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(manifest_path)
+            .map_err(|e| format!("Cannot read {}: {}", manifest_path.display(), e))?;
+
+        let manifest: Manifest = toml::from_str(&content)
+            .map_err(|e| format!("Invalid {}: {}", manifest_path.display(), e))?;
+
+        let package_type = match manifest.package.package_type.as_deref() {
+            None | Some("bin") => PackageType::Bin,
+            Some("lib") => PackageType::Lib,
+            Some(other) => return Err(format!("Unknown package type: {}", other)),
+        };
+
+        let profile = match manifest.package.profile.as_deref() {
+            None | Some("release") => BuildProfile::Release,
+            Some("debug") => BuildProfile::Debug,
+            Some(other) => return Err(format!("Unknown build profile: {}", other)),
+        };
+
+        let base_path = manifest_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        let mut lints = LintLevels::default();
+
+        for (name, level) in manifest.lints.unwrap_or_default() {
+            let level = LintLevel::parse(&level)
+                .ok_or_else(|| format!("Unknown level for lint {}: {}", name, level))?;
+
+            lints.set(&name, level)?;
+        }
+
+        let mut project_config = Self::new(manifest.package.name, base_path, package_type);
+
+        project_config.profile = profile;
+        project_config.lints = lints;
+
+        Ok(project_config)
+    }
+
+    // The root as seen from `current_dir` when it is inside the project, so that the
+    // paths in the diagnostics stay short
+    pub fn relative_to(&mut self, current_dir: &Path) {
+        if let Ok(rest) = current_dir.strip_prefix(&self.base_path) {
+            self.base_path = rest.components().map(|_| "..").collect();
+
+            if self.base_path.as_os_str().is_empty() {
+                self.base_path = PathBuf::from(".");
+            }
+        }
+    }
+
+    // A path relative to the root of the project
+    pub fn path(&self, path: &Path) -> PathBuf {
+        self.base_path.join(path.strip_prefix("./").unwrap_or(path))
+    }
+
+    pub fn entry_file(&self) -> PathBuf {
+        self.path(&self.entry_point)
+    }
+
+    pub fn new(name: String, base_path: PathBuf, package_type: PackageType) -> Self {
+        let entry_point = match package_type {
+            PackageType::Bin => PathBuf::from("./src/main.rk"),
+            PackageType::Lib => PathBuf::from("./src/lib.rk"),
+        };
+
+        Self {
+            name,
+            base_path,
+            package_type,
+            profile: BuildProfile::default(),
+            lints: LintLevels::default(),
+            externs: HashMap::new(),
+            entry_point,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Config {
     pub project_config: ProjectConfig,
@@ -31,6 +267,48 @@ pub struct Config {
     pub verbose: bool,
     pub quiet: bool,
     pub build_folder: PathBuf,
-    pub no_optimize: bool,
+    pub opt_level: OptLevel,
+    pub passes: Option<String>, // Custom pipeline that replaces the one of `opt_level`
+    pub emit: Vec<EmitKind>,
+    pub crate_type: CrateType,
+    pub output_name: Option<String>,
+    pub target: Option<String>, // LLVM triple, the host one when None
+    pub debug_info: bool,
+    pub message_format: MessageFormat,
+    pub lints: LintLevels, // The ones of the project, overriden by the command line
     pub std: bool,
+    pub timings: bool,
+    pub test: bool, // Builds the test harness instead of the main function
+    pub overlays: Arc<HashMap<PathBuf, String>>, // Unsaved files, read instead of the disk ones
+}
+
+impl Config {
+    // The linked executable or library, in the build folder
+    pub fn output_file(&self) -> PathBuf {
+        let name = match &self.output_name {
+            Some(name) => name.clone(),
+            None => {
+                let name = if self.project_config.name.is_empty() {
+                    "out"
+                } else {
+                    &self.project_config.name
+                };
+
+                match self.crate_type {
+                    CrateType::Bin => "a.out".to_string(),
+                    CrateType::StaticLib => format!("lib{}.a", name),
+                    CrateType::Cdylib => format!("lib{}.so", name),
+                }
+            }
+        };
+
+        self.build_folder.join(name)
+    }
+
+    pub fn emitted_files(&self) -> Vec<PathBuf> {
+        self.emit
+            .iter()
+            .map(|kind| self.build_folder.join(kind.file_name()))
+            .collect()
+    }
 }
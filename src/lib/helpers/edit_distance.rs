@@ -0,0 +1,110 @@
+// Levenshtein distance counted in chars, where swapping two adjacent chars is a single edit
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+
+    // dist[i][j] is the distance between the first i chars of a and the first j chars of b
+    let mut dist = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i;
+    }
+
+    for (j, cell) in dist[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+
+            dist[i][j] = (dist[i - 1][j] + 1)
+                .min(dist[i][j - 1] + 1)
+                .min(dist[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                dist[i][j] = dist[i][j].min(dist[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    dist[a.len()][b.len()]
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .map(|c| c.is_alphabetic() || c == '_')
+        .unwrap_or(false)
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+// The closest candidate to a misspelled `name`, if it is close enough to be a typo.
+// The ties are broken by name, as the candidates often come from a HashMap.
+// Only the identifiers are looked at, a single char is anything but a typo.
+pub fn find_best_match<'a, I>(name: &str, candidates: I) -> Option<String>
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    if name.chars().count() < 2 || !is_identifier(name) {
+        return None;
+    }
+
+    let max_distance = (name.chars().count() / 3).max(1);
+
+    candidates
+        .into_iter()
+        .filter(|candidate| candidate.as_str() != name && is_identifier(candidate))
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("foo", "foo"), 0);
+        assert_eq!(edit_distance("foo", ""), 3);
+        assert_eq!(edit_distance("foo", "fo"), 1);
+        assert_eq!(edit_distance("foo", "fao"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("xpos", "xpso"), 1);
+    }
+
+    #[test]
+    fn best_match() {
+        let candidates = vec![
+            "print".to_string(),
+            "printl".to_string(),
+            "main".to_string(),
+        ];
+
+        assert_eq!(
+            find_best_match("prin", &candidates),
+            Some("print".to_string())
+        );
+        assert_eq!(
+            find_best_match("pritnl", &candidates),
+            Some("printl".to_string())
+        );
+        assert_eq!(find_best_match("foo", &candidates), None);
+        assert_eq!(find_best_match("main", &candidates), None);
+    }
+
+    #[test]
+    fn identifiers_only() {
+        let candidates = vec!["n".to_string(), "+".to_string(), "ab".to_string()];
+
+        assert_eq!(find_best_match("-", &candidates), None);
+        assert_eq!(find_best_match("m", &candidates), None);
+        assert_eq!(find_best_match("++", &candidates), None);
+        assert_eq!(find_best_match("ac", &candidates), Some("ab".to_string()));
+    }
+}
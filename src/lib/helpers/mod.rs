@@ -3,9 +3,14 @@ pub mod walk_helpers;
 #[macro_use]
 pub mod class_name;
 pub mod config;
+pub mod edit_distance;
 pub mod scopes;
+pub mod timings;
 
 pub mod test_utils;
 
+#[cfg(test)]
+mod tests;
+
 pub use class_name::*;
 pub use walk_helpers::*;
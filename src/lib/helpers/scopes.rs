@@ -43,6 +43,11 @@ where
         None
     }
 
+    // Every name visible from the current scope
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.scopes.iter().flat_map(|scope| scope.keys())
+    }
+
     pub fn add(&mut self, s: K, val: T) {
         self.scopes.last_mut().unwrap().insert(s, val);
     }
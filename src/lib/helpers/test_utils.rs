@@ -6,11 +6,11 @@ use std::{
 };
 
 fn build(input: String, config: Config) -> bool {
-    let file = SourceFile {
-        file_path: PathBuf::from("src/lib").join(&config.project_config.entry_point),
-        mod_path: PathBuf::from("main"),
-        content: input + "\n",
-    };
+    let file_path = PathBuf::from("src/lib").join(&config.project_config.entry_point);
+
+    let mut file = SourceFile::from_str(file_path.to_str().unwrap(), &input).unwrap();
+
+    file.mod_path = PathBuf::from("main");
 
     if let Err(_e) = crate::compile_str(&file, &config) {
         return false;
@@ -45,7 +45,8 @@ fn build(input: String, config: Config) -> bool {
     true
 }
 
-pub fn run(path: &str, input: String, config: Config) -> (i64, String) {
+// The exit code of the program, with what it printed on its standard output and error
+pub fn run(path: &str, input: String, config: Config) -> (i64, String, String) {
     let path = Path::new("src/lib/").join(path);
 
     let build_path = path.parent().unwrap().join("build");
@@ -56,19 +57,37 @@ pub fn run(path: &str, input: String, config: Config) -> (i64, String) {
     fs::create_dir_all(config.build_folder.clone()).unwrap();
 
     if !build(input, config.clone()) {
-        return (-1, String::new());
+        return (-1, String::new(), String::new());
     }
 
     let cmd = Command::new(config.build_folder.join("a.out").to_str().unwrap())
         .output()
         .expect("failed to execute BINARY");
 
-    let stdout = String::from_utf8(cmd.stderr).unwrap();
+    let stdout = String::from_utf8(cmd.stdout).unwrap();
+    let stderr = String::from_utf8(cmd.stderr).unwrap();
 
     fs::remove_dir_all(config.build_folder).unwrap();
 
     match cmd.status.code() {
-        Some(code) => (code.into(), stdout),
-        None => (-1, stdout),
+        Some(code) => (code.into(), stdout, stderr),
+        None => (-1, stdout, stderr),
+    }
+}
+
+// Same as `run`, with the interpreter instead of the compiled program. The exit code is
+// `None` when the program returns a pointer.
+pub fn interpret(input: String, config: Config) -> (Option<i64>, String) {
+    let file_path = PathBuf::from("src/lib").join(&config.project_config.entry_point);
+
+    let mut file = SourceFile::from_str(file_path.to_str().unwrap(), &input).unwrap();
+
+    file.mod_path = PathBuf::from("main");
+
+    let mut stdout = vec![];
+
+    match crate::interpret_str(&file, &config, vec!["a.out".to_string()], &mut stdout) {
+        Ok(code) => (code, String::from_utf8(stdout).unwrap()),
+        Err(_) => (Some(-1), String::new()),
     }
 }
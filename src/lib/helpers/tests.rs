@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod config {
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+
+    use crate::config::*;
+
+    fn project(name: &str, manifest: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rock_config_{}_{}", name, std::process::id()));
+
+        let _ = fs::remove_dir_all(&dir);
+
+        fs::create_dir_all(dir.join("src/nested")).unwrap();
+        fs::write(dir.join(MANIFEST_FILE), manifest).unwrap();
+
+        dir
+    }
+
+    #[test]
+    fn find_from_nested() {
+        let dir = project("nested", "[package]\nname = \"foo\"\n");
+
+        assert_eq!(
+            ProjectConfig::find_manifest(&dir.join("src/nested")),
+            Some(dir.join(MANIFEST_FILE))
+        );
+    }
+
+    #[test]
+    fn missing_manifest() {
+        let dir = project("missing", "");
+
+        fs::remove_file(dir.join(MANIFEST_FILE)).unwrap();
+
+        assert!(ProjectConfig::from_manifest(&dir.join(MANIFEST_FILE)).is_err());
+    }
+
+    #[test]
+    fn valid_manifest() {
+        let dir = project(
+            "valid",
+            "[package]\nname = \"foo\"\ntype = \"lib\"\nprofile = \"debug\"\n\n[lints]\nunused-function = \"deny\"\n",
+        );
+
+        let project_config = ProjectConfig::from_manifest(&dir.join(MANIFEST_FILE)).unwrap();
+
+        assert_eq!(project_config.name, "foo");
+        assert_eq!(project_config.base_path, dir);
+        assert!(matches!(project_config.package_type, PackageType::Lib));
+        assert_eq!(project_config.profile, BuildProfile::Debug);
+        assert_eq!(project_config.entry_file(), dir.join("src/lib.rk"));
+    }
+
+    #[test]
+    fn malformed_fields() {
+        for (name, manifest, error) in [
+            ("no_name", "[package]\n", "Invalid"),
+            (
+                "type",
+                "[package]\nname = \"foo\"\ntype = \"dylib\"\n",
+                "Unknown package type: dylib",
+            ),
+            (
+                "profile",
+                "[package]\nname = \"foo\"\nprofile = \"fast\"\n",
+                "Unknown build profile: fast",
+            ),
+            (
+                "lint_level",
+                "[package]\nname = \"foo\"\n\n[lints]\nunused-function = \"loud\"\n",
+                "Unknown level for lint unused-function: loud",
+            ),
+        ] {
+            let dir = project(name, manifest);
+
+            let err = ProjectConfig::from_manifest(&dir.join(MANIFEST_FILE)).unwrap_err();
+
+            assert!(err.starts_with(error), "{}", err);
+        }
+    }
+
+    #[test]
+    fn relative_root() {
+        let mut project_config =
+            ProjectConfig::new(String::new(), PathBuf::from("/foo"), PackageType::Bin);
+
+        project_config.relative_to(Path::new("/foo/src/nested"));
+
+        assert_eq!(
+            project_config.entry_file(),
+            PathBuf::from("../../src/main.rk")
+        );
+
+        project_config.base_path = PathBuf::from("/foo");
+        project_config.relative_to(Path::new("/foo"));
+
+        assert_eq!(project_config.entry_file(), PathBuf::from("./src/main.rk"));
+
+        // Outside of the project
+        project_config.base_path = PathBuf::from("/foo");
+        project_config.relative_to(Path::new("/bar"));
+
+        assert_eq!(
+            project_config.entry_file(),
+            PathBuf::from("/foo/src/main.rk")
+        );
+    }
+
+    #[test]
+    fn emit_kinds() {
+        for (name, kind, file_name) in [
+            ("obj", EmitKind::Obj, "out.o"),
+            ("asm", EmitKind::Asm, "out.s"),
+            ("llvm-ir", EmitKind::LlvmIr, "out.ll"),
+            ("llvm-bc", EmitKind::LlvmBc, "out.bc"),
+            ("link", EmitKind::Link, "out.o"),
+        ] {
+            assert_eq!(EmitKind::parse(name), Some(kind));
+            assert_eq!(kind.file_name(), file_name);
+        }
+
+        assert_eq!(EmitKind::parse("exe"), None);
+    }
+
+    #[test]
+    fn crate_types() {
+        assert_eq!(CrateType::parse("bin"), Some(CrateType::Bin));
+        assert_eq!(CrateType::parse("staticlib"), Some(CrateType::StaticLib));
+        assert_eq!(CrateType::parse("cdylib"), Some(CrateType::Cdylib));
+        assert_eq!(CrateType::parse("dylib"), None);
+    }
+
+    #[test]
+    fn output_file() {
+        let mut config = Config {
+            build_folder: PathBuf::from("build"),
+            ..Config::default()
+        };
+
+        assert_eq!(config.output_file(), PathBuf::from("build/a.out"));
+
+        config.crate_type = CrateType::StaticLib;
+
+        assert_eq!(config.output_file(), PathBuf::from("build/libout.a"));
+
+        config.project_config.name = "foo".to_string();
+        config.crate_type = CrateType::Cdylib;
+
+        assert_eq!(config.output_file(), PathBuf::from("build/libfoo.so"));
+
+        config.output_name = Some("bar".to_string());
+
+        assert_eq!(config.output_file(), PathBuf::from("build/bar"));
+    }
+}
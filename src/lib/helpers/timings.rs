@@ -0,0 +1,80 @@
+use std::time::{Duration, Instant};
+
+use colored::*;
+
+#[derive(Debug, Clone)]
+pub enum StageTiming {
+    Done(Duration),
+    Partial(Duration, String),
+    Skipped(String),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Timings {
+    stages: Vec<(String, StageTiming)>,
+}
+
+impl Timings {
+    pub fn done(&mut self, stage: &str, start: Instant) {
+        self.stages
+            .push((stage.to_string(), StageTiming::Done(start.elapsed())));
+    }
+
+    pub fn partial(&mut self, stage: &str, start: Instant, reason: &str) {
+        self.stages.push((
+            stage.to_string(),
+            StageTiming::Partial(start.elapsed(), reason.to_string()),
+        ));
+    }
+
+    pub fn skip(&mut self, stage: &str, reason: &str) {
+        self.stages
+            .push((stage.to_string(), StageTiming::Skipped(reason.to_string())));
+    }
+
+    pub fn print(&self) {
+        let total: Duration = self
+            .stages
+            .iter()
+            .filter_map(|(_, timing)| match timing {
+                StageTiming::Done(duration) | StageTiming::Partial(duration, _) => Some(*duration),
+                StageTiming::Skipped(_) => None,
+            })
+            .sum();
+
+        println!(
+            "{}{}{}",
+            "[".bright_black(),
+            "Timings".cyan(),
+            "]".bright_black()
+        );
+
+        for (stage, timing) in &self.stages {
+            match timing {
+                StageTiming::Done(duration) => println!(
+                    "  {:<12} {:>10.3}ms",
+                    stage,
+                    duration.as_secs_f64() * 1000.0
+                ),
+                StageTiming::Partial(duration, reason) => println!(
+                    "  {:<12} {:>10.3}ms {}",
+                    stage,
+                    duration.as_secs_f64() * 1000.0,
+                    format!("(partially skipped: {})", reason).bright_black()
+                ),
+                StageTiming::Skipped(reason) => println!(
+                    "  {:<12} {:>12} {}",
+                    stage,
+                    "skipped".yellow(),
+                    format!("({})", reason).bright_black()
+                ),
+            }
+        }
+
+        println!(
+            "  {:<12} {:>10.3}ms",
+            "Total".bright_black(),
+            total.as_secs_f64() * 1000.0
+        );
+    }
+}
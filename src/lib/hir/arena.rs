@@ -85,6 +85,8 @@ generate_hirnode_collector!(
     Assign,
     Prototype,
     FunctionDecl,
+    Const,
+    Newtype,
     ArgumentDecl,
     IdentifierPath,
     Identifier,
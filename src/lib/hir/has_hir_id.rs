@@ -22,11 +22,14 @@ macro_rules! impl_direct_get_hir_id_trait {
 impl_direct_get_hir_id_trait!(
     Prototype
     FunctionDecl
+    Const
+    Newtype
     Identifier
     If
     FunctionCall
     Indice
     Dot
+    Ascription
     Literal
     NativeOperator
 );
@@ -9,6 +9,8 @@ pub enum HirNode {
     Assign(Assign),
     Prototype(Prototype),
     FunctionDecl(FunctionDecl),
+    Const(Const),
+    Newtype(Newtype),
     ArgumentDecl(ArgumentDecl),
     IdentifierPath(IdentifierPath),
     Identifier(Identifier),
@@ -30,6 +32,8 @@ impl<'ar> HasHirId for HirNode {
             HirNode::Assign(x) => x.get_hir_id(),
             HirNode::Prototype(x) => x.get_hir_id(),
             HirNode::FunctionDecl(x) => x.get_hir_id(),
+            HirNode::Const(x) => x.get_hir_id(),
+            HirNode::Newtype(x) => x.get_hir_id(),
             HirNode::ArgumentDecl(x) => x.get_hir_id(),
             HirNode::IdentifierPath(x) => x.get_hir_id(),
             HirNode::Identifier(x) => x.get_hir_id(),
@@ -66,6 +70,8 @@ generate_hirnode_from!(
     Assign,
     Prototype,
     FunctionDecl,
+    Const,
+    Newtype,
     ArgumentDecl,
     IdentifierPath,
     Identifier,
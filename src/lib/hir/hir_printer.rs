@@ -105,6 +105,8 @@ impl_visitor_trait2!(
     Assign
     Prototype
     FunctionDecl
+    Const
+    Newtype
     StructDecl
     ArgumentDecl
     Identifier
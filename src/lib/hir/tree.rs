@@ -12,6 +12,11 @@ use crate::{
 
 use super::{arena::Arena, hir_printer, HasHirId, HirNode};
 
+pub static ENTRY_POINT: &str = "rock_main";
+
+// The globals of the constants are named after their module path
+pub static CONST_PREFIX: &str = "rock.const.";
+
 #[derive(Debug, Default)]
 pub struct Root {
     pub arena: Arena,
@@ -115,6 +120,20 @@ impl Root {
             })
     }
 
+    pub fn get_const(&self, hir_id: &HirId) -> Option<&Const> {
+        self.top_levels.iter().find_map(|top| match &top.kind {
+            TopLevelKind::Const(c) if c.hir_id == *hir_id => Some(c),
+            _ => None,
+        })
+    }
+
+    pub fn get_newtype(&self, hir_id: &HirId) -> Option<&Newtype> {
+        self.top_levels.iter().find_map(|top| match &top.kind {
+            TopLevelKind::Newtype(n) if n.hir_id == *hir_id => Some(n),
+            _ => None,
+        })
+    }
+
     pub fn get_body(&self, body_id: &FnBodyId) -> Option<&FnBody> {
         self.bodies.get(body_id)
     }
@@ -228,6 +247,8 @@ impl TopLevel {
             TopLevelKind::Extern(p) => p.hir_id.clone(),
             TopLevelKind::Function(f) => f.hir_id.clone(),
             TopLevelKind::Signature(s) => s.hir_id.clone(),
+            TopLevelKind::Const(c) => c.hir_id.clone(),
+            TopLevelKind::Newtype(n) => n.hir_id.clone(),
         }
     }
 }
@@ -237,6 +258,26 @@ pub enum TopLevelKind {
     Function(FunctionDecl),
     Extern(Prototype),
     Signature(Prototype),
+    Const(Const),
+    Newtype(Newtype),
+}
+
+// A constant has a single instance, so it keeps its ids through the monomorphization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Const {
+    pub name: Identifier,
+    pub symbol: String, // The name of its global, from its module path
+    pub ty: Type,
+    pub value: Expression,
+    pub hir_id: HirId,
+}
+
+// The calls to a newtype are lowered to their argument
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Newtype {
+    pub name: Identifier,
+    pub ty: Type, // The `Type::Newtype`
+    pub hir_id: HirId,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -258,7 +299,13 @@ pub struct FunctionDecl {
 
 impl FunctionDecl {
     pub fn mangle(&mut self, prefixes: Vec<String>) {
+        // The C `main` is generated by the codegen and calls this one
         if self.name.name == "main" {
+            self.mangled_name = Some(Identifier {
+                name: ENTRY_POINT.to_string(),
+                hir_id: self.name.hir_id.clone(),
+            });
+
             return;
         }
 
@@ -512,6 +559,11 @@ impl Expression {
             kind: Box::new(ExpressionKind::Return(ret)),
         }
     }
+    pub fn new_ascription(ascription: Ascription) -> Self {
+        Self {
+            kind: Box::new(ExpressionKind::Ascription(ascription)),
+        }
+    }
 
     pub fn get_terminal_hir_id(&self) -> HirId {
         match &*self.kind {
@@ -523,6 +575,7 @@ impl Expression {
             ExpressionKind::Dot(d) => d.get_hir_id(),
             ExpressionKind::NativeOperation(op, _left, _right) => op.get_hir_id(),
             ExpressionKind::Return(expr) => expr.get_hir_id(),
+            ExpressionKind::Ascription(a) => a.get_hir_id(),
         }
     }
 
@@ -536,6 +589,7 @@ impl Expression {
             ExpressionKind::Dot(d) => d.get_op_terminal_hir_id(),
             ExpressionKind::NativeOperation(op, _left, _right) => op.get_hir_id(),
             ExpressionKind::Return(expr) => expr.get_hir_id(),
+            ExpressionKind::Ascription(a) => a.get_hir_id(),
         }
     }
 
@@ -585,6 +639,15 @@ pub enum ExpressionKind {
     Dot(Dot),
     NativeOperation(NativeOperator, Identifier, Identifier),
     Return(Expression),
+    Ascription(Ascription),
+}
+
+// The span of its id is the one of the annotation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ascription {
+    pub hir_id: HirId,
+    pub expr: Expression,
+    pub ty: Type,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
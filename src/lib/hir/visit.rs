@@ -32,6 +32,8 @@ generate_visitor_trait!(
     AssignLeftSide
     Prototype
     FunctionDecl
+    Const
+    Newtype
     StructDecl
     ArgumentDecl
     IdentifierPath
@@ -49,6 +51,7 @@ generate_visitor_trait!(
     StructCtor
     Indice
     Dot
+    Ascription
     Literal
     Array
     NativeOperator
@@ -83,9 +86,21 @@ pub fn walk_top_level<'a, V: Visitor<'a>>(visitor: &mut V, top_level: &'a TopLev
         TopLevelKind::Extern(p) => visitor.visit_prototype(p),
         TopLevelKind::Signature(p) => visitor.visit_prototype(p),
         TopLevelKind::Function(f) => visitor.visit_function_decl(f),
+        TopLevelKind::Const(c) => visitor.visit_const(c),
+        TopLevelKind::Newtype(n) => visitor.visit_newtype(n),
     };
 }
 
+pub fn walk_const<'a, V: Visitor<'a>>(visitor: &mut V, c: &'a Const) {
+    visitor.visit_identifier(&c.name);
+
+    visitor.visit_expression(&c.value);
+}
+
+pub fn walk_newtype<'a, V: Visitor<'a>>(visitor: &mut V, n: &'a Newtype) {
+    visitor.visit_identifier(&n.name);
+}
+
 pub fn walk_struct_decl<'a, V: Visitor<'a>>(visitor: &mut V, s: &'a StructDecl) {
     visitor.visit_identifier(&s.name);
 
@@ -195,9 +210,14 @@ pub fn walk_expression<'a, V: Visitor<'a>>(visitor: &mut V, expr: &'a Expression
             visitor.visit_identifier(right);
         }
         ExpressionKind::Return(expr) => visitor.visit_expression(expr),
+        ExpressionKind::Ascription(a) => visitor.visit_ascription(a),
     }
 }
 
+pub fn walk_ascription<'a, V: Visitor<'a>>(visitor: &mut V, ascription: &'a Ascription) {
+    visitor.visit_expression(&ascription.expr);
+}
+
 pub fn walk_struct_ctor<'a, V: Visitor<'a>>(visitor: &mut V, s: &'a StructCtor) {
     visitor.visit_identifier(&s.name);
 
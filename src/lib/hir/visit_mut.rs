@@ -30,6 +30,8 @@ generate_visitor_mut_trait!(
     Impl
     Prototype
     FunctionDecl
+    Const
+    Newtype
     StructDecl
     Assign
     AssignLeftSide
@@ -49,6 +51,7 @@ generate_visitor_mut_trait!(
     StructCtor
     Indice
     Dot
+    Ascription
     Literal
     Array
     NativeOperator
@@ -78,6 +81,10 @@ pub fn walk_root<'a, V: VisitorMut<'a>>(visitor: &mut V, root: &'a mut Root) {
     walk_map!(visitor, visit_fn_body, &mut root.bodies);
 }
 
+pub fn walk_newtype<'a, V: VisitorMut<'a>>(visitor: &mut V, n: &'a mut Newtype) {
+    visitor.visit_identifier(&mut n.name);
+}
+
 pub fn walk_struct_decl<'a, V: VisitorMut<'a>>(visitor: &mut V, s: &'a mut StructDecl) {
     visitor.visit_identifier(&mut s.name);
 
@@ -95,9 +102,17 @@ pub fn walk_top_level<'a, V: VisitorMut<'a>>(visitor: &mut V, top_level: &'a mut
         TopLevelKind::Extern(p) => visitor.visit_prototype(p),
         TopLevelKind::Signature(p) => visitor.visit_prototype(p),
         TopLevelKind::Function(f) => visitor.visit_function_decl(f),
+        TopLevelKind::Const(c) => visitor.visit_const(c),
+        TopLevelKind::Newtype(n) => visitor.visit_newtype(n),
     };
 }
 
+pub fn walk_const<'a, V: VisitorMut<'a>>(visitor: &mut V, c: &'a mut Const) {
+    visitor.visit_identifier(&mut c.name);
+
+    visitor.visit_expression(&mut c.value);
+}
+
 #[allow(dead_code)]
 pub fn walk_trait<'a, V: VisitorMut<'a>>(visitor: &mut V, t: &'a mut Trait) {
     visitor.visit_type(&mut t.name);
@@ -211,9 +226,14 @@ pub fn walk_expression<'a, V: VisitorMut<'a>>(visitor: &mut V, expr: &'a mut Exp
             visitor.visit_native_operator(op);
         }
         ExpressionKind::Return(expr) => visitor.visit_expression(expr),
+        ExpressionKind::Ascription(a) => visitor.visit_ascription(a),
     }
 }
 
+pub fn walk_ascription<'a, V: VisitorMut<'a>>(visitor: &mut V, ascription: &'a mut Ascription) {
+    visitor.visit_expression(&mut ascription.expr);
+}
+
 pub fn walk_function_call<'a, V: VisitorMut<'a>>(visitor: &mut V, fc: &'a mut FunctionCall) {
     visitor.visit_expression(&mut fc.op);
 
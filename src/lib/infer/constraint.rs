@@ -1,12 +1,13 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::{
     diagnostics::{Diagnostic, Diagnostics},
     hir::visit::*,
     hir::*,
     infer::Envs,
+    parser::span::Span,
     resolver::ResolutionMap,
-    ty::{FuncType, PrimitiveType, Type},
+    ty::{FuncType, PrimitiveType, StructType, Type},
 };
 
 #[derive(Debug)]
@@ -14,6 +15,8 @@ struct ConstraintContext<'a> {
     hir: &'a Root,
     tmp_resolutions: BTreeMap<HirId, ResolutionMap<HirId>>,
     envs: Envs,
+    // The constructors are visited once per instance of their function
+    checked_struct_ctors: BTreeSet<HirId>,
 }
 
 impl<'a> ConstraintContext<'a> {
@@ -22,6 +25,7 @@ impl<'a> ConstraintContext<'a> {
             envs,
             hir,
             tmp_resolutions: BTreeMap::default(),
+            checked_struct_ctors: BTreeSet::default(),
         }
     }
 
@@ -33,12 +37,47 @@ impl<'a> ConstraintContext<'a> {
     }
 
     pub fn constraint(&mut self, root: &'a Root) {
-        let entry_point = root.get_function_by_name("main").unwrap();
+        let entry_point = match root.get_function_by_name("main") {
+            Some(entry_point) => entry_point,
+            None => {
+                self.envs.diagnostics.push_error(Diagnostic::new_no_main());
+
+                return;
+            }
+        };
+
+        // `main` either takes nothing or the command line arguments, counted by `argc`
+        let args_type: Type = PrimitiveType::Array(
+            Box::new(PrimitiveType::String.into()),
+            PrimitiveType::RUNTIME_SIZE,
+        )
+        .into();
+
+        let entry_point_type = match entry_point.arguments.len() {
+            0 => FuncType::default().with_ret(Type::int64()),
+            1 => FuncType::new(vec![args_type], Type::int64()),
+            nb => {
+                self.envs
+                    .diagnostics
+                    .push_error(Diagnostic::new_signature_mismatch(
+                        self.hir
+                            .get_hir_spans()
+                            .get(&entry_point.hir_id)
+                            .unwrap()
+                            .clone(),
+                        "main".to_string(),
+                        FuncType::from_args_nb(nb),
+                        FuncType::new(vec![args_type], Type::int64()),
+                    ));
 
-        if !self.envs.set_current_fn((
-            entry_point.hir_id.clone(),
-            FuncType::default().with_ret(Type::int64()),
-        )) {
+                return;
+            }
+        };
+
+        if !self
+            .envs
+            .set_current_fn((entry_point.hir_id.clone(), entry_point_type))
+        {
             return;
         }
 
@@ -138,10 +177,88 @@ impl<'a> ConstraintContext<'a> {
 
                     Some(())
                 }
-                _ => unimplemented!("Cannot call {:#?}", reso),
+                HirNode::Newtype(n) => {
+                    self.setup_newtype_call(fc, &n);
+
+                    Some(())
+                }
+                _ => {
+                    self.push_not_a_function(call_hir_id);
+
+                    None
+                }
             });
     }
 
+    // Reports the unknown fields, the ones given twice and the declared ones that are not given
+    fn check_struct_ctor_fields(&mut self, s: &StructCtor, struct_t: &StructType) {
+        let spans = self.hir.get_hir_spans();
+
+        let mut given: BTreeMap<&String, &Identifier> = BTreeMap::new();
+
+        // An unknown field is more likely a misspelling of a missing one than of a given one
+        let missing = struct_t
+            .defs
+            .keys()
+            .filter(|name| !s.defs.keys().any(|k| &k.name == *name))
+            .collect::<Vec<_>>();
+
+        // The keys are ordered by HirId, so in source order
+        for k in s.defs.keys() {
+            if !struct_t.defs.contains_key(&k.name) {
+                self.envs.diagnostics.push_error(
+                    Diagnostic::new_unknown_field(
+                        spans.get(&k.hir_id).unwrap().clone(),
+                        k.name.clone(),
+                        s.name.name.clone(),
+                    )
+                    .with_best_match(&k.name, missing.iter().copied()),
+                );
+
+                continue;
+            }
+
+            match given.get(&k.name) {
+                Some(first) => self
+                    .envs
+                    .diagnostics
+                    .push_error(Diagnostic::new_duplicated_field(
+                        spans.get(&k.hir_id).unwrap().clone(),
+                        k.name.clone(),
+                        spans.get(&first.hir_id).unwrap().clone(),
+                    )),
+                None => {
+                    given.insert(&k.name, k);
+                }
+            }
+        }
+
+        let missing = struct_t
+            .fields_order
+            .iter()
+            .filter(|name| !given.contains_key(name))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if !missing.is_empty() {
+            self.envs
+                .diagnostics
+                .push_error(Diagnostic::new_missing_fields(
+                    spans.get(&s.name.hir_id).unwrap().clone(),
+                    s.name.name.clone(),
+                    missing,
+                ));
+        }
+    }
+
+    fn push_not_a_function(&mut self, hir_id: &HirId) {
+        let span = self.hir.get_hir_spans().get(hir_id).unwrap().clone();
+
+        self.envs
+            .diagnostics
+            .push_error(Diagnostic::new_not_a_function(span));
+    }
+
     pub fn setup_trait_call(&mut self, fc: &FunctionCall, f: &FunctionDecl) {
         self.add_tmp_resolution_to_current_fn(&fc.op.get_hir_id(), &f.hir_id);
 
@@ -178,6 +295,38 @@ impl<'a> ConstraintContext<'a> {
             .set_type(&fc.op.get_hir_id(), &Type::Func(p.signature.clone()));
     }
 
+    // A newtype is built from a value of the type that it wraps
+    pub fn setup_newtype_call(&mut self, fc: &FunctionCall, n: &Newtype) {
+        let inner = n.ty.try_as_newtype_inner().unwrap().clone();
+
+        let sig = FuncType::new(vec![inner.clone()], n.ty.clone());
+
+        let call_t = fc.to_func_type(self.envs.get_current_env().unwrap());
+
+        let is_valid = match call_t.arguments.as_slice() {
+            [arg_t] => arg_t.is_forall() || *arg_t == inner,
+            _ => false,
+        };
+
+        if !is_valid {
+            self.envs
+                .diagnostics
+                .push_error(Diagnostic::new_type_conflict(
+                    self.envs.spans.get(&fc.op.get_hir_id()).unwrap().clone(),
+                    sig.clone().into(),
+                    call_t.clone().into(),
+                    sig.into(),
+                    call_t.into(),
+                ));
+
+            return;
+        }
+
+        self.envs.set_type(&fc.args[0].get_hir_id(), &inner);
+        self.envs.set_type(&fc.get_hir_id(), &n.ty);
+        self.envs.set_type(&fc.op.get_hir_id(), &sig.into());
+    }
+
     // FIXME: This is ugly as well
     pub fn setup_function_call(&mut self, fc: &FunctionCall, f: &FunctionDecl) {
         if f.signature.arguments.len() != fc.args.len() {
@@ -343,6 +492,26 @@ impl<'a> ConstraintContext<'a> {
         });
     }
 
+    // The fields of a struct are suggested in place of an unknown property
+    fn push_not_a_property(&mut self, t: &Type, d: &Dot, op_span: Span) {
+        let diag = Diagnostic::new_is_not_a_property_of(
+            self.hir
+                .get_hir_spans()
+                .get(&d.value.get_hir_id())
+                .unwrap()
+                .clone(),
+            op_span,
+            t.clone(),
+        );
+
+        let diag = match t {
+            Type::Struct(struct_t) => diag.with_best_match(&d.value.name, struct_t.defs.keys()),
+            _ => diag,
+        };
+
+        self.envs.diagnostics.push_error(diag);
+    }
+
     pub fn resolve_dot_notation(&mut self, t: &Type, d: &Dot) -> Option<()> {
         self.envs.set_type(&d.op.get_hir_id(), t);
 
@@ -365,18 +534,7 @@ impl<'a> ConstraintContext<'a> {
                 .unwrap()
                 .clone();
 
-            self.envs
-                .diagnostics
-                .push_error(Diagnostic::new_is_not_a_property_of(
-                    self.hir
-                        .get_hir_spans()
-                        .get(&d.value.get_hir_id())
-                        .unwrap()
-                        .clone()
-                        .into(),
-                    op_span,
-                    t.clone(),
-                ));
+            self.push_not_a_property(t, d, op_span);
 
             return None;
         };
@@ -405,18 +563,7 @@ impl<'a> ConstraintContext<'a> {
 
             let op_span = self.envs.spans.get(&d.op.get_hir_id()).unwrap().clone();
 
-            self.envs
-                .diagnostics
-                .push_error(Diagnostic::new_is_not_a_property_of(
-                    self.hir
-                        .get_hir_spans()
-                        .get(&d.value.get_hir_id())
-                        .unwrap()
-                        .clone()
-                        .into(),
-                    op_span,
-                    t.clone(),
-                ));
+            self.push_not_a_property(t, d, op_span);
 
             return None;
         }
@@ -486,6 +633,70 @@ impl<'a, 'ar> Visitor<'a> for ConstraintContext<'ar> {
         self.add_tmp_resolution_to_current_fn(&f.name.hir_id, &f.hir_id);
     }
 
+    // A constant is typed once, in an env of its own, the first time it is used. Its type
+    // is the one of its value, so that the arrays keep their size.
+    fn visit_const(&mut self, c: &'a Const) {
+        if self.envs.get_fn_types(&c.hir_id).is_none() {
+            let old_f = self.envs.get_current_fn();
+
+            self.envs
+                .set_current_fn((c.hir_id.clone(), FuncType::new(vec![], c.ty.clone())));
+
+            self.visit_expression(&c.value);
+
+            let t = match self.envs.get_type(&c.value.get_hir_id()).cloned() {
+                Some(t) if t.is_solved() && (t.is_func() != c.ty.is_func() || t != c.ty) => {
+                    self.envs
+                        .diagnostics
+                        .push_error(Diagnostic::new_type_conflict(
+                            self.hir
+                                .get_hir_spans()
+                                .get(&c.value.get_hir_id())
+                                .unwrap()
+                                .clone(),
+                            c.ty.clone(),
+                            t.clone(),
+                            c.ty.clone(),
+                            t,
+                        ));
+
+                    c.ty.clone()
+                }
+                Some(t) if t.is_solved() => t,
+                _ => c.ty.clone(),
+            };
+
+            if !t.is_solved() {
+                self.envs
+                    .diagnostics
+                    .push_error(Diagnostic::new_unresolved_type(
+                        self.hir
+                            .get_hir_spans()
+                            .get(&c.name.hir_id)
+                            .unwrap()
+                            .clone(),
+                        t.clone(),
+                    ));
+            }
+
+            self.envs.set_type(&c.hir_id, &t);
+            self.envs.set_type(&c.name.hir_id, &t);
+
+            self.envs.set_current_fn(old_f);
+        }
+
+        let t = self
+            .envs
+            .get_fn_types(&c.hir_id)
+            .and_then(|envs| envs.values().next())
+            .and_then(|env| env.get(&c.hir_id))
+            .cloned();
+
+        if let Some(t) = t {
+            self.envs.set_type(&c.hir_id, &t);
+        }
+    }
+
     fn visit_prototype(&mut self, p: &Prototype) {
         if p.signature.is_solved() {
             self.envs.set_type(&p.hir_id, &p.signature.clone().into());
@@ -522,8 +733,16 @@ impl<'a, 'ar> Visitor<'a> for ConstraintContext<'ar> {
 
         walk_map!(self, visit_expression, &s.defs);
 
+        if self.checked_struct_ctors.insert(s.get_hir_id()) {
+            self.check_struct_ctor_fields(s, &struct_t);
+        }
+
         s.defs.iter().for_each(|(k, expr)| {
-            let declared_type = struct_t.defs.get(&k.name).unwrap();
+            let declared_type = match struct_t.defs.get(&k.name) {
+                Some(declared_type) => declared_type,
+                // Reported by check_struct_ctor_fields
+                None => return,
+            };
 
             declared_type.is_func().then(|| {
                 self.envs.get_type(&expr.get_hir_id()).cloned().or_else(|| {
@@ -551,6 +770,17 @@ impl<'a, 'ar> Visitor<'a> for ConstraintContext<'ar> {
         self.visit_expression(&assign.value);
         self.visit_assign_left_side(&assign.name);
 
+        if let AssignLeftSide::Dot(d) = &assign.name {
+            if let Some(Type::Newtype(name, _)) = self.envs.get_type(&d.op.get_hir_id()).cloned() {
+                self.envs
+                    .diagnostics
+                    .push_error(Diagnostic::new_assign_to_newtype_value(
+                        self.hir.get_hir_spans().get(&d.hir_id).unwrap().clone(),
+                        name,
+                    ));
+            }
+        }
+
         // FIXME: This is problematic, the value's type should not dictate the type of the
         //        operand's index. This makes the string indexing think a character is an Int64. Spooky
         self.envs
@@ -583,6 +813,7 @@ impl<'a, 'ar> Visitor<'a> for ConstraintContext<'ar> {
         match &*expr.kind {
             ExpressionKind::Lit(lit) => self.visit_literal(lit),
             ExpressionKind::Return(expr) => self.visit_expression(expr),
+            ExpressionKind::Ascription(a) => self.visit_ascription(a),
             ExpressionKind::Identifier(id) => self.visit_identifier_path(id),
             ExpressionKind::StructCtor(s) => self.visit_struct_ctor(s),
             ExpressionKind::NativeOperation(op, left, right) => {
@@ -622,11 +853,22 @@ impl<'a, 'ar> Visitor<'a> for ConstraintContext<'ar> {
                 self.visit_native_operator(op);
             }
             ExpressionKind::FunctionCall(fc) => {
+                let nb_diagnostics = self.envs.diagnostics.list.len();
+
                 self.visit_expression(&fc.op);
 
+                let op_failed = self.envs.diagnostics.list.len() > nb_diagnostics;
+
                 walk_list!(self, visit_expression, &fc.args);
 
-                self.setup_call(fc, &fc.op.get_hir_id());
+                // The type of a parameter is not known yet, it will be checked once instanciated
+                match self.envs.get_type(&fc.op.get_hir_id()) {
+                    Some(Type::Func(_)) | Some(Type::ForAll(_)) | None => {
+                        self.setup_call(fc, &fc.op.get_hir_id())
+                    }
+                    Some(_) if op_failed => self.setup_call(fc, &fc.op.get_hir_id()),
+                    Some(_) => self.push_not_a_function(&fc.op.get_terminal_hir_id()),
+                }
             }
             ExpressionKind::Indice(i) => {
                 self.visit_expression(&i.op);
@@ -737,7 +979,13 @@ impl<'a, 'ar> Visitor<'a> for ConstraintContext<'ar> {
                 self.visit_expression(&d.op);
                 self.visit_identifier(&d.value);
 
-                match &self.envs.get_type(&d.op.get_hir_id()).unwrap().clone() {
+                // The operand could not be typed, its error is already reported
+                let op_t = match self.envs.get_type(&d.op.get_hir_id()) {
+                    Some(op_t) => op_t.clone(),
+                    None => return,
+                };
+
+                match &op_t {
                     t @ Type::Struct(struct_t) => {
                         if let Some(field) = struct_t.defs.get(&d.value.name) {
                             self.envs.set_type(&d.op.get_hir_id(), t);
@@ -753,6 +1001,10 @@ impl<'a, 'ar> Visitor<'a> for ConstraintContext<'ar> {
                             self.resolve_dot_notation(t, d);
                         }
                     }
+                    // `.0` is the value that the newtype wraps
+                    Type::Newtype(_, inner) if d.value.name == "0" => {
+                        self.envs.set_type(&d.get_hir_id(), inner);
+                    }
                     other => {
                         self.resolve_dot_notation(other, d);
                     }
@@ -761,12 +1013,70 @@ impl<'a, 'ar> Visitor<'a> for ConstraintContext<'ar> {
         }
     }
 
+    fn visit_ascription(&mut self, a: &'a Ascription) {
+        match &*a.expr.kind {
+            // An empty array can only get its type from the annotation
+            ExpressionKind::Lit(Literal {
+                kind: LiteralKind::Array(arr),
+                ..
+            }) if arr.values.is_empty() && a.ty.is_array() && a.ty.is_solved() => {
+                self.envs.set_type(&a.expr.get_hir_id(), &a.ty)
+            }
+            _ => self.visit_expression(&a.expr),
+        }
+
+        let t = match self.envs.get_type(&a.expr.get_hir_id()).cloned() {
+            Some(t)
+                if t.is_solved()
+                    && a.ty.is_solved()
+                    && (t.is_func() != a.ty.is_func() || t != a.ty) =>
+            {
+                self.envs
+                    .diagnostics
+                    .push_error(Diagnostic::new_type_conflict(
+                        self.hir.get_hir_spans().get(&a.hir_id).unwrap().clone(),
+                        a.ty.clone(),
+                        t.clone(),
+                        a.ty.clone(),
+                        t,
+                    ));
+
+                a.ty.clone()
+            }
+            Some(t) if t.is_solved() || !a.ty.is_solved() => t,
+            // The annotation guides the inference of the expression
+            _ => {
+                self.envs.set_type(&a.expr.get_hir_id(), &a.ty);
+
+                a.ty.clone()
+            }
+        };
+
+        self.envs.set_type(&a.hir_id, &t);
+    }
+
     fn visit_literal(&mut self, lit: &Literal) {
         let t = match &lit.kind {
             LiteralKind::Number(_n) => Type::Primitive(PrimitiveType::Int64),
             LiteralKind::Float(_f) => Type::Primitive(PrimitiveType::Float64),
             LiteralKind::String(_s) => Type::Primitive(PrimitiveType::String),
             LiteralKind::Bool(_b) => Type::Primitive(PrimitiveType::Bool),
+            LiteralKind::Array(arr) if arr.values.is_empty() => {
+                let t = Type::Primitive(PrimitiveType::Array(
+                    Box::new(Type::ForAll("a".to_string())),
+                    0,
+                ));
+
+                // There is no element to infer the type from
+                self.envs
+                    .diagnostics
+                    .push_error(Diagnostic::new_unresolved_type(
+                        self.hir.get_hir_spans().get(&lit.hir_id).unwrap().clone(),
+                        t.clone(),
+                    ));
+
+                t
+            }
             LiteralKind::Array(arr) => {
                 self.visit_array(arr);
 
@@ -827,6 +1137,10 @@ impl<'a, 'ar> Visitor<'a> for ConstraintContext<'ar> {
     fn visit_identifier(&mut self, id: &Identifier) {
         // We set the type to resolution if any
         if let Some(reso) = self.resolve(&id.hir_id) {
+            if let Some(c) = self.hir.get_const(&reso) {
+                self.visit_const(c);
+            }
+
             if self.envs.get_type(&reso).is_some() {
                 self.envs.set_type_eq(&id.get_hir_id(), &reso);
             }
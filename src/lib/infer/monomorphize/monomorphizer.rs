@@ -89,6 +89,8 @@ impl<'a> Monomorphizer<'a> {
 
                                 None
                             }
+                            // Visited once the functions are generated
+                            HirNode::Const(_) => None,
                             _ => {
                                 panic!("Not a function decl");
                             }
@@ -139,6 +141,8 @@ impl<'a> Monomorphizer<'a> {
 
         new_root.top_levels = tops;
         new_root.top_levels.extend(prototypes);
+        new_root.top_levels.extend(self.monomorphize_consts());
+        new_root.top_levels.extend(self.newtypes());
 
         new_root.bodies = bodies;
 
@@ -148,11 +152,55 @@ impl<'a> Monomorphizer<'a> {
         new_root.spans = self.root.spans.clone();
         new_root.structs = self.structs.clone(); // TODO: monomorphize that
         new_root.node_types = self.root.node_types.clone();
+        new_root.type_envs = self.root.type_envs.clone(); // The signatures of the instances
         new_root.trait_solver = self.root.trait_solver.clone();
 
         new_root
     }
 
+    // The constants that are used, in their declaration order
+    fn monomorphize_consts(&mut self) -> Vec<TopLevel> {
+        let consts = self
+            .root
+            .top_levels
+            .iter()
+            .filter_map(|top| match &top.kind {
+                TopLevelKind::Const(c) => Some(c.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        consts
+            .into_iter()
+            .filter_map(|mut c| {
+                let sig = self
+                    .root
+                    .type_envs
+                    .get_fn_types(&c.hir_id)?
+                    .keys()
+                    .next()?
+                    .clone();
+
+                self.root.type_envs.set_current_fn((c.hir_id.clone(), sig));
+
+                self.visit_const(&mut c);
+
+                Some(TopLevel {
+                    kind: TopLevelKind::Const(c),
+                })
+            })
+            .collect()
+    }
+
+    fn newtypes(&self) -> Vec<TopLevel> {
+        self.root
+            .top_levels
+            .iter()
+            .filter(|top| matches!(top.kind, TopLevelKind::Newtype(_)))
+            .cloned()
+            .collect()
+    }
+
     pub fn duplicate_hir_id(&mut self, old_hir_id: &HirId) -> HirId {
         self.root
             .hir_map
@@ -207,6 +255,21 @@ impl<'a, 'b> VisitorMut<'a> for Monomorphizer<'b> {
         }
     }
 
+    fn visit_const(&mut self, c: &'a mut Const) {
+        let save_trans = self.trans_resolutions.clone();
+
+        if let Some(t) = self.root.type_envs.get_type(&c.hir_id) {
+            self.root.node_types.insert(c.hir_id.clone(), t.clone());
+            self.root
+                .node_types
+                .insert(c.name.hir_id.clone(), t.clone());
+        }
+
+        self.visit_expression(&mut c.value);
+
+        self.trans_resolutions = save_trans;
+    }
+
     fn visit_fn_body(&mut self, fn_body: &'a mut FnBody) {
         let save_trans = self.trans_resolutions.clone();
 
@@ -339,6 +402,11 @@ impl<'a, 'b> VisitorMut<'a> for Monomorphizer<'b> {
 
                 self.trans_resolutions.remove(&old_fc_op);
             }
+            // Like the constants, the newtypes keep their ids
+            HirNode::Newtype(n) => {
+                self.new_resolutions
+                    .insert(fc.op.get_hir_id(), n.hir_id.clone());
+            }
             _ => {}
         }
 
@@ -395,6 +463,18 @@ impl<'a, 'b> VisitorMut<'a> for Monomorphizer<'b> {
         self.visit_identifier(&mut dot.value);
     }
 
+    fn visit_ascription(&mut self, a: &'a mut Ascription) {
+        let old_hir_id = a.hir_id.clone();
+
+        a.hir_id = self.duplicate_hir_id(&old_hir_id);
+
+        if let Some(t) = self.root.type_envs.get_type(&old_hir_id) {
+            self.root.node_types.insert(a.hir_id.clone(), t.clone());
+        }
+
+        self.visit_expression(&mut a.expr);
+    }
+
     fn visit_struct_decl(&mut self, s: &'a mut StructDecl) {
         let old_hir_id = s.name.hir_id.clone();
 
@@ -482,7 +562,14 @@ impl<'a, 'b> VisitorMut<'a> for Monomorphizer<'b> {
             self.root.node_types.insert(id.hir_id.clone(), t.clone());
         }
 
-        self.trans_resolutions.insert(old_hir_id, id.hir_id.clone());
+        self.trans_resolutions
+            .insert(old_hir_id.clone(), id.hir_id.clone());
+
+        if let Some(reso) = self.resolve(&old_hir_id) {
+            if self.root.get_const(&reso).is_some() {
+                self.new_resolutions.insert(id.hir_id.clone(), reso);
+            }
+        }
     }
 
     fn visit_native_operator(&mut self, op: &'a mut NativeOperator) {
@@ -155,7 +155,6 @@ impl Envs {
             });
     }
 
-    #[allow(dead_code)]
     pub fn get_fn_types(&self, f: &HirId) -> Option<&HashMap<FuncType, Env>> {
         self.fns.get(f)
     }
@@ -0,0 +1,460 @@
+use std::io::Write;
+
+use crate::hir::{HirId, Prototype};
+
+use super::{Flow, Interpreter, Pointer, Value};
+
+// The libc functions that the interpreter emulates, with the ones of the runtime.
+// The others (like the file ones) cannot be called.
+pub static EXTERNS: &[&str] = &[
+    "exit",
+    "fflush",
+    "gcvt",
+    "malloc",
+    "memcpy",
+    "printf",
+    "puts",
+    "rock_argc",
+    "rock_getenv",
+    "sprintf",
+    "strcat",
+    "strcmp",
+    "strcpy",
+    "strlen",
+    "strncpy",
+    "strtol",
+    "write",
+];
+
+// The ones without effects, that the constants can call at compile time
+pub static PURE_EXTERNS: &[&str] = &[
+    "gcvt", "malloc", "memcpy", "sprintf", "strcat", "strcmp", "strcpy", "strlen", "strncpy",
+    "strtol",
+];
+
+impl<'a> Interpreter<'a> {
+    pub fn call_extern(
+        &mut self,
+        p: &Prototype,
+        hir_id: &HirId,
+        args: Vec<Value>,
+    ) -> Result<Value, Flow> {
+        let name = p.name.name.as_str();
+
+        if !EXTERNS.contains(&name) {
+            return self.runtime_error(
+                hir_id,
+                &format!("The extern `{}` cannot be run by the interpreter", name),
+            );
+        }
+
+        if self.steps_left.is_some() && !PURE_EXTERNS.contains(&name) {
+            return self.runtime_error(
+                hir_id,
+                &format!("The extern `{}` cannot be called at compile time", name),
+            );
+        }
+
+        let ints = args.iter().map(Value::as_int).collect::<Vec<_>>();
+        let ptrs = args.iter().map(Value::as_pointer).collect::<Vec<_>>();
+
+        // The argument `i`, that must be of the kind given by the signature
+        macro_rules! arg {
+            ($args:ident, $i:expr) => {
+                match $args.get($i).cloned().flatten() {
+                    Some(arg) => arg,
+                    None => {
+                        return self.runtime_error(
+                            hir_id,
+                            &format!("Bad argument {} given to `{}`", $i, name),
+                        )
+                    }
+                }
+            };
+        }
+
+        let res = match name {
+            "exit" => return Err(Flow::Exit(arg!(ints, 0))),
+            "fflush" => {
+                let _ = self.out.flush();
+
+                Value::Int(0)
+            }
+            "gcvt" => {
+                let value = match args.first().and_then(Value::as_float) {
+                    Some(value) => value,
+                    None => return self.runtime_error(hir_id, "Bad argument 0 given to `gcvt`"),
+                };
+
+                let s = format_g(value, arg!(ints, 1).max(0) as usize);
+
+                let buf = arg!(ptrs, 2).clone();
+
+                buf.write(0, s.as_bytes());
+                buf.set(s.len(), 0);
+
+                Value::String(buf)
+            }
+            "malloc" => Value::String(Pointer::alloc(arg!(ints, 0).max(0) as usize)),
+            "memcpy" => {
+                let (dest, src) = (arg!(ptrs, 0), arg!(ptrs, 1));
+
+                for i in 0..arg!(ints, 2).max(0) as usize {
+                    dest.set(i, src.get(i));
+                }
+
+                Value::String(dest.clone())
+            }
+            "printf" => {
+                let s = self.format(hir_id, arg!(ptrs, 0), &args[1..])?;
+
+                let _ = self.out.write_all(&s);
+
+                Value::Int(s.len() as i64)
+            }
+            "puts" => {
+                let mut s = arg!(ptrs, 0).bytes();
+
+                s.push(b'\n');
+
+                let _ = self.out.write_all(&s);
+
+                Value::Int(s.len() as i64)
+            }
+            "rock_argc" => Value::Int(self.args.len() as i64),
+            "rock_getenv" => {
+                let value = std::env::var(arg!(ptrs, 0).read()).unwrap_or_default();
+
+                Value::new_string(&value)
+            }
+            "sprintf" => {
+                let s = self.format(hir_id, arg!(ptrs, 1), &args[2..])?;
+
+                let dest = arg!(ptrs, 0);
+
+                dest.write(0, &s);
+                dest.set(s.len(), 0);
+
+                Value::Int(s.len() as i64)
+            }
+            "strcat" => {
+                let (dest, src) = (arg!(ptrs, 0), arg!(ptrs, 1));
+
+                let end = dest.bytes().len();
+                let src = src.bytes();
+
+                dest.write(end, &src);
+                dest.set(end + src.len(), 0);
+
+                Value::String(dest.clone())
+            }
+            "strcmp" => {
+                let (a, b) = (arg!(ptrs, 0).bytes(), arg!(ptrs, 1).bytes());
+
+                Value::Int(match a.cmp(&b) {
+                    std::cmp::Ordering::Less => -1,
+                    std::cmp::Ordering::Equal => 0,
+                    std::cmp::Ordering::Greater => 1,
+                })
+            }
+            "strcpy" => {
+                let (dest, src) = (arg!(ptrs, 0), arg!(ptrs, 1).bytes());
+
+                dest.write(0, &src);
+                dest.set(src.len(), 0);
+
+                Value::String(dest.clone())
+            }
+            "strlen" => Value::Int(arg!(ptrs, 0).bytes().len() as i64),
+            "strncpy" => {
+                let (dest, src) = (arg!(ptrs, 0), arg!(ptrs, 1));
+
+                // The rest is filled with zeros
+                let mut ended = false;
+
+                for i in 0..arg!(ints, 2).max(0) as usize {
+                    let byte = if ended { 0 } else { src.get(i) };
+
+                    ended = byte == 0;
+
+                    dest.set(i, byte);
+                }
+
+                Value::String(dest.clone())
+            }
+            // The end pointer is not supported, it must be null
+            "strtol" => Value::Int(strtol(&arg!(ptrs, 0).bytes(), arg!(ints, 2))),
+            "write" => {
+                let (fd, buf, len) = (arg!(ints, 0), arg!(ptrs, 1), arg!(ints, 2).max(0));
+
+                let s = (0..len as usize).map(|i| buf.get(i)).collect::<Vec<_>>();
+
+                match fd {
+                    1 => {
+                        let _ = self.out.write_all(&s);
+                    }
+                    2 => {
+                        let _ = std::io::stderr().write_all(&s);
+                    }
+                    _ => {
+                        return self.runtime_error(
+                            hir_id,
+                            "Only the standard output and error can be written",
+                        )
+                    }
+                }
+
+                Value::Int(len)
+            }
+            _ => unreachable!(),
+        };
+
+        Ok(res)
+    }
+
+    // The `printf` formats, without the `*` width and precision
+    pub fn format(
+        &mut self,
+        hir_id: &HirId,
+        fmt: &Pointer,
+        args: &[Value],
+    ) -> Result<Vec<u8>, Flow> {
+        match format(&fmt.bytes(), args) {
+            Ok(s) => Ok(s),
+            Err(msg) => self.runtime_error(hir_id, &msg),
+        }
+    }
+}
+
+pub fn format(fmt: &[u8], args: &[Value]) -> Result<Vec<u8>, String> {
+    let mut out = vec![];
+    let mut args = args.iter();
+
+    let mut i = 0;
+
+    while i < fmt.len() {
+        if fmt[i] != b'%' {
+            out.push(fmt[i]);
+            i += 1;
+
+            continue;
+        }
+
+        i += 1;
+
+        let mut left = false;
+        let mut zero = false;
+        let mut plus = false;
+
+        while let Some(flag) = fmt.get(i).filter(|c| b"-0+ #".contains(c)) {
+            match flag {
+                b'-' => left = true,
+                b'0' => zero = true,
+                b'+' => plus = true,
+                _ => (),
+            }
+
+            i += 1;
+        }
+
+        let mut width = 0;
+
+        while let Some(digit) = fmt.get(i).filter(|c| c.is_ascii_digit()) {
+            width = width * 10 + (digit - b'0') as usize;
+            i += 1;
+        }
+
+        let mut precision = None;
+
+        if fmt.get(i) == Some(&b'.') {
+            i += 1;
+
+            let mut p = 0;
+
+            while let Some(digit) = fmt.get(i).filter(|c| c.is_ascii_digit()) {
+                p = p * 10 + (digit - b'0') as usize;
+                i += 1;
+            }
+
+            precision = Some(p);
+        }
+
+        while fmt.get(i).is_some_and(|c| b"hlLqjzt".contains(c)) {
+            i += 1;
+        }
+
+        let conversion = match fmt.get(i) {
+            Some(c) => *c,
+            None => return Err("Unterminated format".to_string()),
+        };
+
+        i += 1;
+
+        if conversion == b'%' {
+            out.push(b'%');
+
+            continue;
+        }
+
+        let arg = args
+            .next()
+            .ok_or_else(|| format!("Missing argument for %{}", conversion as char))?;
+
+        let bad_arg = || format!("Bad argument for %{}: {}", conversion as char, arg);
+
+        let mut s =
+            match conversion {
+                b'd' | b'i' | b'u' => {
+                    let n = arg.as_int().ok_or_else(bad_arg)?;
+
+                    if plus && n >= 0 {
+                        format!("+{}", n).into_bytes()
+                    } else {
+                        n.to_string().into_bytes()
+                    }
+                }
+                b'x' => format!("{:x}", arg.as_int().ok_or_else(bad_arg)?).into_bytes(),
+                b'X' => format!("{:X}", arg.as_int().ok_or_else(bad_arg)?).into_bytes(),
+                b'c' => vec![arg.as_int().ok_or_else(bad_arg)? as u8],
+                b's' => {
+                    let mut s = arg.as_pointer().ok_or_else(bad_arg)?.bytes();
+
+                    if let Some(precision) = precision {
+                        s.truncate(precision);
+                    }
+
+                    s
+                }
+                b'f' | b'F' => format!(
+                    "{:.*}",
+                    precision.unwrap_or(6),
+                    arg.as_float().ok_or_else(bad_arg)?
+                )
+                .into_bytes(),
+                b'e' => format_e(arg.as_float().ok_or_else(bad_arg)?, precision.unwrap_or(6))
+                    .into_bytes(),
+                b'g' => format_g(arg.as_float().ok_or_else(bad_arg)?, precision.unwrap_or(6))
+                    .into_bytes(),
+                c => return Err(format!("Unsupported format %{}", c as char)),
+            };
+
+        if s.len() < width {
+            let pad = width - s.len();
+
+            if left {
+                s.extend(std::iter::repeat(b' ').take(pad));
+            } else if zero && b"dixXfFeg".contains(&conversion) {
+                let sign = s.first().is_some_and(|c| *c == b'-' || *c == b'+') as usize;
+
+                s.splice(sign..sign, std::iter::repeat(b'0').take(pad));
+            } else {
+                s.splice(0..0, std::iter::repeat(b' ').take(pad));
+            }
+        }
+
+        out.extend(s);
+    }
+
+    Ok(out)
+}
+
+// Like `%.*e`, with at least two digits in the exponent
+fn format_e(value: f64, precision: usize) -> String {
+    let s = format!("{:.*e}", precision, value);
+
+    match s.split_once('e') {
+        Some((mantissa, exp)) => {
+            let exp = exp.parse::<i32>().unwrap();
+
+            format!(
+                "{}e{}{:02}",
+                mantissa,
+                if exp < 0 { '-' } else { '+' },
+                exp.abs()
+            )
+        }
+        None => s,
+    }
+}
+
+// Like `%.*g`, that `gcvt` also uses: the fixed or the exponent form for that many
+// significant digits, without the trailing zeros
+pub fn format_g(value: f64, precision: usize) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+
+    let precision = precision.max(1);
+
+    // The exponent once rounded to the precision
+    let exp = format!("{:.*e}", precision - 1, value)
+        .split_once('e')
+        .map(|(_, exp)| exp.parse::<i32>().unwrap())
+        .unwrap_or(0);
+
+    let strip = |s: String| {
+        if s.contains('.') {
+            s.trim_end_matches('0').trim_end_matches('.').to_string()
+        } else {
+            s
+        }
+    };
+
+    if exp < -4 || exp >= precision as i32 {
+        let s = format_e(value, precision - 1);
+
+        let (mantissa, exp) = s.split_once('e').unwrap();
+
+        format!("{}e{}", strip(mantissa.to_string()), exp)
+    } else {
+        strip(format!(
+            "{:.*}",
+            (precision as i32 - 1 - exp).max(0) as usize,
+            value
+        ))
+    }
+}
+
+fn strtol(s: &[u8], base: i64) -> i64 {
+    let mut i = 0;
+
+    while s.get(i).is_some_and(|c| c.is_ascii_whitespace()) {
+        i += 1;
+    }
+
+    let negative = s.get(i) == Some(&b'-');
+
+    if matches!(s.get(i), Some(b'-') | Some(b'+')) {
+        i += 1;
+    }
+
+    let has_hex_prefix = s.get(i) == Some(&b'0') && matches!(s.get(i + 1), Some(b'x') | Some(b'X'));
+
+    let base = match base {
+        0 if has_hex_prefix => 16,
+        0 if s.get(i) == Some(&b'0') => 8,
+        0 => 10,
+        base => base,
+    };
+
+    if !(2..=36).contains(&base) {
+        return 0;
+    }
+
+    if base == 16 && has_hex_prefix {
+        i += 2;
+    }
+
+    let mut n: i64 = 0;
+
+    while let Some(digit) = s.get(i).and_then(|c| (*c as char).to_digit(base as u32)) {
+        n = n.saturating_mul(base).saturating_add(digit as i64);
+        i += 1;
+    }
+
+    if negative {
+        -n
+    } else {
+        n
+    }
+}
@@ -0,0 +1,586 @@
+mod externs;
+mod value;
+
+#[cfg(test)]
+mod tests;
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    io::Write,
+    rc::Rc,
+};
+
+use crate::{
+    diagnostics::{Diagnostic, Diagnostics},
+    hir::*,
+    parser::span::Span,
+    ty::Type,
+};
+
+pub use value::{Pointer, Value};
+
+// The compiled code has no limit but the one of its stack, ours would overflow way before
+const MAX_CALL_DEPTH: usize = 10_000;
+
+// The evaluation of the constants by the compiler must end
+const MAX_COMPILE_TIME_STEPS: usize = 1_000_000;
+
+// What stops the evaluation of an expression before its value
+pub enum Flow {
+    Return(Value),
+    Exit(i64), // The `exit` extern
+    Error,     // A runtime error, reported in the diagnostics
+}
+
+type Frame = HashMap<HirId, Value>;
+
+// A tree-walking interpreter of the typed and monomorphized HIR. It runs what the codegen
+// would lower, with the same resolutions, so the programs behave like the compiled ones
+// without LLVM nor a linker. The externs are emulated, only a known set of the libc ones
+// can be called.
+pub struct Interpreter<'a> {
+    pub hir: &'a Root,
+    pub functions: HashMap<HirId, &'a TopLevel>, // By declaration and name hir ids
+    pub frames: Vec<Frame>,
+    pub args: Vec<String>, // The `argv` of the program, with its name
+    pub out: &'a mut dyn Write,
+    pub consts: HashMap<HirId, Option<Value>>, // None while it is evaluated
+    pub steps_left: Option<usize>, // At compile time, where only the pure externs can be called
+    pub diagnostics: Diagnostics,
+}
+
+impl<'a> Interpreter<'a> {
+    pub fn new(hir: &'a Root, args: Vec<String>, out: &'a mut dyn Write) -> Self {
+        let mut functions = HashMap::new();
+
+        for top_level in &hir.top_levels {
+            match &top_level.kind {
+                TopLevelKind::Function(f) => {
+                    functions.insert(f.hir_id.clone(), top_level);
+                    functions.insert(f.name.hir_id.clone(), top_level);
+                }
+                TopLevelKind::Extern(p) => {
+                    functions.insert(p.hir_id.clone(), top_level);
+                    functions.insert(p.name.hir_id.clone(), top_level);
+                }
+                TopLevelKind::Signature(_) | TopLevelKind::Const(_) | TopLevelKind::Newtype(_) => {}
+            }
+        }
+
+        Self {
+            hir,
+            functions,
+            frames: vec![Frame::new()],
+            args,
+            out,
+            consts: HashMap::new(),
+            steps_left: None,
+            diagnostics: Diagnostics::default(),
+        }
+    }
+
+    // To evaluate the constants while compiling, without effects
+    pub fn new_compile_time(hir: &'a Root, out: &'a mut dyn Write) -> Self {
+        Self {
+            steps_left: Some(MAX_COMPILE_TIME_STEPS),
+            ..Self::new(hir, vec![], out)
+        }
+    }
+
+    // Reports an error on the span of `hir_id`, and stops the evaluation
+    pub fn runtime_error<T>(&mut self, hir_id: &HirId, msg: &str) -> Result<T, Flow> {
+        let span = self
+            .hir
+            .get_hir_spans()
+            .get(hir_id)
+            .cloned()
+            .unwrap_or_else(Span::new_placeholder);
+
+        self.diagnostics
+            .push_error(Diagnostic::new_runtime_error(span, msg));
+
+        Err(Flow::Error)
+    }
+
+    // Runs the entry point like the C `main` of the runtime does, and gives the exit
+    // code of the process. There is none when a pointer is returned, as the compiled
+    // program exits with the low byte of its address.
+    pub fn run_main(&mut self) -> Result<Option<i64>, ()> {
+        let main = self.hir.top_levels.iter().find_map(|top| match &top.kind {
+            TopLevelKind::Function(f) if f.get_name().name == ENTRY_POINT => Some(f),
+            _ => None,
+        });
+
+        let main = match main {
+            Some(main) => main,
+            None => {
+                self.diagnostics.push_error(Diagnostic::new_runtime_error(
+                    Span::new_placeholder(),
+                    "No entry point to run",
+                ));
+
+                return Err(());
+            }
+        };
+
+        let args = if main.arguments.is_empty() {
+            vec![]
+        } else {
+            vec![Value::new_array(
+                self.args.iter().map(|arg| Value::new_string(arg)).collect(),
+            )]
+        };
+
+        let res = self
+            .eval_consts()
+            .and_then(|_| self.call_function(main, args));
+
+        let _ = self.out.flush();
+
+        // Only the low byte of the status is seen by the parent process
+        match res {
+            Ok(value) => Ok(match value {
+                Value::Int(i) => Some(i as u8 as i64),
+                Value::Char(c) => Some(c as i64),
+                Value::Bool(b) => Some(b as i64),
+                Value::Float(_) => Some(0),
+                Value::String(_) | Value::Array(_) | Value::Struct(_) | Value::Func(_) => None,
+            }),
+            Err(Flow::Exit(code)) => Ok(Some(code as u8 as i64)),
+            Err(Flow::Return(_)) | Err(Flow::Error) => Err(()),
+        }
+    }
+
+    // The compiled program initializes all of them before its entry point, in their order
+    fn eval_consts(&mut self) -> Result<(), Flow> {
+        let hir = self.hir;
+
+        for top_level in &hir.top_levels {
+            if let TopLevelKind::Const(c) = &top_level.kind {
+                self.eval_const(c)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // A constant is evaluated once, in a frame of its own, and when another one needs it
+    pub fn eval_const(&mut self, c: &'a Const) -> Result<Value, Flow> {
+        match self.consts.get(&c.hir_id) {
+            Some(Some(value)) => return Ok(value.clone()),
+            Some(None) => {
+                return self.runtime_error(
+                    &c.name.hir_id,
+                    &format!("The constant `{}` depends on itself", c.name.name),
+                )
+            }
+            None => (),
+        }
+
+        self.consts.insert(c.hir_id.clone(), None);
+
+        self.frames.push(Frame::new());
+
+        let res = match self.eval_expression(&c.value) {
+            Err(Flow::Return(value)) => Ok(value),
+            res => res,
+        };
+
+        self.frames.pop();
+
+        match res {
+            Ok(value) => {
+                self.consts.insert(c.hir_id.clone(), Some(value.clone()));
+
+                Ok(value)
+            }
+            Err(flow) => {
+                self.consts.remove(&c.hir_id);
+
+                Err(flow)
+            }
+        }
+    }
+
+    pub fn call_function(&mut self, f: &'a FunctionDecl, args: Vec<Value>) -> Result<Value, Flow> {
+        if self.frames.len() > MAX_CALL_DEPTH {
+            return self.runtime_error(&f.name.hir_id, "Stack overflow");
+        }
+
+        let body = match self.hir.get_body(&f.body_id) {
+            Some(body) => body,
+            None => return self.runtime_error(&f.name.hir_id, "Function without a body"),
+        };
+
+        let mut frame = Frame::new();
+
+        for (arg, value) in f.arguments.iter().zip(args) {
+            frame.insert(arg.name.hir_id.clone(), value);
+        }
+
+        self.frames.push(frame);
+
+        let res = self.eval_body(&body.body);
+
+        self.frames.pop();
+
+        match res {
+            Err(Flow::Return(value)) => Ok(value),
+            res => res,
+        }
+    }
+
+    pub fn eval_body(&mut self, body: &'a Body) -> Result<Value, Flow> {
+        let mut last = Value::Int(0);
+
+        for stmt in &body.stmts {
+            last = self.eval_stmt(stmt)?;
+        }
+
+        Ok(last)
+    }
+
+    pub fn eval_stmt(&mut self, stmt: &'a Statement) -> Result<Value, Flow> {
+        if let Some(steps_left) = &mut self.steps_left {
+            if *steps_left == 0 {
+                return self.runtime_error(&stmt.get_terminal_hir_id(), "Too long to evaluate");
+            }
+
+            *steps_left -= 1;
+        }
+
+        match &*stmt.kind {
+            StatementKind::Expression(e) => self.eval_expression(e),
+            StatementKind::If(e) => self.eval_if_chain(e),
+            StatementKind::Assign(a) => self.eval_assign(a),
+            StatementKind::For(f) => self.eval_for(f),
+        }
+    }
+
+    pub fn eval_for(&mut self, for_loop: &'a For) -> Result<Value, Flow> {
+        let mut last = Value::Int(0);
+
+        match for_loop {
+            For::In(for_in) => {
+                let values = match self.eval_expression(&for_in.expr)? {
+                    Value::Array(values) => values.borrow().clone(),
+                    _ => return self.runtime_error(&for_in.value.hir_id, "Not an array"),
+                };
+
+                for value in values {
+                    self.set_local(&for_in.value, value);
+
+                    last = self.eval_body(&for_in.body)?;
+                }
+            }
+            For::While(w) => {
+                while self.eval_predicat(&w.predicat)? {
+                    last = self.eval_body(&w.body)?;
+                }
+            }
+        }
+
+        Ok(last)
+    }
+
+    pub fn eval_if_chain(&mut self, if_chain: &'a IfChain) -> Result<Value, Flow> {
+        for r#if in &if_chain.ifs {
+            if self.eval_predicat(&r#if.predicat)? {
+                return self.eval_body(&r#if.body);
+            }
+        }
+
+        match &if_chain.else_body {
+            Some(else_body) => self.eval_body(else_body),
+            None => Ok(Value::Int(0)),
+        }
+    }
+
+    fn eval_predicat(&mut self, predicat: &'a Expression) -> Result<bool, Flow> {
+        match self.eval_expression(predicat)?.as_bool() {
+            Some(b) => Ok(b),
+            None => self.runtime_error(&predicat.get_hir_id(), "Not a boolean"),
+        }
+    }
+
+    pub fn eval_assign(&mut self, assign: &'a Assign) -> Result<Value, Flow> {
+        let value = self.eval_expression(&assign.value)?;
+
+        match &assign.name {
+            AssignLeftSide::Identifier(id) => self.set_local(id, value.clone()),
+            AssignLeftSide::Indice(indice) => {
+                let op = self.eval_expression(&indice.op)?;
+                let idx = self.eval_index(&indice.value)?;
+
+                match op {
+                    Value::Array(values) => {
+                        let len = values.borrow().len();
+
+                        match values.borrow_mut().get_mut(idx) {
+                            Some(elem) => *elem = value.clone(),
+                            None => return self.out_of_bounds(&indice.hir_id, idx, len),
+                        }
+                    }
+                    Value::String(ptr) => match value {
+                        Value::Char(c) => ptr.set(idx, c),
+                        _ => return self.runtime_error(&indice.hir_id, "Not a char"),
+                    },
+                    _ => return self.runtime_error(&indice.hir_id, "Indice on non-array"),
+                }
+            }
+            AssignLeftSide::Dot(dot) => match self.eval_expression(&dot.op)? {
+                Value::Struct(fields) => {
+                    fields
+                        .borrow_mut()
+                        .insert(dot.value.name.clone(), value.clone());
+                }
+                _ => return self.runtime_error(&dot.hir_id, "Not a struct"),
+            },
+        }
+
+        Ok(value)
+    }
+
+    // The reassignments resolve to the first declaration
+    fn set_local(&mut self, id: &Identifier, value: Value) {
+        let frame = self.frames.last_mut().unwrap();
+
+        if let Some(reso) = self.hir.resolutions.get(&id.hir_id) {
+            frame.insert(reso, value.clone());
+        }
+
+        frame.insert(id.hir_id.clone(), value);
+    }
+
+    pub fn eval_expression(&mut self, expr: &'a Expression) -> Result<Value, Flow> {
+        match &*expr.kind {
+            ExpressionKind::Lit(l) => self.eval_literal(l),
+            ExpressionKind::Identifier(id) => self.eval_identifier(&id.last_segment()),
+            ExpressionKind::FunctionCall(fc) => self.eval_function_call(fc),
+            ExpressionKind::StructCtor(s) => self.eval_struct_ctor(s),
+            ExpressionKind::Indice(i) => self.eval_indice(i),
+            ExpressionKind::Dot(d) => self.eval_dot(d),
+            ExpressionKind::NativeOperation(op, left, right) => {
+                self.eval_native_operation(op, left, right)
+            }
+            ExpressionKind::Return(expr) => Err(Flow::Return(self.eval_expression(expr)?)),
+            ExpressionKind::Ascription(a) => self.eval_expression(&a.expr),
+        }
+    }
+
+    pub fn eval_literal(&mut self, lit: &'a Literal) -> Result<Value, Flow> {
+        Ok(match &lit.kind {
+            LiteralKind::Number(n) => Value::Int(*n),
+            LiteralKind::Float(f) => Value::Float(*f),
+            LiteralKind::Bool(b) => Value::Bool(*b),
+            LiteralKind::String(s) => Value::new_string(s),
+            LiteralKind::Char(c) => Value::Char(*c as u8),
+            LiteralKind::Array(arr) => {
+                let mut values = vec![];
+
+                for value in &arr.values {
+                    values.push(self.eval_expression(value)?);
+                }
+
+                Value::new_array(values)
+            }
+        })
+    }
+
+    pub fn eval_identifier(&mut self, id: &Identifier) -> Result<Value, Flow> {
+        let reso = self
+            .hir
+            .resolutions
+            .get(&id.hir_id)
+            .unwrap_or_else(|| id.hir_id.clone());
+
+        if let Some(value) = self.frames.last().unwrap().get(&reso) {
+            return Ok(value.clone());
+        }
+
+        if self.functions.contains_key(&reso) {
+            return Ok(Value::Func(reso));
+        }
+
+        if let Some(c) = self.hir.get_const(&reso) {
+            return self.eval_const(c);
+        }
+
+        self.runtime_error(&id.hir_id, &format!("Unknown identifier {}", id.name))
+    }
+
+    pub fn eval_function_call(&mut self, fc: &'a FunctionCall) -> Result<Value, Flow> {
+        let f_id = self.hir.resolutions.get(&fc.op.get_terminal_hir_id());
+
+        // A newtype holds the value that it wraps, like in the compiled program
+        if let Some(_newtype) = f_id.as_ref().and_then(|f_id| self.hir.get_newtype(f_id)) {
+            return self.eval_expression(&fc.args[0]);
+        }
+
+        let top_level = match f_id.and_then(|f_id| self.functions.get(&f_id)) {
+            Some(top_level) => *top_level,
+            None => match self.eval_expression(&fc.op)? {
+                Value::Func(f_id) => self.functions[&f_id],
+                _ => return self.runtime_error(&fc.hir_id, "Not a function"),
+            },
+        };
+
+        let mut args = vec![];
+
+        for arg in &fc.args {
+            args.push(self.eval_expression(arg)?);
+        }
+
+        match &top_level.kind {
+            TopLevelKind::Function(f) => self.call_function(f, args),
+            TopLevelKind::Extern(p) => self.call_extern(p, &fc.hir_id, args),
+            TopLevelKind::Signature(_) | TopLevelKind::Const(_) | TopLevelKind::Newtype(_) => {
+                self.runtime_error(&fc.hir_id, "Not a function")
+            }
+        }
+    }
+
+    pub fn eval_struct_ctor(&mut self, s: &'a StructCtor) -> Result<Value, Flow> {
+        let mut fields = BTreeMap::new();
+
+        for (name, def) in &s.defs {
+            fields.insert(name.name.clone(), self.eval_expression(def)?);
+        }
+
+        Ok(Value::Struct(Rc::new(RefCell::new(fields))))
+    }
+
+    fn eval_index(&mut self, expr: &'a Expression) -> Result<usize, Flow> {
+        match self.eval_expression(expr)?.as_int() {
+            Some(i) if i >= 0 => Ok(i as usize),
+            Some(i) => self.runtime_error(&expr.get_hir_id(), &format!("Negative index {}", i)),
+            None => self.runtime_error(&expr.get_hir_id(), "Not an index"),
+        }
+    }
+
+    fn out_of_bounds<T>(&mut self, hir_id: &HirId, idx: usize, len: usize) -> Result<T, Flow> {
+        self.runtime_error(
+            hir_id,
+            &format!("Index out of bounds: got {} but the len is {}", idx, len),
+        )
+    }
+
+    pub fn eval_indice(&mut self, indice: &'a Indice) -> Result<Value, Flow> {
+        let op = self.eval_expression(&indice.op)?;
+        let idx = self.eval_index(&indice.value)?;
+
+        match op {
+            Value::Array(values) => {
+                let value = values.borrow().get(idx).cloned();
+
+                match value {
+                    Some(value) => Ok(value),
+                    None => {
+                        let len = values.borrow().len();
+
+                        self.out_of_bounds(&indice.hir_id, idx, len)
+                    }
+                }
+            }
+            Value::String(ptr) => Ok(Value::Char(ptr.get(idx))),
+            _ => self.runtime_error(&indice.hir_id, "Indice on non-array"),
+        }
+    }
+
+    pub fn eval_dot(&mut self, dot: &'a Dot) -> Result<Value, Flow> {
+        let op_t = self.hir.node_types.get(&dot.op.get_hir_id());
+
+        // `.0` gives the value that a newtype wraps, which is the newtype itself
+        if op_t.is_some_and(Type::is_newtype) {
+            return self.eval_expression(&dot.op);
+        }
+
+        let field = match self.eval_expression(&dot.op)? {
+            Value::Struct(fields) => fields.borrow().get(&dot.value.name).cloned(),
+            _ => return self.runtime_error(&dot.hir_id, "Not a struct"),
+        };
+
+        match field {
+            Some(field) => Ok(field),
+            None => self.runtime_error(&dot.hir_id, &format!("No field {}", dot.value.name)),
+        }
+    }
+
+    pub fn eval_native_operation(
+        &mut self,
+        op: &NativeOperator,
+        left: &Identifier,
+        right: &Identifier,
+    ) -> Result<Value, Flow> {
+        use NativeOperatorKind::*;
+
+        let left_value = self.eval_identifier(left)?;
+
+        if let Len = op.kind {
+            return match left_value {
+                Value::Array(values) => Ok(Value::Int(values.borrow().len() as i64)),
+                _ => self.runtime_error(&op.hir_id, "Not an array"),
+            };
+        }
+
+        let right_value = self.eval_identifier(right)?;
+
+        let res = match op.kind {
+            IAdd | ISub | IMul | IDiv | IEq | Igt | Ige | Ilt | Ile => {
+                let (a, b) = match (left_value.as_int(), right_value.as_int()) {
+                    (Some(a), Some(b)) => (a, b),
+                    _ => return self.runtime_error(&op.hir_id, "Not an integer"),
+                };
+
+                let int = |i: i64| match left_value {
+                    Value::Char(_) => Value::Char(i as u8),
+                    _ => Value::Int(i),
+                };
+
+                match op.kind {
+                    IAdd => int(a.wrapping_add(b)),
+                    ISub => int(a.wrapping_sub(b)),
+                    IMul => int(a.wrapping_mul(b)),
+                    IDiv => match a.checked_div(b) {
+                        Some(i) => int(i),
+                        None if b == 0 => {
+                            return self.runtime_error(&op.hir_id, "Division by zero")
+                        }
+                        None => return self.runtime_error(&op.hir_id, "Division overflow"),
+                    },
+                    IEq => Value::Bool(a == b),
+                    Igt => Value::Bool(a > b),
+                    Ige => Value::Bool(a >= b),
+                    Ilt => Value::Bool(a < b),
+                    _ => Value::Bool(a <= b),
+                }
+            }
+            FAdd | FSub | FMul | FDiv | FEq | Fgt | Fge | Flt | Fle => {
+                let (a, b) = match (left_value.as_float(), right_value.as_float()) {
+                    (Some(a), Some(b)) => (a, b),
+                    _ => return self.runtime_error(&op.hir_id, "Not a float"),
+                };
+
+                match op.kind {
+                    FAdd => Value::Float(a + b),
+                    FSub => Value::Float(a - b),
+                    FMul => Value::Float(a * b),
+                    FDiv => Value::Float(a / b),
+                    FEq => Value::Bool(a == b),
+                    Fgt => Value::Bool(a > b),
+                    Fge => Value::Bool(a >= b),
+                    Flt => Value::Bool(a < b),
+                    _ => Value::Bool(a <= b),
+                }
+            }
+            BEq => match (left_value.as_int(), right_value.as_int()) {
+                (Some(a), Some(b)) => Value::Bool(a == b),
+                _ => return self.runtime_error(&op.hir_id, "Not a boolean"),
+            },
+            Len => unreachable!(),
+        };
+
+        Ok(res)
+    }
+}
@@ -0,0 +1,242 @@
+use std::path::PathBuf;
+
+use super::{
+    externs::{format, format_g},
+    Value,
+};
+use crate::{parser::SourceFile, Config};
+
+// The exit code of the program, if it does not return a pointer, and its output
+fn interpret(source: &str, std: bool) -> (Result<Option<i64>, ()>, String) {
+    let mut file = SourceFile::from_str("main.rk", source).unwrap();
+
+    if std {
+        file.prepend_header("mod std\nuse std::prelude::(*)\n");
+    }
+
+    file.mod_path = PathBuf::from("root");
+
+    let config = Config {
+        std,
+        quiet: true,
+        ..Config::default()
+    };
+
+    let mut out = vec![];
+
+    let res = crate::interpret_str(&file, &config, vec!["a.out".to_string()], &mut out);
+
+    (res.map_err(|_| ()), String::from_utf8(out).unwrap())
+}
+
+#[cfg(test)]
+mod programs {
+    use super::*;
+
+    #[test]
+    fn exit_code() {
+        // Only the low byte is seen by the parent process
+        assert_eq!(interpret("main: -> 300", false).0, Ok(Some(44)));
+        assert_eq!(interpret("main: -> 'a'", false).0, Ok(Some(97)));
+        assert_eq!(interpret("main: -> 2.5", false).0, Ok(Some(0)));
+
+        // The compiled program exits with a part of the address
+        assert_eq!(interpret("main: -> \"foo\"", false).0, Ok(None));
+    }
+
+    #[test]
+    fn prints() {
+        let (code, out) = interpret(
+            "main: ->
+  (42).print!
+  (10.0 / 4.0).print!
+  true.print!
+  ('a').print!
+  (\"foo\" + \"bar\").print!
+  0
+",
+            true,
+        );
+
+        assert_eq!(code, Ok(Some(0)));
+        assert_eq!(out, "42\n2.5\ntrue\na\nfoobar\n");
+    }
+
+    #[test]
+    fn shared_values() {
+        let (code, _) = interpret(
+            "struct Point
+  x: Int64
+
+move: p -> p.x = p.x + 1
+
+main: ->
+  let a = [1, 2]
+  let b = a
+  b[0] = 40
+  let p = Point x: a[0]
+  move p
+  move p
+  p.x
+",
+            true,
+        );
+
+        assert_eq!(code, Ok(Some(42)));
+    }
+
+    #[test]
+    fn loops_and_recursion() {
+        let (code, _) = interpret(
+            "fact: n ->
+  if n == 0
+  then 1
+  else n * fact (n - 1)
+
+main: ->
+  let i = 0
+  let sum = 0
+  while i < 4
+    sum = sum + fact i
+    i = i + 1
+  sum
+",
+            true,
+        );
+
+        assert_eq!(code, Ok(Some(10)));
+    }
+
+    #[test]
+    fn exit() {
+        let (code, out) = interpret(
+            "use std::externs::exit
+
+main: ->
+  (1).print!
+  exit 3
+  (2).print!
+  0
+",
+            true,
+        );
+
+        assert_eq!(code, Ok(Some(3)));
+        assert_eq!(out, "1\n");
+    }
+
+    #[test]
+    fn runtime_errors() {
+        assert_eq!(
+            interpret("main: ->\n  let a = 1\n  let b = 0\n  ~IDiv a b\n", false).0,
+            Err(())
+        );
+        assert_eq!(interpret("main: -> [1, 2][2]", false).0, Err(()));
+    }
+
+    #[test]
+    fn consts() {
+        // They are initialized before the entry point, the ones they use first
+        let (code, out) = interpret(
+            "const total: Int64 = first + 2
+const first: Int64 = show 40
+
+show: n ->
+  n.print!
+  n
+
+main: ->
+  (1).print!
+  total
+",
+            true,
+        );
+
+        assert_eq!(code, Ok(Some(42)));
+        assert_eq!(out, "40\n1\n");
+
+        assert_eq!(
+            interpret("const a: Int64 = a\n\nmain: -> a\n", false).0,
+            Err(())
+        );
+    }
+
+    #[test]
+    fn newtypes() {
+        let (code, _) = interpret(
+            "newtype UserId = Int64
+
+impl UserId
+  @next: -> UserId (@0 + 1)
+
+main: -> (UserId 41).next!.0
+",
+            true,
+        );
+
+        assert_eq!(code, Ok(Some(42)));
+    }
+
+    #[test]
+    fn unknown_extern() {
+        let (code, _) = interpret(
+            "extern open: String => Int64 => Int64 => Int64\n\nmain: -> open \"foo\", 0, 0\n",
+            false,
+        );
+
+        assert_eq!(code, Err(()));
+    }
+}
+
+#[cfg(test)]
+mod printf {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        let out = format(
+            b"[%5d|%-3s|%c|%.2f|%05d|%e|%x|%%]",
+            &[
+                Value::Int(42),
+                Value::new_string("a"),
+                Value::Char(b'z'),
+                Value::Float(3.14159),
+                Value::Int(-42),
+                Value::Float(1234.5),
+                Value::Int(255),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "[   42|a  |z|3.14|-0042|1.234500e+03|ff|%]"
+        );
+    }
+
+    #[test]
+    fn missing_argument() {
+        assert!(format(b"%d %d", &[Value::Int(1)]).is_err());
+        assert!(format(b"%s", &[Value::Int(1)]).is_err());
+    }
+
+    #[test]
+    fn gcvt() {
+        let cases = [
+            (1.0, "1"),
+            (10.2, "10.2"),
+            (1e20, "1e+20"),
+            (0.0001, "0.0001"),
+            (0.00001, "1e-05"),
+            (123456789.5, "123456789.5"),
+            (12345678901.0, "1.23456789e+10"),
+            (-2.5, "-2.5"),
+            (0.0, "0"),
+            (100.0, "100"),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(format_g(value, 10), expected);
+        }
+    }
+}
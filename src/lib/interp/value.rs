@@ -0,0 +1,171 @@
+use std::{cell::RefCell, collections::BTreeMap, fmt, rc::Rc};
+
+use crate::hir::HirId;
+
+// The arrays, the structs and the strings are pointers in the compiled code, so they
+// are shared between the values that are copied from the same one
+#[derive(Clone, Debug)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Char(u8),
+    String(Pointer),
+    Array(Rc<RefCell<Vec<Value>>>),
+    Struct(Rc<RefCell<BTreeMap<String, Value>>>),
+    Func(HirId), // A top level function or extern
+}
+
+impl Value {
+    pub fn new_string(s: &str) -> Self {
+        Value::String(Pointer::from_bytes(s.as_bytes()))
+    }
+
+    pub fn new_array(values: Vec<Value>) -> Self {
+        Value::Array(Rc::new(RefCell::new(values)))
+    }
+
+    // The chars are signed bytes, like in C
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Value::Int(i) => Some(*i),
+            Value::Char(c) => Some(*c as i8 as i64),
+            Value::Bool(b) => Some(*b as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_pointer(&self) -> Option<&Pointer> {
+        match self {
+            Value::String(p) => Some(p),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(i) => write!(f, "{}", i),
+            Value::Float(n) => write!(f, "{}", n),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Char(c) => write!(f, "{:?}", *c as char),
+            Value::String(p) => write!(f, "{:?}", p.read()),
+            Value::Array(values) => {
+                let values = values.borrow();
+
+                write!(f, "[")?;
+
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{}", value)?;
+                }
+
+                write!(f, "]")
+            }
+            Value::Struct(fields) => {
+                let fields = fields.borrow();
+
+                write!(f, "{{")?;
+
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+
+                    write!(f, " {}: {}", name, value)?;
+                }
+
+                write!(f, " }}")
+            }
+            Value::Func(hir_id) => write!(f, "<function {:?}>", hir_id),
+        }
+    }
+}
+
+// A `char *`, to the start of its buffer. The buffers grow when they are written past
+// their end and read as zeros there, so the off-by-one allocations of the std (like
+// `malloc strlen s` before a `strcpy`) work as they happen to in C.
+#[derive(Clone, Debug)]
+pub struct Pointer {
+    buffer: Rc<RefCell<Vec<u8>>>,
+}
+
+impl Pointer {
+    pub fn alloc(size: usize) -> Self {
+        Self {
+            buffer: Rc::new(RefCell::new(vec![0; size])),
+        }
+    }
+
+    // With the terminating zero
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let ptr = Self::alloc(bytes.len() + 1);
+
+        ptr.write(0, bytes);
+
+        ptr
+    }
+
+    pub fn get(&self, i: usize) -> u8 {
+        self.buffer.borrow().get(i).copied().unwrap_or(0)
+    }
+
+    pub fn set(&self, i: usize, byte: u8) {
+        let mut buffer = self.buffer.borrow_mut();
+
+        if i >= buffer.len() {
+            buffer.resize(i + 1, 0);
+        }
+
+        buffer[i] = byte;
+    }
+
+    pub fn write(&self, i: usize, bytes: &[u8]) {
+        for (j, byte) in bytes.iter().enumerate() {
+            self.set(i + j, *byte);
+        }
+    }
+
+    // The bytes up to the terminating zero
+    pub fn bytes(&self) -> Vec<u8> {
+        let buffer = self.buffer.borrow();
+
+        buffer
+            .iter()
+            .take_while(|byte| **byte != 0)
+            .copied()
+            .collect()
+    }
+
+    // The whole buffer, past the terminating zero
+    pub fn buffer(&self) -> Vec<u8> {
+        self.buffer.borrow().clone()
+    }
+
+    // The copies of a pointer share its buffer
+    pub fn addr(&self) -> usize {
+        Rc::as_ptr(&self.buffer) as usize
+    }
+
+    pub fn read(&self) -> String {
+        String::from_utf8_lossy(&self.bytes()).to_string()
+    }
+}
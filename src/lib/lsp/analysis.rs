@@ -0,0 +1,671 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    ast::{self, tree::*, visit::*, NodeId},
+    ast_lowering,
+    diagnostics::Diagnostics,
+    hir, infer,
+    parser::{self, ParsingCtx, SourceFile, Span},
+    resolver,
+    ty::{FuncType, Type},
+    Config,
+};
+
+use super::{
+    line_index::LineIndex,
+    protocol::{completion_kind, path_to_uri, symbol_kind, *},
+};
+
+// The result of a compilation of a project, without the code generation.
+// Each stage is kept when it succeeds, so the resolution is available even when
+// the types cannot be inferred.
+#[derive(Debug)]
+pub struct Analysis {
+    pub entry: PathBuf,
+    pub files: HashMap<PathBuf, SourceFile>,
+    pub diagnostics: Diagnostics,
+    pub ast: Option<ast::Root>,
+    pub hir: Option<hir::Root>,
+}
+
+impl Analysis {
+    pub fn new(entry: &Path, config: &Config) -> Self {
+        let read = |path: &Path| {
+            config
+                .overlays
+                .get(path)
+                .cloned()
+                .or_else(|| fs::read_to_string(path).ok())
+        };
+
+        let mut analysis = Self {
+            entry: entry.to_path_buf(),
+            files: HashMap::new(),
+            diagnostics: Diagnostics::default(),
+            ast: None,
+            hir: None,
+        };
+
+        let original = read(entry).unwrap_or_default();
+
+        let mut source_file = match SourceFile::from_str(&entry.to_string_lossy(), &original) {
+            Ok(source_file) => source_file,
+            Err(diagnostic) => {
+                analysis.diagnostics.push_error(diagnostic);
+
+                return analysis;
+            }
+        };
+
+        if config.std {
+            source_file.prepend_header("mod std\nuse std::prelude::(*)\n");
+        }
+
+        source_file.mod_path = PathBuf::from("root");
+
+        let mut parsing_ctx = ParsingCtx::new(config);
+
+        parsing_ctx.add_file(&source_file);
+
+        if let Ok(mut ast) = parser::parse(&mut parsing_ctx) {
+            if resolver::resolve(&mut ast, &mut parsing_ctx).is_ok() {
+                let mut hir = ast_lowering::lower_crate(&ast);
+
+                analysis.hir = infer::infer(&mut hir, &mut parsing_ctx, config).ok();
+            }
+
+            analysis.ast = Some(ast);
+        }
+
+        analysis.files = parsing_ctx.files;
+        analysis.diagnostics = parsing_ctx.diagnostics;
+
+        analysis
+    }
+
+    // `None` for the std files
+    pub fn line_index(&self, path: &Path) -> Option<LineIndex> {
+        if parser::is_stdlib_file(path.to_str()?) {
+            return None;
+        }
+
+        Some(LineIndex::new(self.files.get(path)?))
+    }
+
+    // The files of the project, without the std
+    pub fn project_files(&self) -> impl Iterator<Item = &PathBuf> {
+        self.files
+            .keys()
+            .filter(|path| !parser::is_stdlib_file(path.to_str().unwrap_or_default()))
+    }
+
+    // `None` for the std files, that the editor cannot open, and for the std header
+    pub fn location(&self, span: &Span) -> Option<Location> {
+        let line_index = self.line_index(&span.file_path)?;
+
+        if line_index.in_header(span.start) {
+            return None;
+        }
+
+        Some(Location {
+            uri: path_to_uri(&span.file_path),
+            range: Range::new(
+                line_index.position(span.start),
+                line_index.position(span.end),
+            ),
+        })
+    }
+
+    pub fn span_text(&self, span: &Span) -> Option<&str> {
+        self.files
+            .get(&span.file_path)?
+            .content
+            .get(span.start..span.end)
+    }
+
+    fn span(&self, node_id: NodeId) -> Option<Span> {
+        let mut span = self.ast.as_ref()?.spans.get(&node_id)?.clone();
+
+        // Some nodes only have a position, like the functions and the capitalized
+        // identifiers, they cover the name that follows it
+        if span.start == span.end {
+            let rest = self.files.get(&span.file_path)?.content.get(span.start..)?;
+
+            span.end += rest.len() - rest.trim_start_matches(is_ident).len();
+        }
+
+        Some(span)
+    }
+
+    // The innermost named node at the offset, the cursor can be right after it
+    pub fn node_at(&self, path: &Path, offset: usize) -> Option<NodeId> {
+        let ast = self.ast.as_ref()?;
+
+        ast.spans
+            .keys()
+            .filter_map(|node_id| Some((*node_id, self.span(*node_id)?)))
+            .filter(|(_, span)| {
+                span.file_path == path
+                    && span.start < span.end
+                    && span.start <= offset
+                    && offset <= span.end
+            })
+            .filter(|(_, span)| {
+                self.span_text(span)
+                    .map(|text| text.starts_with(|c: char| c.is_alphabetic() || c == '_'))
+                    .unwrap_or(false)
+            })
+            .min_by_key(|(node_id, span)| {
+                (
+                    span.end - span.start,
+                    !self.is_resolved(*node_id),
+                    self.types(*node_id).is_empty(),
+                )
+            })
+            .map(|(node_id, _)| node_id)
+    }
+
+    fn is_resolved(&self, node_id: NodeId) -> bool {
+        self.ast
+            .as_ref()
+            .map(|ast| ast.resolutions.get(&node_id).is_some())
+            .unwrap_or(false)
+    }
+
+    // A node that is not a reference is its own definition
+    pub fn definition(&self, node_id: NodeId) -> NodeId {
+        self.ast
+            .as_ref()
+            .and_then(|ast| ast.resolutions.get_recur(&node_id))
+            .unwrap_or(node_id)
+    }
+
+    pub fn references(&self, def: NodeId, include_declaration: bool) -> Vec<Location> {
+        let ast = match &self.ast {
+            Some(ast) => ast,
+            None => return vec![],
+        };
+
+        let mut nodes = ast
+            .resolutions
+            .inner()
+            .keys()
+            .filter(|pointer| **pointer != def && ast.resolutions.get_recur(pointer) == Some(def))
+            .copied()
+            .collect::<BTreeSet<_>>();
+
+        if include_declaration {
+            nodes.insert(def);
+        }
+
+        let mut locations = nodes
+            .into_iter()
+            .filter_map(|node_id| self.location(&self.span(node_id)?))
+            .collect::<Vec<_>>();
+
+        locations.sort_by_key(|location| (location.uri.clone(), location.range.start.line));
+        locations.dedup();
+
+        locations
+    }
+
+    pub fn definition_location(&self, node_id: NodeId) -> Option<Location> {
+        self.location(&self.span(self.definition(node_id))?)
+    }
+
+    // The inferred types of a node, one per instance of its generic function
+    pub fn types(&self, node_id: NodeId) -> Vec<Type> {
+        let hir = match &self.hir {
+            Some(hir) => hir,
+            None => return vec![],
+        };
+
+        let mut types = vec![];
+
+        for hir_id in hir.hir_map.get_hir_ids(node_id) {
+            if let Some(t) = hir.node_types.get(&hir_id) {
+                if !types.contains(t) {
+                    types.push(t.clone());
+                }
+            }
+        }
+
+        // The generic ones are only shown when there is no instance
+        if types.iter().any(Type::is_solved) {
+            types.retain(Type::is_solved);
+        }
+
+        types
+    }
+
+    // The signatures of the instances of a function
+    pub fn instances(&self, node_id: NodeId) -> Vec<FuncType> {
+        let hir = match &self.hir {
+            Some(hir) => hir,
+            None => return vec![],
+        };
+
+        let mut instances = hir
+            .hir_map
+            .get_hir_ids(node_id)
+            .iter()
+            .filter_map(|hir_id| hir.type_envs.get_fn_types(hir_id))
+            .flat_map(|types| types.keys().cloned())
+            .filter(FuncType::is_solved)
+            .collect::<Vec<_>>();
+
+        // By their name, as they are hashed
+        instances.sort_by_key(|f| format!("{:?}", f));
+        instances.dedup();
+
+        instances
+    }
+
+    pub fn hover(&self, node_id: NodeId) -> Option<String> {
+        let span = self.span(node_id)?;
+
+        let name = self.span_text(&span)?;
+
+        let mut types = self.types(node_id);
+
+        if types.is_empty() {
+            types = self.types(self.definition(node_id));
+        }
+
+        if types.is_empty() {
+            return None;
+        }
+
+        Some(
+            types
+                .iter()
+                .map(|t| format!("{}: {}", name, format_type(t)))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    }
+
+    pub fn document_symbols(&self, path: &Path) -> Vec<DocumentSymbol> {
+        match &self.ast {
+            Some(ast) => self.mod_symbols(&ast.r#mod, path),
+            None => vec![],
+        }
+    }
+
+    fn mod_symbols(&self, m: &Mod, path: &Path) -> Vec<DocumentSymbol> {
+        let mut symbols = vec![];
+
+        for top_level in &m.top_levels {
+            // The items of a mod are in its own file
+            if let TopLevel::Mod(_, m) = top_level {
+                symbols.extend(self.mod_symbols(m, path));
+            }
+
+            if self.top_level_file(top_level) != Some(path) {
+                continue;
+            }
+
+            let symbol = match top_level {
+                TopLevel::Function(f) => self.symbol(&f.name, symbol_kind::FUNCTION, vec![]),
+                TopLevel::Clauses(c) => self.symbol(&c.name, symbol_kind::FUNCTION, vec![]),
+                TopLevel::Test(t) => self
+                    .symbol(&t.func.name, symbol_kind::FUNCTION, vec![])
+                    .map(|symbol| DocumentSymbol {
+                        name: format!("test \"{}\"", t.name),
+                        ..symbol
+                    }),
+                TopLevel::Extern(p) => self.symbol(&p.name, symbol_kind::FUNCTION, vec![]),
+                TopLevel::Const(c) => self.symbol(&c.name, symbol_kind::CONSTANT, vec![]),
+                // Like the other editors, the aliases are shown as type parameters
+                TopLevel::TypeAlias(t) => self.symbol(&t.name, symbol_kind::TYPE_PARAMETER, vec![]),
+                TopLevel::Newtype(n) => self.symbol(&n.name, symbol_kind::STRUCT, vec![]),
+                TopLevel::Struct(s) => {
+                    let fields = s
+                        .defs
+                        .iter()
+                        .filter_map(|p| self.symbol(&p.name, symbol_kind::FIELD, vec![]))
+                        .collect();
+
+                    self.symbol(&s.name, symbol_kind::STRUCT, fields)
+                }
+                TopLevel::Trait(t) => {
+                    let methods = t
+                        .defs
+                        .iter()
+                        .filter_map(|p| self.symbol(&p.name, symbol_kind::METHOD, vec![]))
+                        .collect::<Vec<_>>();
+
+                    self.group_symbol(t.name.get_name(), methods)
+                }
+                TopLevel::Impl(i) => {
+                    let methods = i
+                        .defs
+                        .iter()
+                        .filter_map(|f| self.symbol(&f.name, symbol_kind::METHOD, vec![]))
+                        .collect();
+
+                    let name = match i.types.first() {
+                        Some(t) => format!("impl {} {}", i.name.get_name(), t.get_name()),
+                        None => format!("impl {}", i.name.get_name()),
+                    };
+
+                    self.span(i.node_id)
+                        .and_then(|span| self.location(&span))
+                        .map(|location| DocumentSymbol {
+                            name,
+                            kind: symbol_kind::CLASS,
+                            range: location.range,
+                            selection_range: location.range,
+                            children: methods,
+                        })
+                }
+                TopLevel::Mod(name, _) => self.symbol(name, symbol_kind::MODULE, vec![]),
+                TopLevel::FnSignature(_) | TopLevel::Use(_) | TopLevel::Infix(_, _) => None,
+            };
+
+            symbols.extend(symbol);
+        }
+
+        symbols
+    }
+
+    fn symbol(
+        &self,
+        name: &Identifier,
+        kind: u32,
+        children: Vec<DocumentSymbol>,
+    ) -> Option<DocumentSymbol> {
+        let location = self.location(&self.span(name.node_id)?)?;
+
+        Some(DocumentSymbol {
+            name: name.name.clone(),
+            kind,
+            range: location.range,
+            selection_range: location.range,
+            children,
+        })
+    }
+
+    // The range of a trait covers the ones of its methods
+    fn group_symbol(&self, name: String, children: Vec<DocumentSymbol>) -> Option<DocumentSymbol> {
+        let first = children.first()?;
+        let last = children.last()?;
+
+        Some(DocumentSymbol {
+            name,
+            kind: symbol_kind::INTERFACE,
+            range: Range::new(first.range.start, last.range.end),
+            selection_range: first.selection_range,
+            children,
+        })
+    }
+
+    fn top_level_file(&self, top_level: &TopLevel) -> Option<&Path> {
+        let node_id = match top_level {
+            TopLevel::Function(f) => f.name.node_id,
+            TopLevel::Clauses(c) => c.name.node_id,
+            TopLevel::Test(t) => t.func.name.node_id,
+            TopLevel::Extern(p) => p.name.node_id,
+            TopLevel::Const(c) => c.name.node_id,
+            TopLevel::TypeAlias(t) => t.name.node_id,
+            TopLevel::Newtype(n) => n.name.node_id,
+            TopLevel::Struct(s) => s.name.node_id,
+            TopLevel::Trait(t) => t.node_id,
+            TopLevel::Impl(i) => i.node_id,
+            TopLevel::Mod(name, _) => name.node_id,
+            TopLevel::FnSignature(_) | TopLevel::Use(_) | TopLevel::Infix(_, _) => return None,
+        };
+
+        Some(&self.ast.as_ref()?.spans.get(&node_id)?.file_path)
+    }
+
+    // The fields of the struct named `receiver`, the closest one before the offset
+    pub fn field_completions(
+        &self,
+        path: &Path,
+        offset: usize,
+        receiver: &str,
+    ) -> Vec<CompletionItem> {
+        let ast = match &self.ast {
+            Some(ast) => ast,
+            None => return vec![],
+        };
+
+        let receiver_node = ast
+            .spans
+            .iter()
+            .filter(|(_, span)| {
+                span.file_path == path
+                    && span.start <= offset
+                    && self.span_text(span) == Some(receiver)
+            })
+            .max_by_key(|(node_id, span)| (span.start, self.types(**node_id).len()))
+            .map(|(node_id, _)| *node_id);
+
+        let node_id = match receiver_node {
+            Some(node_id) => node_id,
+            None => return vec![],
+        };
+
+        let mut types = self.types(node_id);
+
+        if types.is_empty() {
+            types = self.types(self.definition(node_id));
+        }
+
+        let struct_t = match types.iter().find_map(Type::try_as_struct_type) {
+            Some(struct_t) => struct_t,
+            None => return vec![],
+        };
+
+        struct_t
+            .ordered_defs()
+            .into_iter()
+            .map(|(name, t)| CompletionItem {
+                label: name,
+                kind: completion_kind::FIELD,
+                detail: Some(format_type(&t)),
+            })
+            .collect()
+    }
+
+    // The top level names of the module of the file, and the locals of the function at
+    // the offset that are declared before it
+    pub fn scope_completions(&self, path: &Path, offset: usize) -> Vec<CompletionItem> {
+        let ast = match &self.ast {
+            Some(ast) => ast,
+            None => return vec![],
+        };
+
+        let mod_path = match self.files.get(path) {
+            Some(file) => file.mod_path.to_string_lossy().to_string(),
+            None => return vec![],
+        };
+
+        let mut names: Vec<(String, NodeId)> = ast
+            .mod_scopes
+            .iter()
+            .find(|(scope_path, _)| {
+                scope_path
+                    .path
+                    .iter()
+                    .map(|ident| ident.name.clone())
+                    .collect::<Vec<_>>()
+                    .join("/")
+                    == mod_path
+            })
+            .map(|(_, scope)| {
+                scope
+                    .iter()
+                    .map(|(name, node_id)| (name.clone(), *node_id))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        names.extend(self.locals_at(path, offset));
+
+        let kinds = DefinitionKinds::collect(ast);
+
+        let mut items = names
+            .into_iter()
+            // The native operators are not callable by their name
+            .filter(|(name, _)| {
+                name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+                    && name.chars().all(is_ident)
+            })
+            .map(|(name, node_id)| CompletionItem {
+                label: name,
+                kind: kinds
+                    .0
+                    .get(&node_id)
+                    .copied()
+                    .unwrap_or(completion_kind::VARIABLE),
+                detail: self.types(node_id).first().map(format_type),
+            })
+            .collect::<Vec<_>>();
+
+        items.sort_by(|a, b| a.label.cmp(&b.label));
+        items.dedup_by(|a, b| a.label == b.label);
+
+        items
+    }
+
+    fn locals_at(&self, path: &Path, offset: usize) -> Vec<(String, NodeId)> {
+        let ast = match &self.ast {
+            Some(ast) => ast,
+            None => return vec![],
+        };
+
+        let mut functions = FunctionCollector::default();
+
+        functions.visit_root(ast);
+
+        // A function goes until the next one
+        let function = functions
+            .0
+            .into_iter()
+            .filter_map(|f| Some((self.span(f.node_id)?, f)))
+            .filter(|(span, _)| span.file_path == path && span.start <= offset)
+            .max_by_key(|(span, _)| span.start)
+            .map(|(_, f)| f);
+
+        let function = match function {
+            Some(function) => function,
+            None => return vec![],
+        };
+
+        let mut locals = LocalsCollector::default();
+
+        locals.visit_function_decl(function);
+
+        locals
+            .0
+            .into_iter()
+            .filter(|ident| {
+                self.span(ident.node_id)
+                    .map(|span| span.start < offset)
+                    .unwrap_or(false)
+            })
+            .map(|ident| (ident.name.clone(), ident.node_id))
+            .collect()
+    }
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn format_type(t: &Type) -> String {
+    crate::diagnostics::strip_colors(&t.to_string())
+}
+
+#[derive(Default)]
+struct FunctionCollector<'a>(Vec<&'a FunctionDecl>);
+
+impl<'a> Visitor<'a> for FunctionCollector<'a> {
+    fn visit_function_decl(&mut self, f: &'a FunctionDecl) {
+        self.0.push(f);
+    }
+}
+
+#[derive(Default)]
+struct LocalsCollector<'a>(Vec<&'a Identifier>);
+
+impl<'a> Visitor<'a> for LocalsCollector<'a> {
+    fn visit_function_decl(&mut self, f: &'a FunctionDecl) {
+        self.0.extend(&f.arguments);
+
+        self.visit_body(&f.body);
+    }
+
+    fn visit_assign(&mut self, assign: &'a Assign) {
+        if let (true, AssignLeftSide::Identifier(id)) = (assign.is_let, &assign.name) {
+            if let Some(ident) = id.as_identifier() {
+                self.0.push(ident);
+            }
+        }
+
+        walk_assign(self, assign);
+    }
+
+    fn visit_for_in(&mut self, for_in: &'a ForIn) {
+        self.0.push(&for_in.value);
+
+        walk_for_in(self, for_in);
+    }
+}
+
+// The completion kind of each declaration, the other ones are variables
+#[derive(Default)]
+struct DefinitionKinds(HashMap<NodeId, u32>);
+
+impl DefinitionKinds {
+    fn collect(root: &ast::Root) -> Self {
+        let mut kinds = Self::default();
+
+        kinds.visit_root(root);
+
+        kinds
+    }
+}
+
+impl<'a> Visitor<'a> for DefinitionKinds {
+    fn visit_top_level(&mut self, top_level: &'a TopLevel) {
+        match top_level {
+            // The scopes point to the declarations, not to their names
+            TopLevel::Function(f) => {
+                self.0.insert(f.node_id, completion_kind::FUNCTION);
+            }
+            TopLevel::Clauses(c) => {
+                self.0.insert(c.node_id, completion_kind::FUNCTION);
+            }
+            TopLevel::Extern(p) => {
+                self.0.insert(p.node_id, completion_kind::FUNCTION);
+            }
+            TopLevel::Const(c) => {
+                self.0.insert(c.node_id, completion_kind::CONSTANT);
+            }
+            TopLevel::Struct(s) => {
+                self.0.insert(s.name.node_id, completion_kind::STRUCT);
+            }
+            TopLevel::Newtype(n) => {
+                self.0.insert(n.node_id, completion_kind::STRUCT);
+            }
+            TopLevel::Trait(t) => {
+                for p in &t.defs {
+                    self.0.insert(p.node_id, completion_kind::METHOD);
+                }
+            }
+            TopLevel::Mod(name, m) => {
+                self.0.insert(name.node_id, completion_kind::MODULE);
+
+                self.visit_mod(m);
+            }
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use crate::parser::SourceFile;
+
+use super::protocol::Position;
+
+// Converts the offsets of the content of a file, that the spans point into, from and to
+// the positions that the editor knows. The content has the same lines and columns as the
+// source, after the header that is prepended to it.
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    content: String,
+    line_starts: Vec<usize>,
+    header_lines: usize,
+}
+
+impl LineIndex {
+    pub fn new(file: &SourceFile) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(file.content.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Self {
+            content: file.content.clone(),
+            line_starts,
+            header_lines: file.header_lines(),
+        }
+    }
+
+    pub fn position(&self, offset: usize) -> Position {
+        let offset = offset.min(self.content.len());
+
+        let line_idx = self.line_idx(offset);
+
+        let line = line_idx.saturating_sub(self.header_lines);
+
+        let line_start = self.line_starts[line_idx];
+
+        let character = self.content[line_start..offset].encode_utf16().count();
+
+        Position::new(line as u32, character as u32)
+    }
+
+    // The imports of the std that are prepended to the file
+    pub fn in_header(&self, offset: usize) -> bool {
+        self.line_idx(offset.min(self.content.len())) < self.header_lines
+    }
+
+    fn line_idx(&self, offset: usize) -> usize {
+        match self.line_starts.binary_search(&offset) {
+            Ok(idx) => idx,
+            Err(idx) => idx - 1,
+        }
+    }
+
+    // `None` when the position is past the end of the file
+    pub fn offset(&self, position: Position) -> Option<usize> {
+        let line_start = *self
+            .line_starts
+            .get(self.header_lines + position.line as usize)?;
+
+        let line = self.content[line_start..]
+            .split('\n')
+            .next()
+            .unwrap_or_default();
+
+        let mut utf16 = 0;
+
+        for (i, c) in line.char_indices() {
+            if utf16 >= position.character as usize {
+                return Some(line_start + i);
+            }
+
+            utf16 += c.len_utf16();
+        }
+
+        Some(line_start + line.len())
+    }
+}
@@ -0,0 +1,14 @@
+// A Language Server Protocol server, on the standard input and output.
+// The analyses stop before the code generation.
+
+mod analysis;
+mod line_index;
+mod protocol;
+mod server;
+mod transport;
+
+#[cfg(test)]
+mod tests;
+
+pub(crate) use analysis::Analysis;
+pub use server::{run, Server};
@@ -0,0 +1,121 @@
+use std::path::{Component, Path, PathBuf};
+
+// The subset of the Language Server Protocol types that the server uses
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    pub line: u32,
+    pub character: u32, // In UTF-16 code units
+}
+
+impl Position {
+    pub fn new(line: u32, character: u32) -> Self {
+        Self { line, character }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Range {
+    pub fn new(start: Position, end: Position) -> Self {
+        Self { start, end }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    pub uri: String,
+    pub range: Range,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentSymbol {
+    pub name: String,
+    pub kind: u32,
+    pub range: Range,
+    pub selection_range: Range,
+    pub children: Vec<DocumentSymbol>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletionItem {
+    pub label: String,
+    pub kind: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+pub mod symbol_kind {
+    pub const MODULE: u32 = 2;
+    pub const CLASS: u32 = 5;
+    pub const METHOD: u32 = 6;
+    pub const FIELD: u32 = 8;
+    pub const INTERFACE: u32 = 11;
+    pub const FUNCTION: u32 = 12;
+    pub const CONSTANT: u32 = 14;
+    pub const STRUCT: u32 = 23;
+    pub const TYPE_PARAMETER: u32 = 26;
+}
+
+pub mod completion_kind {
+    pub const METHOD: u32 = 2;
+    pub const FUNCTION: u32 = 3;
+    pub const FIELD: u32 = 5;
+    pub const VARIABLE: u32 = 6;
+    pub const MODULE: u32 = 9;
+    pub const CONSTANT: u32 = 21;
+    pub const STRUCT: u32 = 22;
+}
+
+pub mod error_code {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const SERVER_NOT_INITIALIZED: i64 = -32002;
+}
+
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+
+    let mut bytes = vec![];
+    let mut chars = path.bytes();
+
+    while let Some(c) = chars.next() {
+        if c == b'%' {
+            let hex = [chars.next()?, chars.next()?];
+
+            bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            bytes.push(c);
+        }
+    }
+
+    Some(normalize(Path::new(&String::from_utf8(bytes).ok()?)))
+}
+
+pub fn path_to_uri(path: &Path) -> String {
+    let mut uri = "file://".to_string();
+
+    for c in path.to_string_lossy().bytes() {
+        if c.is_ascii_alphanumeric() || b"/-_.~".contains(&c) {
+            uri.push(c as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", c));
+        }
+    }
+
+    uri
+}
+
+// Removes the `.` components, as the entry point of a project is `./src/main.rk`
+pub fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
@@ -0,0 +1,558 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    io::{BufRead, Write},
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    config::ProjectConfig,
+    diagnostics::{DiagnosticType, JsonDiagnostic},
+    Config,
+};
+
+use super::{
+    analysis::Analysis,
+    protocol::*,
+    transport::{read_message, write_message},
+};
+
+type RequestResult = Result<Value, (i64, String)>;
+
+// The analyses of a project, keyed by its entry point. The last one that
+// inferred the types is kept while the code does not compile.
+#[derive(Debug)]
+struct Project {
+    latest: Analysis,
+    typed: Option<Analysis>,
+}
+
+impl Project {
+    fn contains(&self, path: &Path) -> bool {
+        self.latest.files.contains_key(path)
+            || self
+                .typed
+                .as_ref()
+                .map(|typed| typed.files.contains_key(path))
+                .unwrap_or(false)
+    }
+
+    fn resolved(&self) -> &Analysis {
+        match &self.typed {
+            Some(typed) if self.latest.ast.is_none() => typed,
+            _ => &self.latest,
+        }
+    }
+
+    fn typed(&self) -> &Analysis {
+        match &self.typed {
+            Some(typed) if self.latest.hir.is_none() => typed,
+            _ => &self.latest,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Server {
+    config: Config,
+    documents: HashMap<PathBuf, String>, // The files opened in the editor
+    projects: HashMap<PathBuf, Project>,
+    published: HashSet<PathBuf>, // The files that have diagnostics in the editor
+    initialized: bool,
+    shutdown: bool,
+    exit: bool,
+}
+
+impl Server {
+    pub fn new(config: &Config) -> Self {
+        let mut config = config.clone();
+
+        // The standard output is the channel of the protocol
+        config.quiet = true;
+        config.verbose = false;
+
+        Self {
+            config,
+            documents: HashMap::new(),
+            projects: HashMap::new(),
+            published: HashSet::new(),
+            initialized: false,
+            shutdown: false,
+            exit: false,
+        }
+    }
+
+    pub fn serve<R: BufRead, W: Write>(&mut self, mut reader: R, mut writer: W) -> io::Result<()> {
+        while let Some(message) = read_message(&mut reader)? {
+            for response in self.handle(message) {
+                write_message(&mut writer, &response)?;
+            }
+
+            if self.exit {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Returns the messages to send back, the response of a request comes last
+    pub fn handle(&mut self, message: Value) -> Vec<Value> {
+        let mut out = vec![];
+
+        let method = match message.get("method").and_then(Value::as_str) {
+            Some(method) => method.to_string(),
+            // The responses of the client are ignored, as we send no request
+            None if message.is_object() => return out,
+            None => {
+                out.push(error_response(
+                    Value::Null,
+                    error_code::PARSE_ERROR,
+                    "Invalid message",
+                ));
+
+                return out;
+            }
+        };
+
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        match message.get("id").cloned() {
+            Some(id) => {
+                let response = match self.request(&method, &params) {
+                    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                    Err((code, msg)) => error_response(id, code, &msg),
+                };
+
+                out.push(response);
+            }
+            None => self.notification(&method, &params, &mut out),
+        }
+
+        out
+    }
+
+    fn request(&mut self, method: &str, params: &Value) -> RequestResult {
+        if method == "initialize" {
+            self.initialized = true;
+
+            return Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "referencesProvider": true,
+                    "documentSymbolProvider": true,
+                    "completionProvider": { "triggerCharacters": ["."] },
+                },
+                "serverInfo": { "name": "rock", "version": env!("CARGO_PKG_VERSION") },
+            }));
+        }
+
+        if !self.initialized {
+            return Err((
+                error_code::SERVER_NOT_INITIALIZED,
+                "The server is not initialized".to_string(),
+            ));
+        }
+
+        if self.shutdown {
+            return Err((
+                error_code::INVALID_REQUEST,
+                "The server is shutting down".to_string(),
+            ));
+        }
+
+        match method {
+            "shutdown" => {
+                self.shutdown = true;
+
+                Ok(Value::Null)
+            }
+            "textDocument/hover" => self.hover(params),
+            "textDocument/definition" => self.definition(params),
+            "textDocument/references" => self.references(params),
+            "textDocument/documentSymbol" => self.document_symbols(params),
+            "textDocument/completion" => self.completion(params),
+            _ => Err((
+                error_code::METHOD_NOT_FOUND,
+                format!("Unknown method: {}", method),
+            )),
+        }
+    }
+
+    fn notification(&mut self, method: &str, params: &Value, out: &mut Vec<Value>) {
+        if method == "exit" {
+            self.exit = true;
+
+            return;
+        }
+
+        let path = match document_path(params) {
+            Some(path) => path,
+            None => return,
+        };
+
+        match method {
+            "textDocument/didOpen" => {
+                if let Some(text) = params["textDocument"]["text"].as_str() {
+                    self.documents.insert(path.clone(), text.to_string());
+                }
+            }
+            // The whole content is sent on each change
+            "textDocument/didChange" => {
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+
+                if let Some(text) = text {
+                    self.documents.insert(path.clone(), text.to_string());
+                }
+            }
+            "textDocument/didSave" => (),
+            "textDocument/didClose" => {
+                self.documents.remove(&path);
+            }
+            _ => return,
+        }
+
+        self.analyze(&path, out);
+    }
+
+    fn analyze(&mut self, path: &Path, out: &mut Vec<Value>) {
+        let (entry, project_config) = self.entry_point(path);
+
+        let mut config = self.config.clone();
+
+        config.overlays = Arc::new(self.documents.clone());
+
+        if let Some(project_config) = project_config {
+            config.lints = project_config.lints.clone();
+            config.project_config = project_config;
+        }
+
+        // A bug of the compiler must not stop the server, the panic is printed on stderr
+        let analysis =
+            match panic::catch_unwind(AssertUnwindSafe(|| Analysis::new(&entry, &config))) {
+                Ok(analysis) => analysis,
+                Err(_) => return,
+            };
+
+        self.publish_diagnostics(&analysis, out);
+
+        let typed = match self.projects.remove(&entry) {
+            _ if analysis.hir.is_some() => None,
+            Some(project) if project.latest.hir.is_some() => Some(project.latest),
+            Some(project) => project.typed,
+            None => None,
+        };
+
+        self.projects.insert(
+            entry,
+            Project {
+                latest: analysis,
+                typed,
+            },
+        );
+    }
+
+    // The entry point of the project of a file, and its config when it has a manifest.
+    // A file out of any project is compiled on its own.
+    fn entry_point(&self, path: &Path) -> (PathBuf, Option<ProjectConfig>) {
+        if let Some((entry, _)) = self
+            .projects
+            .iter()
+            .find(|(_, project)| project.contains(path))
+        {
+            let project_config = ProjectConfig::find_manifest(entry)
+                .and_then(|manifest| ProjectConfig::from_manifest(&manifest).ok());
+
+            return (entry.clone(), project_config);
+        }
+
+        let project_config = path
+            .parent()
+            .and_then(ProjectConfig::find_manifest)
+            .and_then(|manifest| ProjectConfig::from_manifest(&manifest).ok());
+
+        if let Some(project_config) = project_config {
+            let entry = normalize(&project_config.entry_file());
+
+            if entry.is_file() || self.documents.contains_key(&entry) {
+                return (entry, Some(project_config));
+            }
+        }
+
+        (path.to_path_buf(), None)
+    }
+
+    fn publish_diagnostics(&mut self, analysis: &Analysis, out: &mut Vec<Value>) {
+        let mut by_file: HashMap<PathBuf, Vec<Value>> = analysis
+            .project_files()
+            .map(|path| (path.clone(), vec![]))
+            .collect();
+
+        for (diag, diag_type) in analysis
+            .diagnostics
+            .list
+            .iter()
+            .zip(analysis.diagnostics.list_types.iter())
+        {
+            // Not tied to any file, like a missing `main`
+            let location = if diag.span.file_path.as_os_str().is_empty() {
+                Some(Location {
+                    uri: path_to_uri(&analysis.entry),
+                    range: Range::new(Position::new(0, 0), Position::new(0, 0)),
+                })
+            } else {
+                analysis.location(&diag.span)
+            };
+
+            let location = match location {
+                Some(location) => location,
+                None => continue,
+            };
+
+            let json = JsonDiagnostic::new(diag, diag_type, &analysis.files);
+
+            let mut message = json.message;
+
+            if let Some(note) = json.note {
+                message += &format!("\n{}", note);
+            }
+
+            for suggestion in &json.suggestions {
+                message += &format!("\nHelp: {}", suggestion.message);
+            }
+
+            let related = diag
+                .get_kind()
+                .secondary_labels()
+                .iter()
+                .zip(json.secondary_labels.iter())
+                .filter_map(|((span, _), label)| {
+                    Some(json!({
+                        "location": analysis.location(span)?,
+                        "message": label.message,
+                    }))
+                })
+                .collect::<Vec<_>>();
+
+            let severity = match diag_type {
+                DiagnosticType::Error => 1,
+                DiagnosticType::Warning => 2,
+            };
+
+            by_file
+                .entry(uri_to_path(&location.uri).unwrap())
+                .or_default()
+                .push(json!({
+                    "range": location.range,
+                    "severity": severity,
+                    "code": json.code,
+                    "source": "rock",
+                    "message": message,
+                    "relatedInformation": related,
+                }));
+        }
+
+        // The files that are fixed, or not part of the project anymore
+        for path in self.published.drain() {
+            by_file.entry(path).or_default();
+        }
+
+        let mut paths = by_file.keys().cloned().collect::<Vec<_>>();
+
+        paths.sort();
+
+        for path in paths {
+            let diagnostics = by_file.remove(&path).unwrap();
+
+            if !diagnostics.is_empty() {
+                self.published.insert(path.clone());
+            }
+
+            out.push(json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/publishDiagnostics",
+                "params": { "uri": path_to_uri(&path), "diagnostics": diagnostics },
+            }));
+        }
+    }
+
+    fn project(&self, path: &Path) -> Option<&Project> {
+        self.projects
+            .values()
+            .find(|project| project.contains(path))
+    }
+
+    fn hover(&self, params: &Value) -> RequestResult {
+        let (path, position) = text_document_position(params)?;
+
+        let analysis = match self.project(&path) {
+            Some(project) => project.typed(),
+            None => return Ok(Value::Null),
+        };
+
+        let hover = analysis
+            .line_index(&path)
+            .and_then(|line_index| line_index.offset(position))
+            .and_then(|offset| analysis.node_at(&path, offset))
+            .and_then(|node_id| analysis.hover(node_id));
+
+        Ok(match hover {
+            Some(hover) => json!({
+                "contents": { "kind": "markdown", "value": format!("```rock\n{}\n```", hover) },
+            }),
+            None => Value::Null,
+        })
+    }
+
+    fn definition(&self, params: &Value) -> RequestResult {
+        let (path, position) = text_document_position(params)?;
+
+        let analysis = match self.project(&path) {
+            Some(project) => project.resolved(),
+            None => return Ok(Value::Null),
+        };
+
+        let location = analysis
+            .line_index(&path)
+            .and_then(|line_index| line_index.offset(position))
+            .and_then(|offset| analysis.node_at(&path, offset))
+            .and_then(|node_id| analysis.definition_location(node_id));
+
+        Ok(json!(location))
+    }
+
+    fn references(&self, params: &Value) -> RequestResult {
+        let (path, position) = text_document_position(params)?;
+
+        let include_declaration = params["context"]["includeDeclaration"]
+            .as_bool()
+            .unwrap_or(true);
+
+        let analysis = match self.project(&path) {
+            Some(project) => project.resolved(),
+            None => return Ok(json!([])),
+        };
+
+        let references = analysis
+            .line_index(&path)
+            .and_then(|line_index| line_index.offset(position))
+            .and_then(|offset| analysis.node_at(&path, offset))
+            .map(|node_id| analysis.references(analysis.definition(node_id), include_declaration))
+            .unwrap_or_default();
+
+        Ok(json!(references))
+    }
+
+    fn document_symbols(&self, params: &Value) -> RequestResult {
+        let path = document_path(params).ok_or_else(invalid_params)?;
+
+        let symbols = self
+            .project(&path)
+            .map(|project| project.resolved().document_symbols(&path))
+            .unwrap_or_default();
+
+        Ok(json!(symbols))
+    }
+
+    fn completion(&self, params: &Value) -> RequestResult {
+        let (path, position) = text_document_position(params)?;
+
+        let analysis = match self.project(&path) {
+            Some(project) => project.typed(),
+            None => return Ok(json!({ "isIncomplete": false, "items": [] })),
+        };
+
+        let text = self
+            .documents
+            .get(&path)
+            .cloned()
+            .or_else(|| fs::read_to_string(&path).ok())
+            .unwrap_or_default();
+
+        let line = text
+            .split('\n')
+            .nth(position.line as usize)
+            .unwrap_or_default();
+
+        let mut utf16 = 0;
+        let before_cursor = line
+            .chars()
+            .take_while(|c| {
+                utf16 += c.len_utf16();
+
+                utf16 <= position.character as usize
+            })
+            .collect::<String>();
+
+        let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+
+        // The field being written, after a dot
+        let receiver = before_cursor
+            .trim_end_matches(is_ident)
+            .strip_suffix('.')
+            .map(|before_dot| {
+                let start = before_dot.trim_end_matches(is_ident).len();
+
+                before_dot[start..].to_string()
+            });
+
+        // The analysis may be older than the text, the position is then approximative
+        let offset = analysis
+            .line_index(&path)
+            .and_then(|line_index| line_index.offset(position))
+            .or_else(|| analysis.files.get(&path).map(|file| file.content.len()))
+            .unwrap_or_default();
+
+        let items = match receiver {
+            Some(receiver) if !receiver.is_empty() => {
+                analysis.field_completions(&path, offset, &receiver)
+            }
+            Some(_) => vec![],
+            None => analysis.scope_completions(&path, offset),
+        };
+
+        Ok(json!({ "isIncomplete": false, "items": items }))
+    }
+}
+
+// Serves the editor on the standard input and output
+pub fn run(config: &Config) -> io::Result<()> {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+
+    Server::new(config).serve(stdin.lock(), stdout.lock())
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn invalid_params() -> (i64, String) {
+    (error_code::INVALID_PARAMS, "Invalid params".to_string())
+}
+
+fn document_path(params: &Value) -> Option<PathBuf> {
+    uri_to_path(params["textDocument"]["uri"].as_str()?)
+}
+
+fn text_document_position(params: &Value) -> Result<(PathBuf, Position), (i64, String)> {
+    let path = document_path(params).ok_or_else(invalid_params)?;
+
+    let position =
+        serde_json::from_value(params["position"].clone()).map_err(|_| invalid_params())?;
+
+    Ok((path, position))
+}
@@ -0,0 +1,437 @@
+use std::{
+    fs,
+    io::Cursor,
+    path::{Path, PathBuf},
+};
+
+use serde_json::{json, Value};
+
+use super::{
+    protocol::{error_code, path_to_uri, uri_to_path},
+    transport::{read_message, write_message},
+    Server,
+};
+use crate::Config;
+
+const MAIN: &str = "struct Point
+  x: Int64
+  y: Int64
+
+# Some comment
+add: a, b -> a + b
+
+main: ->
+  let p = Point
+    x: 1
+    y: 2
+  let sum = add p.x, p.y
+  sum
+";
+
+// A scripted session: the messages are framed, served, and the ones sent back are
+// returned in order
+fn session(messages: Vec<Value>) -> Vec<Value> {
+    let mut input = vec![];
+
+    for message in &messages {
+        write_message(&mut input, message).unwrap();
+    }
+
+    let mut output = vec![];
+
+    Server::new(&config())
+        .serve(Cursor::new(input), &mut output)
+        .unwrap();
+
+    let mut reader = Cursor::new(output);
+    let mut responses = vec![];
+
+    while let Some(message) = read_message(&mut reader).unwrap() {
+        responses.push(message);
+    }
+
+    responses
+}
+
+fn config() -> Config {
+    Config {
+        std: true,
+        ..Config::default()
+    }
+}
+
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rock_lsp_{}_{}", name, std::process::id()));
+
+    let _ = fs::remove_dir_all(&dir);
+
+    fs::create_dir_all(&dir).unwrap();
+
+    dir
+}
+
+fn initialize() -> Value {
+    json!({ "jsonrpc": "2.0", "id": 0, "method": "initialize", "params": {} })
+}
+
+fn did_open(path: &Path, text: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {
+            "textDocument": { "uri": path_to_uri(path), "languageId": "rock", "version": 1, "text": text },
+        },
+    })
+}
+
+fn did_change(path: &Path, text: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didChange",
+        "params": {
+            "textDocument": { "uri": path_to_uri(path), "version": 2 },
+            "contentChanges": [{ "text": text }],
+        },
+    })
+}
+
+fn request(id: u64, method: &str, path: &Path, line: u32, character: u32) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": method,
+        "params": {
+            "textDocument": { "uri": path_to_uri(path) },
+            "position": { "line": line, "character": character },
+            "context": { "includeDeclaration": true },
+        },
+    })
+}
+
+fn response(responses: &[Value], id: u64) -> &Value {
+    responses
+        .iter()
+        .find(|response| response["id"] == json!(id))
+        .unwrap()
+}
+
+fn published(responses: &[Value]) -> Vec<&Value> {
+    responses
+        .iter()
+        .filter(|response| response["method"] == "textDocument/publishDiagnostics")
+        .map(|response| &response["params"])
+        .collect()
+}
+
+fn labels(items: &Value) -> Vec<String> {
+    items
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["label"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod transport {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let message = json!({ "jsonrpc": "2.0", "id": 1, "method": "shutdown" });
+
+        let mut buffer = vec![];
+
+        write_message(&mut buffer, &message).unwrap();
+        write_message(&mut buffer, &message).unwrap();
+
+        let mut reader = Cursor::new(buffer);
+
+        assert_eq!(read_message(&mut reader).unwrap(), Some(message.clone()));
+        assert_eq!(read_message(&mut reader).unwrap(), Some(message));
+        assert_eq!(read_message(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn invalid_content() {
+        let mut reader = Cursor::new(b"Content-Length: 3\r\n\r\n{{{".to_vec());
+
+        assert_eq!(read_message(&mut reader).unwrap(), Some(Value::Null));
+    }
+
+    #[test]
+    fn uris() {
+        let path = Path::new("/tmp/some dir/main.rk");
+
+        let uri = path_to_uri(path);
+
+        assert_eq!(uri, "file:///tmp/some%20dir/main.rk");
+        assert_eq!(uri_to_path(&uri).unwrap(), path);
+    }
+}
+
+#[cfg(test)]
+mod lifecycle {
+    use super::*;
+
+    #[test]
+    fn not_initialized() {
+        let path = Path::new("/tmp/main.rk");
+
+        let responses = session(vec![request(1, "textDocument/hover", path, 0, 0)]);
+
+        assert_eq!(
+            response(&responses, 1)["error"]["code"],
+            json!(error_code::SERVER_NOT_INITIALIZED)
+        );
+    }
+
+    #[test]
+    fn initialize_and_exit() {
+        let responses = session(vec![
+            initialize(),
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "unknown/method" }),
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "shutdown" }),
+            json!({ "jsonrpc": "2.0", "method": "exit" }),
+            // Not answered, the server exited
+            json!({ "jsonrpc": "2.0", "id": 3, "method": "shutdown" }),
+        ]);
+
+        let capabilities = &response(&responses, 0)["result"]["capabilities"];
+
+        assert_eq!(capabilities["hoverProvider"], json!(true));
+        assert_eq!(
+            capabilities["completionProvider"]["triggerCharacters"],
+            json!(["."])
+        );
+
+        assert_eq!(
+            response(&responses, 1)["error"]["code"],
+            json!(error_code::METHOD_NOT_FOUND)
+        );
+        assert_eq!(response(&responses, 2)["result"], Value::Null);
+        assert_eq!(responses.len(), 3);
+    }
+
+    #[test]
+    fn invalid_message() {
+        let mut input = b"Content-Length: 3\r\n\r\n{{{".to_vec();
+
+        write_message(&mut input, &json!({ "jsonrpc": "2.0", "method": "exit" })).unwrap();
+
+        let mut output = vec![];
+
+        Server::new(&Config::default())
+            .serve(Cursor::new(input), &mut output)
+            .unwrap();
+
+        let response = read_message(&mut Cursor::new(output)).unwrap().unwrap();
+
+        assert_eq!(response["error"]["code"], json!(error_code::PARSE_ERROR));
+    }
+}
+
+#[cfg(test)]
+mod features {
+    use super::*;
+
+    #[test]
+    fn diagnostics_on_change() {
+        let path = test_dir("diagnostics").join("main.rk");
+
+        let broken = MAIN.replace("y: 2", "y: 2\n    z: 3");
+
+        let responses = session(vec![
+            initialize(),
+            did_open(&path, MAIN),
+            did_change(&path, &broken),
+            did_change(&path, MAIN),
+        ]);
+
+        let published = published(&responses);
+
+        assert_eq!(published.len(), 3);
+        assert_eq!(published[0]["diagnostics"], json!([]));
+
+        let diagnostic = &published[1]["diagnostics"][0];
+
+        assert_eq!(diagnostic["code"], "R0023");
+        assert_eq!(diagnostic["severity"], 1);
+        assert_eq!(
+            diagnostic["range"],
+            json!({ "start": { "line": 11, "character": 4 }, "end": { "line": 11, "character": 5 } })
+        );
+
+        // Cleared once fixed
+        assert_eq!(published[2]["diagnostics"], json!([]));
+    }
+
+    #[test]
+    fn hover() {
+        let path = test_dir("hover").join("main.rk");
+
+        let responses = session(vec![
+            initialize(),
+            did_open(&path, MAIN),
+            request(1, "textDocument/hover", &path, 11, 7),
+            request(2, "textDocument/hover", &path, 5, 1),
+            request(3, "textDocument/hover", &path, 5, 6),
+            request(4, "textDocument/hover", &path, 3, 0),
+        ]);
+
+        let hover = |id| response(&responses, id)["result"]["contents"]["value"].clone();
+
+        assert_eq!(hover(1), "```rock\nsum: Int64\n```");
+        assert_eq!(hover(2), "```rock\nadd: (Int64 => Int64 => Int64)\n```");
+        assert_eq!(hover(3), "```rock\na: Int64\n```");
+        assert_eq!(response(&responses, 4)["result"], Value::Null);
+    }
+
+    #[test]
+    fn definition_and_references() {
+        let path = test_dir("definition").join("main.rk");
+
+        let responses = session(vec![
+            initialize(),
+            did_open(&path, MAIN),
+            request(1, "textDocument/definition", &path, 11, 13),
+            request(2, "textDocument/definition", &path, 8, 12),
+            request(3, "textDocument/references", &path, 8, 6),
+        ]);
+
+        let range = |line, start, end| {
+            json!({
+                "start": { "line": line, "character": start },
+                "end": { "line": line, "character": end },
+            })
+        };
+
+        let definition = &response(&responses, 1)["result"];
+
+        assert_eq!(definition["uri"], json!(path_to_uri(&path)));
+        assert_eq!(definition["range"], range(5, 0, 3));
+
+        assert_eq!(response(&responses, 2)["result"]["range"], range(0, 7, 12));
+
+        let references = response(&responses, 3)["result"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|location| location["range"].clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            references,
+            vec![range(8, 6, 7), range(11, 16, 17), range(11, 21, 22)]
+        );
+    }
+
+    #[test]
+    fn document_symbols() {
+        let path = test_dir("symbols").join("main.rk");
+
+        let responses = session(vec![
+            initialize(),
+            did_open(&path, MAIN),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "textDocument/documentSymbol",
+                "params": { "textDocument": { "uri": path_to_uri(&path) } },
+            }),
+        ]);
+
+        let symbols = response(&responses, 1)["result"]
+            .as_array()
+            .unwrap()
+            .clone();
+
+        let names = symbols
+            .iter()
+            .map(|symbol| symbol["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+
+        // The std is not part of the file
+        assert_eq!(names, vec!["Point", "add", "main"]);
+
+        let fields = symbols[0]["children"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|field| field["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(fields, vec!["x", "y"]);
+    }
+
+    #[test]
+    fn completion() {
+        let path = test_dir("completion").join("main.rk");
+
+        let responses = session(vec![
+            initialize(),
+            did_open(&path, MAIN),
+            request(1, "textDocument/completion", &path, 11, 18),
+            request(2, "textDocument/completion", &path, 12, 2),
+            request(3, "textDocument/completion", &path, 9, 4),
+        ]);
+
+        let fields = &response(&responses, 1)["result"]["items"];
+
+        assert_eq!(labels(fields), vec!["x", "y"]);
+        assert_eq!(fields[0]["detail"], "Int64");
+
+        let names = labels(&response(&responses, 2)["result"]["items"]);
+
+        for name in ["Point", "add", "main", "p", "sum", "puts"] {
+            assert!(names.contains(&name.to_string()), "{} is missing", name);
+        }
+
+        // The native operators cannot be called by their name
+        assert!(!names.iter().any(|name| name.contains('+')));
+
+        // The locals that are declared after the cursor
+        let names = labels(&response(&responses, 3)["result"]["items"]);
+
+        assert!(names.contains(&"add".to_string()));
+        assert!(!names.contains(&"sum".to_string()));
+    }
+
+    #[test]
+    fn project() {
+        let dir = test_dir("project");
+
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("Rock.toml"), "[package]\nname = \"project\"\n").unwrap();
+        fs::write(dir.join("src/main.rk"), "mod foo\n\nmain: -> foo::bar 2\n").unwrap();
+        fs::write(dir.join("src/foo.rk"), "bar: x -> x + 1\n").unwrap();
+
+        let main = dir.join("src/main.rk");
+        let foo = dir.join("src/foo.rk");
+
+        let responses = session(vec![
+            initialize(),
+            did_open(&foo, "bar: x -> x + 1\n"),
+            request(1, "textDocument/definition", &main, 2, 15),
+            // Unsaved, the disk file still compiles
+            did_change(&foo, "bar: x -> x + \"a\"\n"),
+        ]);
+
+        let definition = &response(&responses, 1)["result"];
+
+        assert_eq!(definition["uri"], json!(path_to_uri(&foo)));
+        assert_eq!(
+            definition["range"]["start"],
+            json!({ "line": 0, "character": 0 })
+        );
+
+        let published = published(&responses);
+
+        // Both files are published on each change, in the order of their paths
+        assert_eq!(published.len(), 4);
+        assert_eq!(published[2]["uri"], json!(path_to_uri(&foo)));
+        assert_eq!(published[2]["diagnostics"][0]["code"], "R0008");
+        assert_eq!(published[3]["uri"], json!(path_to_uri(&main)));
+        assert_eq!(published[3]["diagnostics"], json!([]));
+    }
+}
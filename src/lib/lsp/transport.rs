@@ -0,0 +1,57 @@
+use std::io::{self, BufRead, Write};
+
+use serde_json::Value;
+
+// The messages are JSON-RPC objects, each preceded by a `Content-Length` header
+
+pub fn read_message<R: BufRead>(reader: &mut R) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+
+    loop {
+        let mut line = String::new();
+
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+
+        if line.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+
+            continue;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                content_length = Some(value.trim().parse::<usize>().map_err(|e| {
+                    io::Error::new(io::ErrorKind::InvalidData, format!("Bad header: {}", e))
+                })?);
+            }
+        }
+    }
+
+    let mut content = vec![0; content_length.unwrap()];
+
+    reader.read_exact(&mut content)?;
+
+    // An invalid content is answered with a parse error, not a broken connection
+    Ok(Some(
+        serde_json::from_slice(&content).unwrap_or(Value::Null),
+    ))
+}
+
+pub fn write_message<W: Write>(writer: &mut W, message: &Value) -> io::Result<()> {
+    let content = serde_json::to_string(message)?;
+
+    write!(
+        writer,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+
+    writer.flush()
+}
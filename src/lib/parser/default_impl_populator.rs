@@ -1,17 +1,33 @@
 use std::collections::BTreeMap;
 
-use crate::{ast::visit_mut::*, ast::*};
+use crate::{
+    ast::visit::Visitor,
+    ast::visit_mut::*,
+    ast::*,
+    diagnostics::{Diagnostic, Diagnostics},
+    parser::span::Span,
+};
 
-#[derive(Debug)]
-pub struct DefaultImplPopulator {
-    pub traits: BTreeMap<String, Trait>,
+// The traits are collected before the impls are populated, as an impl can come first
+#[derive(Debug, Default)]
+struct TraitCollector {
+    traits: BTreeMap<String, Trait>,
 }
 
-impl<'a> VisitorMut<'a> for DefaultImplPopulator {
-    fn visit_trait(&mut self, trait_: &mut Trait) {
+impl<'a> Visitor<'a> for TraitCollector {
+    fn visit_trait(&mut self, trait_: &'a Trait) {
         self.traits.insert(trait_.name.get_name(), trait_.clone());
     }
+}
+
+#[derive(Debug)]
+pub struct DefaultImplPopulator<'b> {
+    pub traits: BTreeMap<String, Trait>,
+    pub identities: &'b BTreeMap<NodeId, Span>,
+    pub diagnostics: Diagnostics,
+}
 
+impl<'a, 'b> VisitorMut<'a> for DefaultImplPopulator<'b> {
     fn visit_impl(&mut self, i: &'a mut Impl) {
         // If this is not a Trait impl (but a simple impl)
         // then we don't need to do anything.
@@ -20,7 +36,19 @@ impl<'a> VisitorMut<'a> for DefaultImplPopulator {
         }
 
         let trait_name = i.name.get_name();
-        let trait_ = self.traits.get(&trait_name).unwrap();
+        let trait_ = match self.traits.get(&trait_name) {
+            Some(trait_) => trait_,
+            None => {
+                self.diagnostics.push_error(
+                    Diagnostic::new_unknown_identifier(
+                        self.identities.get(&i.node_id).unwrap().clone(),
+                    )
+                    .with_best_match(&trait_name, self.traits.keys()),
+                );
+
+                return;
+            }
+        };
 
         // We remove any default implementation that has been overriden
         let default_impl: Vec<_> = trait_
@@ -39,9 +67,18 @@ impl<'a> VisitorMut<'a> for DefaultImplPopulator {
     }
 }
 
-pub fn populate_default_impl(root: &mut Root) {
-    DefaultImplPopulator {
-        traits: BTreeMap::new(),
-    }
-    .visit_root(root);
+pub fn populate_default_impl(root: &mut Root, identities: &BTreeMap<NodeId, Span>) -> Diagnostics {
+    let mut trait_collector = TraitCollector::default();
+
+    trait_collector.visit_root(root);
+
+    let mut populator = DefaultImplPopulator {
+        traits: trait_collector.traits,
+        identities,
+        diagnostics: Diagnostics::default(),
+    };
+
+    populator.visit_root(root);
+
+    populator.diagnostics
 }
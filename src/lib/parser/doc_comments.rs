@@ -0,0 +1,161 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+use crate::ast::{
+    tree::{Mod, Root, TopLevel},
+    NodeId,
+};
+
+use super::{SourceFile, Span};
+
+// The `##` comments right above an item, or above its annotations, document it.
+// They are attached to the node id of the item, without their `## ` prefix.
+pub fn collect(
+    root: &Root,
+    identities: &BTreeMap<NodeId, Span>,
+    files: &HashMap<PathBuf, SourceFile>,
+) -> HashMap<NodeId, String> {
+    let mut docs = HashMap::new();
+
+    collect_mod(&root.r#mod, identities, files, &mut docs);
+
+    docs
+}
+
+fn collect_mod(
+    r#mod: &Mod,
+    identities: &BTreeMap<NodeId, Span>,
+    files: &HashMap<PathBuf, SourceFile>,
+    docs: &mut HashMap<NodeId, String>,
+) {
+    for (node_id, _) in items(r#mod) {
+        let doc = identities
+            .get(&node_id)
+            .and_then(|span| doc_above(files.get(&span.file_path)?, span.start));
+
+        if let Some(doc) = doc {
+            docs.insert(node_id, doc);
+        }
+    }
+
+    for top_level in &r#mod.top_levels {
+        if let TopLevel::Mod(_, r#mod) = top_level {
+            collect_mod(r#mod, identities, files, docs);
+        }
+    }
+}
+
+// The items of a module that can be documented, without the ones of its submodules.
+// The members of the structs, traits and impls are named after them, like `Point.x`.
+pub fn items(r#mod: &Mod) -> Vec<(NodeId, String)> {
+    let mut items = vec![];
+
+    let member = |parent: &str, name: &str| format!("{}.{}", parent, name);
+
+    for top_level in &r#mod.top_levels {
+        match top_level {
+            TopLevel::Extern(p) | TopLevel::FnSignature(p) => {
+                items.push((p.node_id, p.name.name.clone()))
+            }
+            TopLevel::Function(f) => items.push((f.node_id, f.name.name.clone())),
+            TopLevel::Clauses(c) => items.push((c.node_id, c.name.name.clone())),
+            TopLevel::Const(c) => items.push((c.node_id, c.name.name.clone())),
+            TopLevel::TypeAlias(t) => items.push((t.name.node_id, t.name.name.clone())),
+            TopLevel::Newtype(n) => items.push((n.node_id, n.name.name.clone())),
+            TopLevel::Struct(s) => {
+                items.push((s.name.node_id, s.name.name.clone()));
+                items.extend(
+                    s.defs
+                        .iter()
+                        .map(|p| (p.node_id, member(&s.name.name, &p.name.name))),
+                );
+            }
+            TopLevel::Trait(t) => {
+                let name = t.name.get_name();
+
+                items.push((t.node_id, name.clone()));
+                items.extend(
+                    t.defs
+                        .iter()
+                        .map(|p| (p.node_id, member(&name, &p.name.name))),
+                );
+                items.extend(
+                    t.default_impl
+                        .iter()
+                        .map(|f| (f.node_id, member(&name, &f.name.name))),
+                );
+            }
+            TopLevel::Impl(i) => {
+                let name = i.types.first().unwrap_or(&i.name).get_name();
+
+                items.push((i.node_id, name.clone()));
+                items.extend(
+                    i.defs
+                        .iter()
+                        .map(|f| (f.node_id, member(&name, &f.name.name))),
+                );
+            }
+            TopLevel::Mod(name, _) => items.push((name.node_id, name.name.clone())),
+            TopLevel::Test(_) | TopLevel::Use(_) | TopLevel::Infix(_, _) => (),
+        }
+    }
+
+    items
+}
+
+// The code blocks of a doc comment that are run as tests, the ones without a language
+// or in `rock`
+pub fn examples(doc: &str) -> Vec<String> {
+    let mut examples = vec![];
+
+    // Whether the open block runs, and its lines
+    let mut block: Option<(bool, Vec<&str>)> = None;
+
+    for line in doc.lines() {
+        let fence = line.trim_start().strip_prefix("```");
+
+        match (&mut block, fence) {
+            (None, Some(lang)) => block = Some((matches!(lang.trim(), "" | "rock"), vec![])),
+            (Some((runs, lines)), Some(_)) => {
+                if *runs && lines.iter().any(|line| !line.trim().is_empty()) {
+                    examples.push(lines.join("\n"));
+                }
+
+                block = None;
+            }
+            (Some((_, lines)), None) => lines.push(line),
+            (None, None) => (),
+        }
+    }
+
+    examples
+}
+
+// The offset is in the content of the file, that can have a header before the source
+fn doc_above(file: &SourceFile, offset: usize) -> Option<String> {
+    let offset = file.source_offset(offset)?;
+
+    let line_start = file.source[..offset].rfind('\n').map_or(0, |i| i + 1);
+
+    let mut lines = vec![];
+
+    for line in file.source[..line_start].lines().rev() {
+        let line = line.trim();
+
+        if let Some(doc) = line.strip_prefix("##") {
+            lines.push(doc.strip_prefix(' ').unwrap_or(doc));
+        } else if !line.starts_with("@[") {
+            break;
+        }
+    }
+
+    if lines.is_empty() {
+        return None;
+    }
+
+    lines.reverse();
+
+    Some(lines.join("\n"))
+}
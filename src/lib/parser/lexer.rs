@@ -0,0 +1,168 @@
+// The tokenizer in front of the parser. It is lossless: the tokens cover the whole source,
+// so the comments and the strings are known to every tool, and the content given to the
+// parser keeps the offsets of the original file.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Code,
+    Whitespace, // Spaces and tabs
+    Newline,
+    LineComment,  // From `#` to the end of the line
+    DocComment,   // From `##` to the end of the line, documents the item below it
+    BlockComment, // Between `/*` and `*/`, can span several lines
+    String,       // With its quotes, the escapes are kept as is
+    Char,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Token {
+    pub fn is_comment(&self) -> bool {
+        matches!(
+            self.kind,
+            TokenKind::LineComment | TokenKind::DocComment | TokenKind::BlockComment
+        )
+    }
+
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.start..self.end]
+    }
+}
+
+pub fn tokenize(source: &str) -> Vec<Token> {
+    let bytes = source.as_bytes();
+
+    let mut tokens: Vec<Token> = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let start = i;
+
+        let kind = match bytes[i] {
+            b'\n' => {
+                i += 1;
+
+                TokenKind::Newline
+            }
+            b'\r' if bytes.get(i + 1) == Some(&b'\n') => {
+                i += 2;
+
+                TokenKind::Newline
+            }
+            b' ' | b'\t' => {
+                i = skip_while(bytes, i, |b| b == b' ' || b == b'\t');
+
+                TokenKind::Whitespace
+            }
+            b'#' => {
+                i = skip_while(bytes, i, |b| b != b'\n');
+
+                // The `\r` of a `\r\n` belongs to the newline
+                if i > start + 1 && bytes[i - 1] == b'\r' {
+                    i -= 1;
+                }
+
+                if bytes.get(start + 1) == Some(&b'#') {
+                    TokenKind::DocComment
+                } else {
+                    TokenKind::LineComment
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = match source[i + 2..].find("*/") {
+                    Some(idx) => i + 2 + idx + 2,
+                    None => bytes.len(),
+                };
+
+                TokenKind::BlockComment
+            }
+            b'"' => {
+                i = quoted_end(bytes, i, b'"');
+
+                TokenKind::String
+            }
+            b'\'' if is_char_literal(source, i) => {
+                i = quoted_end(bytes, i, b'\'');
+
+                TokenKind::Char
+            }
+            _ => {
+                i += 1;
+
+                TokenKind::Code
+            }
+        };
+
+        // The consecutive bytes of code are merged in a single token
+        match tokens.last_mut() {
+            Some(last) if kind == TokenKind::Code && last.kind == TokenKind::Code => {
+                last.end = i;
+            }
+            _ => tokens.push(Token {
+                kind,
+                start,
+                end: i,
+            }),
+        }
+    }
+
+    tokens
+}
+
+// The content given to the parser: the comments are replaced by spaces, except their
+// newlines, so that every offset and every line stays the same as in the source
+pub fn strip_comments(source: &str) -> String {
+    let mut bytes = source.as_bytes().to_vec();
+
+    for token in tokenize(source).iter().filter(|token| token.is_comment()) {
+        for byte in &mut bytes[token.start..token.end] {
+            if *byte != b'\n' && *byte != b'\r' {
+                *byte = b' ';
+            }
+        }
+    }
+
+    // Whole characters are replaced, so it is still valid utf8
+    String::from_utf8(bytes).unwrap()
+}
+
+fn skip_while(bytes: &[u8], mut i: usize, pred: impl Fn(u8) -> bool) -> usize {
+    while i < bytes.len() && pred(bytes[i]) {
+        i += 1;
+    }
+
+    i
+}
+
+// The end of a literal that starts with `quote` at `start`, after its closing quote.
+// An unterminated literal stops at the end of the line, the parser reports it.
+fn quoted_end(bytes: &[u8], start: usize, quote: u8) -> usize {
+    let mut i = start + 1;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if i + 1 < bytes.len() && bytes[i + 1] != b'\n' => i += 2,
+            b'\n' => return i,
+            b if b == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+
+    i
+}
+
+// A quote only starts a char literal like `'a'` or `'\n'` when it is closed right after
+fn is_char_literal(source: &str, start: usize) -> bool {
+    let mut chars = source[start + 1..].chars();
+
+    match chars.next() {
+        Some('\\') => chars.nth(1) == Some('\''),
+        Some('\'') | Some('\n') | None => false,
+        Some(_) => chars.next() == Some('\''),
+    }
+}
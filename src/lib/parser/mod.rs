@@ -1,16 +1,20 @@
 use std::{
-    collections::{BTreeMap, HashMap},
-    path::PathBuf,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
 };
 
 use nom::{
     branch::alt,
     bytes::complete::{escaped_transform, tag, take_while},
     character::complete::{
-        alphanumeric0, char, line_ending, none_of, one_of, satisfy, space0, space1,
+        alphanumeric0, alphanumeric1, char, none_of, not_line_ending, one_of, satisfy, space0,
+        space1,
     },
-    combinator::{cond, eof, map, opt, peek, recognize, value},
-    error::{make_error, ErrorKind, FromExternalError, ParseError, VerboseError},
+    combinator::{cond, consumed, cut, eof, map, opt, peek, recognize, value, verify},
+    error::{context, make_error, ErrorKind, ParseError, VerboseError, VerboseErrorKind},
     error_position,
     multi::{many0, many1, separated_list0, separated_list1},
     sequence::{delimited, preceded, terminated, tuple},
@@ -26,7 +30,8 @@ use crate::{
         tree::{self, *},
         NodeId,
     },
-    diagnostics::{Diagnostic, Diagnostics},
+    cache::{self, id_base, ParsedModule},
+    diagnostics::{Diagnostic, Diagnostics, LintLevel, LintLevels},
     ty::{FuncType, PrimitiveType, StructType, Type},
     Config,
 };
@@ -36,13 +41,17 @@ pub type Parser<'a> = LocatedSpan<&'a str, ParserCtx>;
 type Res<T, U> = IResult<T, U, VerboseError<T>>;
 
 pub mod default_impl_populator;
+pub mod doc_comments;
+pub mod lexer;
 pub mod parsing_context;
 pub mod source_file;
 pub mod span;
+pub mod test_harness;
 
 pub use parsing_context::ParsingCtx;
 pub use source_file::SourceFile;
 pub use span::Span;
+use test_harness::TEST_PREFIX;
 
 #[cfg(test)]
 mod tests;
@@ -63,10 +72,18 @@ lazy_static! {
             "/std/src/lib.rk".into(),
             include_str!("../../../std/src/lib.rk"),
         );
+        m.insert(
+            "/std/src/assert.rk".into(),
+            include_str!("../../../std/src/assert.rk"),
+        );
         m.insert(
             "/std/src/clone.rk".into(),
             include_str!("../../../std/src/clone.rk"),
         );
+        m.insert(
+            "/std/src/env.rk".into(),
+            include_str!("../../../std/src/env.rk"),
+        );
         m.insert(
             "/std/src/eq.rk".into(),
             include_str!("../../../std/src/eq.rk"),
@@ -111,12 +128,17 @@ lazy_static! {
     };
 }
 
+pub fn is_stdlib_file(path: &str) -> bool {
+    STDLIB_FILES.contains_key(path)
+}
+
 #[derive(Debug, Clone)]
 pub struct ParserCtx {
     files: HashMap<PathBuf, SourceFile>,
     diagnostics: Diagnostics,
     cur_file_path: PathBuf,
     identities: BTreeMap<NodeId, Span>,
+    lint_scopes: Vec<(Span, LintLevels)>,
     operators_list: HashMap<String, u8>,
     block_indent: usize,
     first_indent: Option<usize>,
@@ -124,6 +146,12 @@ pub struct ParserCtx {
     structs: HashMap<String, Type>,
     pub config: Config,
     allow_newline_dot: Vec<()>,
+    load_mods: bool,
+    mod_path: String,
+    id_bases: BTreeMap<NodeId, String>, // The modules that number their nodes from each base
+    module_cache: Arc<BTreeMap<String, ParsedModule>>, // The modules of the previous build
+    parsed_mods: Arc<BTreeMap<String, ParsedModule>>, // Shared, as the parser clones its context a lot
+    reused_mods: BTreeSet<String>,
 }
 
 impl ParserCtx {
@@ -132,6 +160,7 @@ impl ParserCtx {
             files: HashMap::new(),
             cur_file_path: file_path,
             identities: BTreeMap::new(),
+            lint_scopes: vec![],
             operators_list: HashMap::new(),
             block_indent: 0,
             first_indent: None,
@@ -140,6 +169,12 @@ impl ParserCtx {
             diagnostics: Diagnostics::default(),
             config,
             allow_newline_dot: vec![],
+            load_mods: true,
+            mod_path: "root".to_string(),
+            id_bases: BTreeMap::from([(0, "root".to_string())]),
+            module_cache: Arc::default(),
+            parsed_mods: Arc::default(),
+            reused_mods: BTreeSet::new(),
         }
     }
 
@@ -153,6 +188,7 @@ impl ParserCtx {
             files: HashMap::new(),
             cur_file_path: file_path,
             identities: BTreeMap::new(),
+            lint_scopes: vec![],
             operators_list: operators,
             block_indent: 0,
             first_indent: None,
@@ -161,42 +197,57 @@ impl ParserCtx {
             diagnostics: Diagnostics::default(),
             config,
             allow_newline_dot: vec![],
+            load_mods: true,
+            mod_path: "root".to_string(),
+            id_bases: BTreeMap::from([(0, "root".to_string())]),
+            module_cache: Arc::default(),
+            parsed_mods: Arc::default(),
+            reused_mods: BTreeSet::new(),
         }
     }
 
     pub fn new_from(&self, name: &str, config: Config) -> Self {
-        Self {
-            files: HashMap::new(),
-            cur_file_path: self
-                .cur_file_path
+        self.new_submodule(
+            name,
+            self.cur_file_path
                 .parent()
                 .unwrap()
                 .join(name.to_owned() + ".rk"),
-            identities: BTreeMap::new(),
-            operators_list: HashMap::new(),
-            block_indent: 0,
-            first_indent: None,
-            next_node_id: self.next_node_id,
-            structs: HashMap::new(),
-            diagnostics: Diagnostics::default(), // FIXME
             config,
-            allow_newline_dot: vec![],
-        }
+        )
     }
 
     pub fn new_std(&self, config: Config) -> Self {
+        self.new_submodule("std", PathBuf::from("/std/src/lib.rk"), config)
+    }
+
+    fn new_submodule(&self, name: &str, file_path: PathBuf, config: Config) -> Self {
+        let mod_path = format!("{}::{}", self.mod_path, name);
+        let base = id_base(&mod_path, &self.id_bases);
+
+        let mut id_bases = self.id_bases.clone();
+
+        id_bases.insert(base, mod_path.clone());
+
         Self {
             files: HashMap::new(),
-            cur_file_path: PathBuf::from("/std/src/lib.rk"),
+            cur_file_path: file_path,
             identities: BTreeMap::new(),
+            lint_scopes: vec![],
             operators_list: HashMap::new(),
             block_indent: 0,
             first_indent: None,
-            next_node_id: self.next_node_id,
+            next_node_id: base,
             structs: HashMap::new(),
-            diagnostics: Diagnostics::default(),
+            diagnostics: Diagnostics::default(), // FIXME
             config,
             allow_newline_dot: vec![],
+            load_mods: true,
+            mod_path,
+            id_bases,
+            module_cache: self.module_cache.clone(),
+            parsed_mods: Arc::default(),
+            reused_mods: BTreeSet::new(),
         }
     }
 
@@ -226,6 +277,10 @@ impl ParserCtx {
         self.identities.clone()
     }
 
+    pub fn lint_scopes(&self) -> Vec<(Span, LintLevels)> {
+        self.lint_scopes.clone()
+    }
+
     pub fn operators_list(&self) -> HashMap<String, u8> {
         self.operators_list.clone()
     }
@@ -237,6 +292,114 @@ impl ParserCtx {
     pub fn diagnostics(&self) -> Diagnostics {
         self.diagnostics.clone()
     }
+
+    pub fn parsed_mods(&self) -> BTreeMap<String, ParsedModule> {
+        (*self.parsed_mods).clone()
+    }
+
+    pub fn reused_mods(&self) -> BTreeSet<String> {
+        self.reused_mods.clone()
+    }
+
+    // The files of the submodules of the module of the previous build, when neither
+    // its file nor theirs have changed since
+    fn cached_module_files(&self, file: &SourceFile) -> Option<Vec<SourceFile>> {
+        let cached = self.module_cache.get(&self.mod_path)?;
+
+        if cached.source_hash != cache::hash(&file.content) || cached.id_base != self.next_node_id {
+            return None;
+        }
+
+        cached
+            .submodules
+            .iter()
+            .map(|name| {
+                let submodule = self.module_cache.get(name)?;
+
+                if self.id_bases.contains_key(&submodule.id_base) {
+                    return None;
+                }
+
+                let file = load_mod_file(submodule.file_path.to_str()?, &self.config).ok()?;
+
+                (cache::hash(&file.content) == submodule.source_hash).then_some(file)
+            })
+            .collect()
+    }
+
+    // Takes the module of `child` from the previous build instead of parsing it again
+    fn reuse_module(&mut self, child: &ParserCtx, files: Vec<SourceFile>) -> Mod {
+        let cached = &child.module_cache[&child.mod_path];
+
+        self.operators_list.extend(cached.operators.clone());
+        self.diagnostics.append(cached.diagnostics.clone());
+        self.identities.extend(cached.identities.clone());
+        self.lint_scopes.extend(cached.lint_scopes.clone());
+
+        for file in files {
+            self.files.insert(file.file_path.clone(), file);
+        }
+
+        let parsed_mods = Arc::make_mut(&mut self.parsed_mods);
+
+        for name in std::iter::once(&child.mod_path).chain(&cached.submodules) {
+            let module = child.module_cache[name].clone();
+
+            self.id_bases.insert(module.id_base, name.clone());
+            self.reused_mods.insert(name.clone());
+
+            parsed_mods.insert(name.clone(), module);
+        }
+
+        cached.ast.clone()
+    }
+
+    // Keeps what the parsing of the module of `child` gave, for the next builds
+    fn add_parsed_module(&mut self, child: &ParserCtx, file: &SourceFile, mod_: &Mod) {
+        let module = ParsedModule {
+            file_path: file.file_path.clone(),
+            source_hash: cache::hash(&file.content),
+            id_base: *child
+                .id_bases
+                .iter()
+                .find(|(_, name)| **name == child.mod_path)
+                .unwrap()
+                .0,
+            submodules: child.parsed_mods.keys().cloned().collect(),
+            ast: mod_.clone(),
+            identities: child.identities.clone(),
+            lint_scopes: child.lint_scopes.clone(),
+            operators: child.operators_list.clone(),
+            diagnostics: child.diagnostics.clone(),
+        };
+
+        let parsed_mods = Arc::make_mut(&mut self.parsed_mods);
+
+        parsed_mods.extend(child.parsed_mods());
+        parsed_mods.insert(child.mod_path.clone(), module);
+
+        self.reused_mods.extend(child.reused_mods.clone());
+        self.id_bases.extend(child.id_bases.clone());
+    }
+}
+
+// Loads the file of a module, the ones of the user import the std prelude
+pub fn load_mod_file(file_path: &str, config: &Config) -> Result<SourceFile, Diagnostic> {
+    let mut file = match config.overlays.get(Path::new(file_path)) {
+        Some(content) => SourceFile::from_str(file_path, content),
+        None => SourceFile::from_file(file_path.to_string()),
+    }?;
+
+    if config.std && !is_stdlib_file(file_path) {
+        file.prepend_header("use root::std::prelude::(*)\n");
+    }
+
+    Ok(file)
+}
+
+// The trailing whitespaces, like the ones left by the blanked comments, are skipped
+fn line_ending(input: Parser) -> Res<Parser, Parser> {
+    preceded(space0, nom::character::complete::line_ending)(input)
 }
 
 pub fn parse_root(input: Parser) -> Res<Parser, Root> {
@@ -245,12 +408,196 @@ pub fn parse_root(input: Parser) -> Res<Parser, Root> {
 }
 
 pub fn parse_mod(input: Parser) -> Res<Parser, Mod> {
-    map(
-        terminated(many1(terminated(parse_top_level, many0(line_ending))), eof),
-        Mod::new,
+    let (mut input, _) = many0(line_ending)(input)?;
+
+    let mut top_levels = vec![];
+
+    while eof::<_, VerboseError<Parser>>(input.clone()).is_err() {
+        let item_start = input.clone();
+
+        let (rest, annotations) =
+            many0(terminated(parse_lint_annotation, many1(line_ending)))(input)?;
+
+        input = rest;
+
+        match parse_top_level(input.clone()) {
+            Ok((rest, top_level)) => {
+                push_top_level(&mut top_levels, top_level);
+
+                input = rest;
+
+                if !annotations.is_empty() {
+                    add_lint_scope(&mut input, item_start, annotations);
+                }
+            }
+            Err(Err::Error(err)) | Err(Err::Failure(err)) => {
+                push_syntax_error(&mut input, err);
+
+                // resync on the next unindented line
+                input = skip_to_indent(0)(input)?.0;
+            }
+            Err(err) => return Err(err),
+        }
+
+        input = many0(line_ending)(input)?.0;
+    }
+
+    if top_levels.is_empty() && !input.extra.diagnostics.must_stop {
+        return Err(Err::Error(ParseError::from_error_kind(
+            input,
+            ErrorKind::Many1,
+        )));
+    }
+
+    Ok((input, Mod::new(top_levels)))
+}
+
+// The clauses of a function follow each other, a clause or a function with the same name as
+// the previous one is merged with it when one of them matches a literal or has a guard.
+// Two plain functions stay apart, and the resolver reports the second one.
+fn push_top_level(top_levels: &mut Vec<TopLevel>, top_level: TopLevel) {
+    let clause = match top_level {
+        TopLevel::Clauses(mut clauses) => clauses.clauses.remove(0),
+        TopLevel::Function(f) => match top_levels.last() {
+            Some(TopLevel::Clauses(prev)) if prev.name == f.name => Clause::from(f),
+            _ => return top_levels.push(TopLevel::Function(f)),
+        },
+        top_level => return top_levels.push(top_level),
+    };
+
+    match top_levels.last_mut() {
+        Some(TopLevel::Clauses(prev)) if prev.name == clause.name => prev.push(clause),
+        Some(TopLevel::Function(prev)) if prev.name == clause.name => {
+            let mut clauses = FunctionClauses::new(vec![Clause::from(prev.clone())]);
+
+            clauses.push(clause);
+
+            *top_levels.last_mut().unwrap() = TopLevel::Clauses(clauses);
+        }
+        _ => top_levels.push(TopLevel::new_clause(clause)),
+    }
+}
+
+// `@[allow(unused-function, ...)]`, before a top level item
+pub fn parse_lint_annotation(input: Parser) -> Res<Parser, (LintLevel, Vec<Parser>)> {
+    delimited(
+        tag("@["),
+        tuple((
+            alt((
+                value(LintLevel::Allow, tag("allow")),
+                value(LintLevel::Warn, tag("warn")),
+                value(LintLevel::Deny, tag("deny")),
+            )),
+            delimited(
+                terminated(tag("("), space0),
+                separated_list1(
+                    tuple((space0, tag(","), space0)),
+                    recognize(many1(satisfy(|c: char| c.is_ascii_lowercase() || c == '-'))),
+                ),
+                preceded(space0, tag(")")),
+            ),
+        )),
+        tag("]"),
     )(input)
 }
 
+// The levels of the annotations apply to every diagnostic within the item
+fn add_lint_scope<'a>(
+    input: &mut Parser<'a>,
+    item_start: Parser<'a>,
+    annotations: Vec<(LintLevel, Vec<Parser<'a>>)>,
+) {
+    let mut levels = LintLevels::default();
+
+    for (level, names) in annotations {
+        for name in names {
+            if levels.set(name.fragment(), level).is_err() {
+                input
+                    .extra
+                    .diagnostics
+                    .push_warning(Diagnostic::new_unknown_lint(
+                        Span::from(name.clone()),
+                        name.fragment().to_string(),
+                    ));
+            }
+        }
+    }
+
+    let span = Span::new(
+        input.extra.current_file_path().clone(),
+        item_start.location_offset(),
+        input.location_offset(),
+    );
+
+    input.extra.lint_scopes.push((span, levels));
+}
+
+// Records a syntax error at the deepest failing position, keeping the errors
+// already recovered in the failing branch
+fn push_syntax_error<'a>(input: &mut Parser<'a>, err: VerboseError<Parser<'a>>) {
+    let message = syntax_error_message(&err);
+    let (err_input, _kind) = err.errors.into_iter().next().unwrap();
+
+    // The failing parser may have reported a more precise error itself
+    let already_reported =
+        err_input.extra.diagnostics.list.len() > input.extra.diagnostics.list.len();
+
+    input.extra.files.extend(err_input.extra.files.clone());
+    input.extra.diagnostics = err_input.extra.diagnostics.clone();
+
+    if already_reported {
+        return;
+    }
+    input
+        .extra
+        .diagnostics
+        .push_error(syntax_error_until_eol(&err_input, message));
+}
+
+// The context given by the failing parser if any, or else what the deepest one expected
+fn syntax_error_message(err: &VerboseError<Parser>) -> String {
+    let context = err.errors.iter().find_map(|(_, kind)| match kind {
+        VerboseErrorKind::Context(ctx) => Some(ctx.to_string()),
+        _ => None,
+    });
+
+    context.unwrap_or_else(|| match &err.errors[0].1 {
+        VerboseErrorKind::Char(c) => format!("Expected `{}`", c),
+        VerboseErrorKind::Nom(kind) => format!("Unexpected input ({})", kind.description()),
+        VerboseErrorKind::Context(ctx) => ctx.to_string(),
+    })
+}
+
+// The failing input spans until the end of file, only keep its first line
+fn syntax_error_until_eol(input: &Parser, message: String) -> Diagnostic {
+    let mut span = Span::from(input.clone());
+
+    span.end = span.start
+        + input
+            .fragment()
+            .find('\n')
+            .unwrap_or(input.fragment().len());
+
+    Diagnostic::new_syntax_error(span, message)
+}
+
+// Skips the rest of the line and every following line indented deeper than `indent`
+fn skip_to_indent<'a>(indent: usize) -> impl FnMut(Parser<'a>) -> Res<Parser<'a>, ()> {
+    move |input| {
+        value(
+            (),
+            tuple((
+                not_line_ending,
+                many0(tuple((
+                    many1(line_ending),
+                    verify(space0, |spaces: &Parser| spaces.fragment().len() > indent),
+                    not_line_ending,
+                ))),
+            )),
+        )(input)
+    }
+}
+
 pub fn parse_top_level(input: Parser) -> Res<Parser, TopLevel> {
     alt((
         preceded(
@@ -262,17 +609,40 @@ pub fn parse_top_level(input: Parser) -> Res<Parser, TopLevel> {
         map(parse_struct_decl, TopLevel::new_struct),
         map(parse_trait, TopLevel::new_trait),
         map(parse_impl, TopLevel::new_impl),
+        map(parse_test, TopLevel::new_test),
+        map(parse_const, TopLevel::new_const),
+        map(parse_type_alias, TopLevel::new_type_alias),
+        map(parse_newtype, TopLevel::new_newtype),
+        map(parse_clause, TopLevel::new_clause),
         map(parse_fn, TopLevel::new_function),
         map(parse_prototype, TopLevel::new_fn_signature),
         map(parse_mod_decl, |(name, mod_)| TopLevel::new_mod(name, mod_)),
     ))(input)
 }
 
+// The modules that can be declared next to `file_path`, except the declaring one
+fn sibling_mods(file_path: &Path, declaring_file_path: &Path) -> Vec<String> {
+    let dir = match file_path.parent().and_then(|dir| fs::read_dir(dir).ok()) {
+        Some(dir) => dir,
+        None => return vec![],
+    };
+
+    dir.filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension() == Some(OsStr::new("rk")))
+        .filter(|path| path.file_name() != declaring_file_path.file_name())
+        .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+        .collect()
+}
+
 pub fn parse_mod_decl(input: Parser) -> Res<Parser, (Identifier, Mod)> {
     let config = input.extra.config.clone();
 
     let (mut input, mod_name) = preceded(terminated(tag("mod"), space1), parse_identifier)(input)?;
 
+    if !input.extra.load_mods {
+        return Ok((input, (mod_name, Mod::new(vec![]))));
+    }
+
     let mut new_ctx = if mod_name.name == "std" {
         input.extra.new_std(config.clone())
     } else {
@@ -281,13 +651,27 @@ pub fn parse_mod_decl(input: Parser) -> Res<Parser, (Identifier, Mod)> {
 
     let file_path = new_ctx.current_file_path().to_str().unwrap().to_string();
 
-    let mut file = SourceFile::from_file(file_path.clone()).unwrap(); // FIXME: ERRORS ARE swallowed HERE
+    let file = match load_mod_file(&file_path, &config) {
+        Ok(file) => file,
+        Err(_) => {
+            let span = input
+                .extra
+                .identities
+                .get(&mod_name.node_id)
+                .unwrap()
+                .clone();
+
+            let siblings =
+                sibling_mods(new_ctx.current_file_path(), input.extra.current_file_path());
 
-    if config.std {
-        if STDLIB_FILES.get(&file_path).is_none() {
-            file.content = "use root::std::prelude::(*)\n".to_owned() + &file.content;
+            input.extra.diagnostics.push_error(
+                Diagnostic::new_module_not_found(span, file_path)
+                    .with_best_match(&mod_name.name, &siblings),
+            );
+
+            return Ok((input, (mod_name, Mod::new(vec![]))));
         }
-    }
+    };
 
     new_ctx
         .files
@@ -298,6 +682,12 @@ pub fn parse_mod_decl(input: Parser) -> Res<Parser, (Identifier, Mod)> {
         .files
         .insert(new_ctx.current_file_path().clone(), file.clone());
 
+    if let Some(files) = new_ctx.cached_module_files(&file) {
+        let mod_ = input.extra.reuse_module(&new_ctx, files);
+
+        return Ok((input, (mod_name, mod_)));
+    }
+
     let new_parser = Parser::new_extra(&file.content, new_ctx.clone());
 
     use nom::Finish;
@@ -307,24 +697,20 @@ pub fn parse_mod_decl(input: Parser) -> Res<Parser, (Identifier, Mod)> {
     let (input2, mod_) = match parsed_mod_opt {
         Ok((input2, mod_)) => (input2, mod_),
         Err(err) => {
-            input
-                .extra
-                .diagnostics
-                .append(Diagnostics::from(err.clone()));
-
             input
                 .extra
                 .files
                 .extend(err.errors.get(0).unwrap().0.extra.files.clone());
 
-            return Err(nom::Err::Failure(VerboseError::from_external_error(
-                input,
-                ErrorKind::Fail,
-                err.to_owned(),
-            )));
+            input.extra.diagnostics.append(Diagnostics::from(err));
+
+            // keep parsing the parent module, the errors are reported before the resolution
+            return Ok((input, (mod_name, Mod::new(vec![]))));
         }
     };
 
+    input.extra.add_parsed_module(&input2.extra, &file, &mod_);
+
     // hydrate `input` with the new parser's operators
     // TODO: handle duplicate operators
     input
@@ -334,9 +720,9 @@ pub fn parse_mod_decl(input: Parser) -> Res<Parser, (Identifier, Mod)> {
 
     input.extra.diagnostics.append(input2.extra.diagnostics);
 
-    // extend identities
-    input.extra.next_node_id = input2.extra.next_node_id;
+    // extend identities, the module numbers its nodes from its own base
     input.extra.identities.extend(input2.extra.identities);
+    input.extra.lint_scopes.extend(input2.extra.lint_scopes);
     input.extra.files.extend(input2.extra.files);
 
     Ok((input, (mod_name, mod_)))
@@ -362,10 +748,13 @@ fn partition_defs_or_fns(input: Vec<ProtoOrFn>) -> (Vec<Prototype>, Vec<Function
 }
 
 pub fn parse_trait(input: Parser) -> Res<Parser, Trait> {
+    let (input, (parsed_name, name)) =
+        preceded(terminated(tag("trait"), space1), consumed(parse_type))(input)?;
+
+    let (input, node_id) = new_identity(input, &parsed_name);
+
     map(
         tuple((
-            terminated(tag("trait"), space1),
-            parse_type,
             many0(line_ending),
             indent(separated_list1(
                 many1(line_ending),
@@ -382,27 +771,36 @@ pub fn parse_trait(input: Parser) -> Res<Parser, Trait> {
             )),
             many0(line_ending),
         )),
-        |(_, name, _, defs_or_fns, _)| {
+        move |(_, defs_or_fns, _)| {
             let (defs, fns) = partition_defs_or_fns(defs_or_fns);
 
-            Trait::new(name, vec![Type::ForAll("@".to_string())], defs, fns)
+            Trait::new(
+                name.clone(),
+                vec![Type::ForAll("@".to_string())],
+                defs,
+                fns,
+                node_id,
+            )
         },
     )(input)
 }
 
 pub fn parse_impl(input: Parser) -> Res<Parser, Impl> {
+    let (input, (parsed_name, name)) =
+        preceded(terminated(tag("impl"), space1), consumed(parse_type))(input)?;
+
+    let (input, node_id) = new_identity(input, &parsed_name);
+
     map(
         tuple((
-            terminated(tag("impl"), space1),
-            parse_type,
             many0(delimited(space1, parse_type, space0)),
-            line_ending,
+            many1(line_ending),
             indent(separated_list0(
                 many1(line_ending),
                 preceded(parse_block_indent, alt((parse_self_fn, parse_fn))),
             )),
         )),
-        |(_, name, types, _, defs)| Impl::new(name, types, defs),
+        move |(types, _, defs)| Impl::new(name.clone(), types, defs, node_id),
     )(input)
 }
 
@@ -553,6 +951,140 @@ pub fn parse_fn(input: Parser) -> Res<Parser, FunctionDecl> {
     )(input)
 }
 
+// `fact: 0 -> 1` or `sign: n | n < 0 -> -1`. The functions without arguments are parsed by
+// `parse_fn`
+pub fn parse_clause(input: Parser) -> Res<Parser, Clause> {
+    map(
+        tuple((
+            parse_identity,
+            terminated(
+                tuple((
+                    parse_identifier_or_operator,
+                    terminated(space0, tag(":")),
+                    space1,
+                    separated_list1(tuple((space0, tag(","), space0)), parse_pattern),
+                    opt(preceded(
+                        delimited(space0, tag("|"), space0),
+                        parse_expression,
+                    )),
+                )),
+                delimited(space0, tag("->"), space0),
+            ),
+            parse_body,
+        )),
+        |(node_id, (name, _, _, patterns, guard), body)| {
+            Clause::new(node_id, name, patterns, guard, body)
+        },
+    )(input)
+}
+
+pub fn parse_pattern(input: Parser) -> Res<Parser, Pattern> {
+    alt((
+        map(parse_literal_pattern, |(lit, op)| Pattern::Literal(lit, op)),
+        map(parse_identifier, Pattern::Identifier),
+    ))(input)
+}
+
+// The literal comes with the `==` operator that compares it with the argument
+fn parse_literal_pattern(input: Parser) -> Res<Parser, (Literal, Identifier)> {
+    let (input, (parsed, lit)) = consumed(alt((
+        parse_bool,
+        parse_float,
+        parse_number,
+        parse_string,
+        parse_char,
+    )))(input)?;
+
+    let (input, node_id) = new_identity(input, &parsed);
+
+    Ok((input, (lit, Identifier::new("==".to_string(), node_id))))
+}
+
+// The function of a test is named after its node id, the test harness calls it
+pub fn parse_test(input: Parser) -> Res<Parser, Test> {
+    map(
+        tuple((
+            parse_identity,
+            parse_identity,
+            preceded(terminated(tag("test"), space1), parse_string),
+            tuple((space0, tag(":"), space1, tag("->"), space0)),
+            parse_body,
+        )),
+        |(node_id, name_node_id, name, _, body)| {
+            let name = match name.kind {
+                LiteralKind::String(name) => name,
+                _ => unreachable!(),
+            };
+
+            Test::new(
+                name,
+                FunctionDecl {
+                    node_id,
+                    name: Identifier::new(format!("{}{}", TEST_PREFIX, name_node_id), name_node_id),
+                    body,
+                    signature: FuncType::from_args_nb(0),
+                    arguments: vec![],
+                },
+            )
+        },
+    )(input)
+}
+
+pub fn parse_const(input: Parser) -> Res<Parser, Const> {
+    map(
+        tuple((
+            parse_identity,
+            preceded(terminated(tag("const"), space1), parse_identifier),
+            // Past the name this can only be a constant
+            cut(tuple((
+                // The functions are already global, a constant holds a value
+                context(
+                    "Expected the type of the constant, like `const a: Int64 = 1`",
+                    preceded(
+                        delimited(space0, tag(":"), space0),
+                        verify(parse_type, |t: &Type| !t.is_func()),
+                    ),
+                ),
+                context(
+                    "Expected `=` and the value of the constant",
+                    preceded(delimited(space0, tag("="), space0), parse_expression),
+                ),
+            ))),
+        )),
+        |(node_id, name, (ty, value))| Const::new(node_id, name, ty, value),
+    )(input)
+}
+
+pub fn parse_type_alias(input: Parser) -> Res<Parser, TypeAlias> {
+    map(
+        tuple((
+            preceded(
+                terminated(tag("type"), space1),
+                parse_capitalized_identifier,
+            ),
+            preceded(delimited(space0, tag("="), space0), parse_type),
+        )),
+        |(name, ty)| TypeAlias::new(name, ty),
+    )(input)
+}
+
+pub fn parse_newtype(input: Parser) -> Res<Parser, Newtype> {
+    map(
+        tuple((
+            parse_identity,
+            preceded(
+                terminated(tag("newtype"), space1),
+                parse_capitalized_identifier,
+            ),
+            preceded(
+                delimited(space0, tag("="), space0),
+                verify(parse_type, |t: &Type| !t.is_func()),
+            ),
+        )),
+        |(node_id, name, ty)| Newtype::new(node_id, name, ty),
+    )(input)
+}
+
 fn indent<'a, O, E, F>(mut parser: F) -> impl FnMut(Parser<'a>) -> IResult<Parser<'a>, O, E>
 where
     F: nom::Parser<Parser<'a>, O, E>,
@@ -614,18 +1146,68 @@ pub fn parse_body(input: Parser) -> Res<Parser, Body> {
     let (input, opt_eol) = opt(many1(line_ending))(input)?; // NOTE: should not fail
 
     if opt_eol.is_some() {
-        indent(map(
-            separated_list1(
-                many1(line_ending),
-                preceded(parse_block_indent, parse_statement),
-            ),
-            Body::new,
-        ))(input)
+        indent(map(parse_statements, Body::new))(input)
     } else {
         map(parse_statement, |stmt| Body::new(vec![stmt]))(input)
     }
 }
 
+// Parses the statements of an indented block. A statement that fails to parse
+// is recorded as a syntax error and the parsing resumes on the next statement.
+pub fn parse_statements(input: Parser) -> Res<Parser, Vec<Statement>> {
+    let (mut input, block_indent) = parse_block_indent(input)?;
+
+    let mut stmts = vec![];
+
+    loop {
+        let end_of_statement = peek(preceded(space0, alt((line_ending, eof))));
+
+        match terminated(parse_statement, end_of_statement)(input.clone()) {
+            Ok((rest, stmt)) => {
+                stmts.push(stmt);
+
+                input = rest;
+            }
+            Err(Err::Error(err)) | Err(Err::Failure(err)) => {
+                push_syntax_error(&mut input, err);
+
+                input = skip_to_indent(block_indent)(input)?.0;
+            }
+            Err(err) => return Err(err),
+        }
+
+        // lines indented deeper than the block are errors, lesser ones end it
+        loop {
+            let next_line: Res<Parser, _> = many1(line_ending)(input.clone());
+
+            let (next_line, _) = match next_line {
+                Ok(res) => res,
+                Err(_) => return Ok((input, stmts)),
+            };
+
+            let (next_line, indent) = space0(next_line)?;
+            let indent_len = indent.fragment().len();
+
+            if indent_len < block_indent {
+                return Ok((input, stmts));
+            }
+
+            input = next_line;
+
+            if indent_len == block_indent {
+                break;
+            }
+
+            input.extra.diagnostics.push_error(syntax_error_until_eol(
+                &input,
+                "Unexpected indentation".to_string(),
+            ));
+
+            input = skip_to_indent(block_indent)(input)?.0;
+        }
+    }
+}
+
 pub fn parse_statement(input: Parser) -> Res<Parser, Statement> {
     alt((
         map(parse_if, Statement::new_if),
@@ -733,17 +1315,34 @@ pub fn parse_while(input: Parser) -> Res<Parser, While> {
 }
 
 pub fn parse_assign(input: Parser) -> Res<Parser, Assign> {
+    let (input, opt_let) = opt(terminated(tag("let"), space1))(input)?;
+
     map(
         tuple((
-            opt(terminated(tag("let"), space1)),
             terminated(parse_assign_left_side, space0),
+            // Only a new binding can be annotated
+            cond(
+                opt_let.is_some(),
+                opt(preceded(
+                    terminated(tag(":"), space0),
+                    terminated(parse_type_ascription, space0),
+                )),
+            ),
             terminated(tag("="), space0),
             terminated(parse_expression, space0),
         )),
-        |(opt_let, var, _, expr)| Assign::new(var, expr, opt_let.is_some()),
+        move |(var, ty, _, expr)| Assign::new(var, expr, opt_let.is_some(), ty.flatten()),
     )(input)
 }
 
+pub fn parse_type_ascription(input: Parser) -> Res<Parser, TypeAscription> {
+    let (input, (parsed, ty)) = consumed(parse_type)(input)?;
+
+    let (input, node_id) = new_identity(input, &parsed);
+
+    Ok((input, TypeAscription::new(node_id, ty)))
+}
+
 pub fn parse_assign_left_side(input: Parser) -> Res<Parser, AssignLeftSide> {
     let (input, expr) = parse_expression(input)?;
 
@@ -791,52 +1390,41 @@ pub fn parse_expression(input: Parser) -> Res<Parser, Expression> {
 pub fn parse_native_operator(
     input: Parser,
 ) -> Res<Parser, (NativeOperator, Identifier, Identifier)> {
-    map(
-        tuple((
-            preceded(
-                tag("~"),
-                alt((
-                    tag("IAdd"),
-                    tag("ISub"),
-                    tag("IMul"),
-                    tag("IDiv"),
-                    tag("FAdd"),
-                    tag("FSub"),
-                    tag("FMul"),
-                    tag("FDiv"),
-                    tag("IEq"),
-                    tag("Igt"),
-                    tag("Ige"),
-                    tag("Ilt"),
-                    tag("Ile"),
-                    tag("FEq"),
-                    tag("Fgt"),
-                    tag("Fge"),
-                    tag("Flt"),
-                    tag("Fle"),
-                    tag("BEq"),
-                    tag("Len"),
-                )),
-            ),
-            preceded(space1, parse_identifier),
-            preceded(space1, parse_identifier),
-        )),
-        |(tag, id1, id2)| {
-            let (_input, node_id) = new_identity(input.clone(), &tag);
-            (
-                NativeOperator::new(node_id, NativeOperatorKind::from_str(tag.fragment())),
-                id1,
-                id2,
-            )
-        },
-    )(input.clone())
+    let (mut rest, op) = preceded(tag("~"), alphanumeric1)(input.clone())?;
+
+    let kind = match NativeOperatorKind::from_str(op.fragment()) {
+        Some(kind) => kind,
+        None => {
+            rest.extra
+                .diagnostics
+                .push_error(Diagnostic::new_syntax_error(
+                    Span::from(op.clone()),
+                    format!("Unknown native operator ~{}", op.fragment()),
+                ));
+
+            return Err(Err::Failure(VerboseError::from_error_kind(
+                rest,
+                ErrorKind::Tag,
+            )));
+        }
+    };
+
+    let (rest, (id1, id2)) = tuple((
+        preceded(space1, parse_identifier),
+        preceded(space1, parse_identifier),
+    ))(rest)?;
+
+    let (_input, node_id) = new_identity(input, &op);
+
+    Ok((rest, (NativeOperator::new(node_id, kind), id1, id2)))
 }
 
 pub fn parse_struct_ctor_one_line(input: Parser) -> Res<Parser, StructCtor> {
     map(
         tuple((
             terminated(parse_capitalized_identifier, space1),
-            separated_list0(
+            // Without any field, this is a call like `UserId 1`
+            separated_list1(
                 terminated(tag(","), space0),
                 tuple((
                     terminated(parse_identifier, delimited(space0, tag(":"), space0)),
@@ -844,7 +1432,7 @@ pub fn parse_struct_ctor_one_line(input: Parser) -> Res<Parser, StructCtor> {
                 )),
             ),
         )),
-        |(name, decls)| StructCtor::new(name, decls.into_iter().collect()),
+        |(name, decls)| StructCtor::new(name, decls),
     )(input)
 }
 
@@ -859,14 +1447,14 @@ pub fn parse_struct_ctor(input: Parser) -> Res<Parser, StructCtor> {
                     parse_struct_ctor_decls,
                 ))),
             )),
-            |(_, (_, name, decls))| StructCtor::new(name, decls.into_iter().collect()),
+            |(_, (_, name, decls))| StructCtor::new(name, decls),
         ),
         map(
             tuple((
                 terminated(parse_capitalized_identifier, line_ending),
                 parse_struct_ctor_decls,
             )),
-            |(name, decls)| StructCtor::new(name, decls.into_iter().collect()),
+            |(name, decls)| StructCtor::new(name, decls),
         ),
     ))(input)
 }
@@ -999,7 +1587,16 @@ pub fn parse_operand(input: Parser) -> Res<Parser, Operand> {
         map(
             delimited(
                 terminated(tag("("), space0),
-                parse_expression,
+                alt((
+                    map(
+                        tuple((
+                            parse_expression,
+                            preceded(delimited(space0, tag(":"), space0), parse_type_ascription),
+                        )),
+                        |(expr, ty)| Expression::new_ascription(expr, ty),
+                    ),
+                    parse_expression,
+                )),
                 terminated(space0, tag(")")),
             ),
             Operand::new_expression,
@@ -1038,7 +1635,9 @@ pub fn parse_self_identifier(input: Parser) -> Res<Parser, Identifier> {
 }
 
 pub fn parse_capitalized_identifier(input: Parser) -> Res<Parser, Identifier> {
-    let (input, (node_id, txt)) = tuple((parse_identity, parse_capitalized_text))(input)?;
+    let (input, (parsed, txt)) = consumed(parse_capitalized_text)(input)?;
+
+    let (input, node_id) = new_identity(input, &parsed);
 
     Ok((
         input,
@@ -1077,7 +1676,8 @@ pub fn parse_operator(input: Parser) -> Res<Parser, Operator> {
         )))),
     ))(input)?;
 
-    if parsed_op.to_string() == "=" {
+    // `->` ends the guard of a clause
+    if parsed_op.to_string() == "=" || parsed_op.to_string() == "->" {
         return Err(Err::Error(error_position!(input, ErrorKind::Eof)));
     }
 
@@ -1110,23 +1710,30 @@ pub fn parse_string(input: Parser) -> Res<Parser, Literal> {
     let (mut input, string) = map(
         tuple((
             parse_identity,
-            terminated(tag("\""), space0),
+            tag("\""),
             recognize(many0(parse_escaped_char)),
             tag("\""),
         )),
         |(node_id, _, s, _)| {
+            let raw_len = s.fragment().len();
+
             // The unescape function does not accept `\\0` as escapable pattern, so we treat it
             // beforehand
             let s = s.replace("\\0", "\0");
 
-            Literal::new_string(unescape(&("\"".to_owned() + &s + "\"")).unwrap(), node_id)
+            (
+                Literal::new_string(unescape(&("\"".to_owned() + &s + "\"")).unwrap(), node_id),
+                raw_len,
+            )
         },
     )(input)?;
 
-    // Fix the span to have the correct length
+    let (string, raw_len) = string;
+
+    // Fix the span to cover the literal as written, with its escapes
     let span = input.extra.identities.get_mut(&string.node_id).unwrap();
 
-    span.end = span.start + string.as_str().len() + 2;
+    span.end = span.start + raw_len + 2;
 
     Ok((input, string))
 }
@@ -1360,14 +1967,44 @@ pub fn parse(parsing_ctx: &mut ParsingCtx) -> Result<tree::Root, Diagnostic> {
         parsing_ctx.get_current_file().clone(),
     );
 
+    parser.extra.module_cache = parsing_ctx.modules.previous_parsed.clone();
+
     let ast = parse_root(parser).finish();
 
     let ast = match ast {
-        Ok((ctx, mut ast)) => {
-            default_impl_populator::populate_default_impl(&mut ast);
+        Ok((mut ctx, mut ast)) => {
+            parsing_ctx.files.extend(ctx.extra.files());
+            parsing_ctx.diagnostics.append(ctx.extra.diagnostics());
+
+            parsing_ctx.modules.parsed = ctx.extra.parsed_mods();
+            parsing_ctx.modules.reused_parses = ctx.extra.reused_mods();
+
+            // every syntax error has been collected, stop before the resolution
+            parsing_ctx.return_if_error()?;
+
+            ast.docs = doc_comments::collect(&ast, &ctx.extra.identities, &parsing_ctx.files);
+
+            if parsing_ctx.config.test {
+                // The ones of the doc examples are the only new diagnostics
+                ctx.extra.diagnostics = Diagnostics::default();
+
+                ctx.extra = test_harness::add_harness(&mut ast, ctx.extra);
+
+                parsing_ctx.files.extend(ctx.extra.files());
+                parsing_ctx.diagnostics.append(ctx.extra.diagnostics());
+
+                parsing_ctx.return_if_error()?;
+            }
 
             parsing_ctx.identities = ctx.extra.identities();
-            parsing_ctx.files.extend(ctx.extra.files());
+
+            let diagnostics =
+                default_impl_populator::populate_default_impl(&mut ast, &parsing_ctx.identities);
+
+            parsing_ctx.diagnostics.append(diagnostics);
+
+            parsing_ctx.return_if_error()?;
+            parsing_ctx.lint_scopes = ctx.extra.lint_scopes();
 
             ast.operators_list = ctx.extra.operators_list();
             ast.spans = ctx.extra.identities().into_iter().collect();
@@ -1400,3 +2037,38 @@ pub fn parse(parsing_ctx: &mut ParsingCtx) -> Result<tree::Root, Diagnostic> {
 
     Ok(ast)
 }
+
+// Parses the current file alone, without loading its modules nor populating the default
+// methods of its traits, for the tools that only need its syntax like the formatter.
+// The errors are printed and returned.
+pub fn parse_file(parsing_ctx: &mut ParsingCtx) -> Result<tree::Root, Diagnostics> {
+    use nom::Finish;
+
+    let file = parsing_ctx.get_current_file();
+
+    let mut ctx = ParserCtx::new(file.file_path.clone(), parsing_ctx.config.clone());
+
+    ctx.load_mods = false;
+    ctx.files.insert(file.file_path.clone(), file.clone());
+
+    let ast = match parse_root(LocatedSpan::new_extra(file.content.as_str(), ctx)).finish() {
+        Ok((ctx, mut ast)) => {
+            parsing_ctx.diagnostics.append(ctx.extra.diagnostics());
+
+            ast.operators_list = ctx.extra.operators_list();
+            ast.spans = ctx.extra.identities().into_iter().collect();
+
+            Some(ast)
+        }
+        Err(e) => {
+            parsing_ctx.diagnostics.append(Diagnostics::from(e));
+
+            None
+        }
+    };
+
+    match (parsing_ctx.return_if_error(), ast) {
+        (Ok(()), Some(ast)) => Ok(ast),
+        _ => Err(parsing_ctx.diagnostics.clone()),
+    }
+}
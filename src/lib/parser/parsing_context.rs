@@ -7,7 +7,10 @@ use colored::*;
 
 use crate::{
     ast::{Identifier, NodeId},
-    diagnostics::{Diagnostic, DiagnosticType, Diagnostics},
+    cache::ModuleCache,
+    config::MessageFormat,
+    diagnostics::{Diagnostic, DiagnosticType, Diagnostics, Lint, LintLevel, LintLevels},
+    helpers::timings::Timings,
     parser::span::Span,
     Config,
 };
@@ -22,6 +25,9 @@ pub struct ParsingCtx {
     pub diagnostics: Diagnostics,
     pub operators_list: HashMap<String, u8>,
     pub identities: BTreeMap<NodeId, Span>,
+    pub lint_scopes: Vec<(Span, LintLevels)>, // The items annotated with lint levels
+    pub timings: Timings,
+    pub modules: ModuleCache,
 }
 
 impl ParsingCtx {
@@ -45,12 +51,35 @@ impl ParsingCtx {
             .clone()
     }
 
+    // Reports the diagnostic of a lint at the level chosen for its span
+    pub fn push_lint(&mut self, lint: Lint, diag: Diagnostic) {
+        let mut levels = self.config.lints.clone();
+
+        for (span, item_levels) in &self.lint_scopes {
+            if span.file_path == diag.span.file_path
+                && span.start <= diag.span.start
+                && diag.span.end <= span.end
+            {
+                levels.extend(item_levels);
+            }
+        }
+
+        match levels.level(lint) {
+            LintLevel::Allow => (),
+            LintLevel::Warn => self.diagnostics.push_warning(diag),
+            LintLevel::Deny => self.diagnostics.push_error(diag),
+        }
+    }
+
     pub fn print_diagnostics(&self) {
         if self.config.quiet {
             return;
         }
 
-        self.diagnostics.print(&self.files);
+        match self.config.message_format {
+            MessageFormat::Human => self.diagnostics.print(&self.files),
+            MessageFormat::Json => self.diagnostics.print_json(&self.files),
+        }
     }
 
     pub fn print_success_diagnostics(&self) {
@@ -60,6 +89,10 @@ impl ParsingCtx {
 
         self.print_diagnostics();
 
+        if self.config.message_format == MessageFormat::Json {
+            return;
+        }
+
         if !self.diagnostics.list.is_empty() {
             let diag_type_str = format!(
                 "{}{}{}",
@@ -116,7 +149,7 @@ impl ParsingCtx {
                 "]".bright_black(),
             );
 
-            if !self.config.quiet {
+            if !self.config.quiet && self.config.message_format == MessageFormat::Human {
                 println!(
                     "{} {} {} {} {} {} {} {} {}",
                     diag_type_str,
@@ -3,17 +3,17 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use regex::Regex;
-
 use crate::diagnostics::Diagnostic;
 
-use super::span::Span;
+use super::{lexer, span::Span};
 
 #[derive(Default, Debug, Clone)]
 pub struct SourceFile {
     pub file_path: PathBuf,
     pub mod_path: PathBuf,
-    pub content: String,
+    pub content: String, // What the parser reads, with the same offsets as the source
+    pub source: String,
+    pub header_len: usize,
 }
 
 impl SourceFile {
@@ -26,10 +26,6 @@ impl SourceFile {
             })?
         };
 
-        let content = Self::sanitize_content(&content);
-
-        let content = content.replace("^[ \t]*\n", "\n");
-
         let mut mod_path = PathBuf::from(in_name.clone());
 
         mod_path.set_extension("");
@@ -37,7 +33,9 @@ impl SourceFile {
         Ok(SourceFile {
             file_path: PathBuf::from(in_name),
             mod_path,
-            content,
+            content: Self::sanitize_content(&content),
+            source: content,
+            header_len: 0,
         })
     }
 
@@ -46,12 +44,12 @@ impl SourceFile {
 
         mod_path.set_extension("");
 
-        let content = Self::sanitize_content(&content);
-
         Ok(SourceFile {
             file_path: PathBuf::from(path.clone()),
             mod_path,
-            content,
+            content: Self::sanitize_content(content),
+            source: content.to_string(),
+            header_len: 0,
         })
     }
 
@@ -82,12 +80,12 @@ custom =
             .to_owned()
             + &expr;
 
-        let content = Self::sanitize_content(&top_levels);
-
         Ok(SourceFile {
             file_path: PathBuf::from("./src/main.rk"),
             mod_path: PathBuf::from("root"),
-            content,
+            content: Self::sanitize_content(&top_levels),
+            source: top_levels,
+            header_len: 0,
         })
     }
 
@@ -103,26 +101,51 @@ custom =
             Err(_) => return Err(mod_path.as_path().to_str().unwrap().to_string()),
         };
 
-        let content = Self::sanitize_content(&content);
-
         Ok(Self {
             file_path,
             mod_path,
-            content,
+            content: Self::sanitize_content(&content),
+            source: content,
+            header_len: 0,
         })
     }
 
-    // This function Remove all the comments
-    // Then replace the lines containing only whitespaces and tabs with a newline
-    // And append a \n at the end of file to avoid out of bounds error as the parser requires
-    // a newline at the end of the file
-    fn sanitize_content(content: &str) -> String {
-        let without_comments = Regex::new(r#"#.*\n"#).unwrap().replace_all(content, "\n");
+    // Prepends some code to the content, like the imports of the std prelude. The spans
+    // still point into the content, and are shifted back to the source by `header_len`.
+    pub fn prepend_header(&mut self, header: &str) {
+        self.content = header.to_owned() + &self.content;
+        self.header_len += header.len();
+    }
+
+    pub fn header_lines(&self) -> usize {
+        self.content[..self.header_len].matches('\n').count()
+    }
+
+    // The offset in the source of an offset in the content, `None` in the header
+    pub fn source_offset(&self, offset: usize) -> Option<usize> {
+        offset
+            .checked_sub(self.header_len)
+            .map(|offset| offset.min(self.source.len()))
+    }
+
+    // The line and column of an offset in the content, starting at 1. The header is
+    // reported on the first line.
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let offset = self.source_offset(offset).unwrap_or(0);
+
+        let line_start = self.source[..offset]
+            .rfind('\n')
+            .map(|i| i + 1)
+            .unwrap_or(0);
 
-        Regex::new(r"[ \t]+\n")
-            .unwrap()
-            .replace_all(&without_comments, "\n\n")
-            .to_string()
-            + "\n"
+        let line = self.source[..line_start].matches('\n').count() + 1;
+
+        (line, offset - line_start + 1)
+    }
+
+    // The comments are blanked by the lexer, without moving the rest of the source. A
+    // newline is appended as the parser requires one at the end of the file.
+    fn sanitize_content(content: &str) -> String {
+        lexer::strip_comments(content) + "\n"
     }
 }
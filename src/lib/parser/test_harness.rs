@@ -0,0 +1,241 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use nom::Finish;
+use nom_locate::LocatedSpan;
+
+use crate::ast::{
+    tree::{Mod, Root, Test, TopLevel},
+    NodeId,
+};
+
+use super::{doc_comments, is_stdlib_file, parse_mod, ParserCtx, SourceFile};
+
+// The functions of the tests are named with this prefix and their node id
+pub const TEST_PREFIX: &str = "__test_";
+
+// The main function of the project is renamed, as the harness replaces it
+pub const USER_MAIN: &str = "__main";
+
+pub const HARNESS_PATH: &str = "<test harness>";
+
+// Adds the main function of `rock test` to the root module. It runs the test whose index is
+// given in the `ROCK_TEST` environment variable, so that each test has its own process, or
+// lists every test with its location when it is not set: `<index> <file:line:col> <name>`.
+pub fn add_harness(root: &mut Root, ctx: ParserCtx) -> ParserCtx {
+    let ctx = add_doc_tests(&mut root.r#mod, &root.docs, &mut vec![], ctx);
+
+    let mut tests = vec![];
+
+    collect_tests(&root.r#mod, &mut vec![], &mut tests);
+
+    let source = if tests.is_empty() {
+        "main: -> 0\n".to_string()
+    } else {
+        let mut source = "main: ->\n  let test = getenv \"ROCK_TEST\"\n".to_string();
+
+        for (i, (path, _)) in tests.iter().enumerate() {
+            source += &run_if(&format!("\"{}\"", i), &[format!("{}!", path)]);
+        }
+
+        let listing = tests
+            .iter()
+            .enumerate()
+            .map(|(i, (_, test))| {
+                format!(
+                    "(\"{} {} {}\").print!",
+                    i,
+                    escape(&location(&ctx, test)),
+                    escape(&test.name)
+                )
+            })
+            .collect::<Vec<_>>();
+
+        source + &run_if("\"\"", &listing) + "  0\n"
+    };
+
+    rename_main(&mut root.r#mod);
+
+    parse_harness(root, ctx, &source)
+}
+
+// The code examples of the doc comments of the project are tests too. They are added
+// to the module of their item, so they see the same names, and are reported at its place.
+fn add_doc_tests(
+    r#mod: &mut Mod,
+    docs: &HashMap<NodeId, String>,
+    mods: &mut Vec<String>,
+    mut ctx: ParserCtx,
+) -> ParserCtx {
+    let mut doc_tests = vec![];
+
+    for (node_id, name) in doc_comments::items(r#mod) {
+        let (span, doc) = match (ctx.identities.get(&node_id), docs.get(&node_id)) {
+            (Some(span), Some(doc)) => (span.clone(), doc),
+            _ => continue,
+        };
+
+        if is_stdlib_file(span.file_path.to_str().unwrap_or_default()) {
+            continue;
+        }
+
+        let examples = doc_comments::examples(doc);
+
+        for (i, example) in examples.iter().enumerate() {
+            let mut name = mods
+                .iter()
+                .cloned()
+                .chain(std::iter::once(name.clone()))
+                .collect::<Vec<_>>()
+                .join("::");
+
+            name += &if examples.len() == 1 {
+                " (doc example)".to_string()
+            } else {
+                format!(" (doc example {})", i + 1)
+            };
+
+            let body = example
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| format!("  {}\n", line))
+                .collect::<String>();
+
+            let source = format!("test \"{}\": ->\n{}", escape(&name), body);
+
+            doc_tests.push((name, source, span.clone()));
+        }
+    }
+
+    for (name, source, span) in doc_tests {
+        let (new_ctx, parsed) = parse_source(ctx, &format!("<{}>", name), &source);
+
+        ctx = new_ctx;
+
+        for top_level in &parsed.top_levels {
+            if let TopLevel::Test(test) = top_level {
+                ctx.identities.insert(test.func.node_id, span.clone());
+            }
+        }
+
+        r#mod.top_levels.extend(parsed.top_levels);
+    }
+
+    for top_level in &mut r#mod.top_levels {
+        if let TopLevel::Mod(name, r#mod) = top_level {
+            mods.push(name.name.clone());
+
+            ctx = add_doc_tests(r#mod, docs, mods, ctx);
+
+            mods.pop();
+        }
+    }
+
+    ctx
+}
+
+// The ifs are not chained with `else if`, so each one returns its own value
+fn run_if(test: &str, stmts: &[String]) -> String {
+    let stmts = stmts
+        .iter()
+        .map(|stmt| format!("    {}\n", stmt))
+        .collect::<String>();
+
+    format!("  if test == {}\n  then\n{}    0\n  else 0\n", test, stmts)
+}
+
+// The path of the function of each test from the root module
+fn collect_tests<'a>(r#mod: &'a Mod, mods: &mut Vec<String>, tests: &mut Vec<(String, &'a Test)>) {
+    for top_level in &r#mod.top_levels {
+        match top_level {
+            TopLevel::Test(test) => {
+                let path = mods
+                    .iter()
+                    .cloned()
+                    .chain(std::iter::once(test.func.name.name.clone()))
+                    .collect::<Vec<_>>()
+                    .join("::");
+
+                tests.push((path, test));
+            }
+            TopLevel::Mod(name, r#mod) => {
+                mods.push(name.name.clone());
+
+                collect_tests(r#mod, mods, tests);
+
+                mods.pop();
+            }
+            _ => (),
+        }
+    }
+}
+
+fn rename_main(r#mod: &mut Mod) {
+    for top_level in &mut r#mod.top_levels {
+        match top_level {
+            TopLevel::Function(f) if f.name.name == "main" => {
+                f.name.name = USER_MAIN.to_string();
+            }
+            TopLevel::FnSignature(p) if p.name.name == "main" => {
+                p.name.name = USER_MAIN.to_string();
+            }
+            _ => (),
+        }
+    }
+}
+
+fn location(ctx: &ParserCtx, test: &Test) -> String {
+    let span = match ctx.identities.get(&test.func.node_id) {
+        Some(span) => span,
+        None => return String::new(),
+    };
+
+    let (line, col) = ctx
+        .files
+        .get(&span.file_path)
+        .map(|file| file.line_col(span.start))
+        .unwrap_or((1, 1));
+
+    format!("{}:{}:{}", span.file_path.display(), line, col)
+}
+
+// The names are printed on a single line
+fn escape(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '\\' => "\\\\".to_string(),
+            '"' => "\\\"".to_string(),
+            '\n' | '\r' | '\0' => " ".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+fn parse_harness(root: &mut Root, ctx: ParserCtx, source: &str) -> ParserCtx {
+    let (ctx, r#mod) = parse_source(ctx, HARNESS_PATH, source);
+
+    root.r#mod.top_levels.extend(r#mod.top_levels);
+
+    ctx
+}
+
+// The syntax errors are left in the diagnostics of the context
+fn parse_source(mut ctx: ParserCtx, path: &str, source: &str) -> (ParserCtx, Mod) {
+    let file = SourceFile::from_str(path, source).unwrap();
+
+    let previous_path = ctx.cur_file_path.clone();
+
+    ctx.cur_file_path = PathBuf::from(path);
+    ctx.files.insert(ctx.cur_file_path.clone(), file.clone());
+
+    let (rest, r#mod) = match parse_mod(LocatedSpan::new_extra(&file.content, ctx.clone())).finish()
+    {
+        Ok((rest, r#mod)) => (rest.extra, r#mod),
+        Err(_) => (ctx, Mod::new(vec![])),
+    };
+
+    let mut ctx = rest;
+
+    ctx.cur_file_path = previous_path;
+
+    (ctx, r#mod)
+}
@@ -1,6 +1,7 @@
 use nom::Finish;
 
 use super::*;
+use crate::diagnostics::Lint;
 
 #[cfg(test)]
 mod parse_literal {
@@ -320,6 +321,25 @@ mod parse_operand {
 
         assert!(matches!(parsed, Operand::Expression(_expr)));
     }
+
+    #[test]
+    fn valid_ascription() {
+        let input = Parser::new_extra(
+            "(a : Float64)",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (rest, parsed) = parse_operand(input).finish().unwrap();
+
+        assert!(rest.fragment().is_empty());
+        assert!(matches!(
+            parsed,
+            Operand::Expression(expr) if matches!(
+                &*expr,
+                Expression::Ascription(_, TypeAscription { ty: Type::Primitive(PrimitiveType::Float64), .. })
+            )
+        ));
+    }
 }
 
 #[cfg(test)]
@@ -648,6 +668,152 @@ mod parse_fn_decl {
     }
 }
 
+#[cfg(test)]
+mod parse_recovery {
+    use super::*;
+
+    #[test]
+    fn resync_on_next_top_level() {
+        let input = Parser::new_extra(
+            "foo: a -> a )\n  a\nbar: a -> a\nbaz : ( ->\nqux: a -> a",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (rest, parsed) = parse_mod(input).finish().unwrap();
+
+        assert_eq!(parsed.top_levels.len(), 3);
+        assert_eq!(rest.extra.diagnostics().list.len(), 2);
+    }
+
+    #[test]
+    fn resync_on_next_statement() {
+        let input = Parser::new_extra(
+            "toto: a ->\n  a )\n  a\n    a\n  ) a\n  a",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (rest, parsed) = parse_fn(input).finish().unwrap();
+
+        assert!(rest.fragment().is_empty());
+        assert_eq!(parsed.body.stmts.len(), 2);
+        assert_eq!(rest.extra.diagnostics().list.len(), 3);
+    }
+
+    #[test]
+    fn messages() {
+        let input = Parser::new_extra(
+            "const c = 3\nconst d: Int64 3\nfoo: a ->\n  a\n    a\nbar: a -> a",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (rest, parsed) = parse_mod(input).finish().unwrap();
+
+        let messages = rest
+            .extra
+            .diagnostics()
+            .list
+            .iter()
+            .map(|diag| (diag.span.start, diag.get_kind().message()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(parsed.top_levels.len(), 2);
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    8,
+                    "Syntax error: Expected the type of the constant, like `const a: Int64 = 1`"
+                        .to_string()
+                ),
+                (
+                    27,
+                    "Syntax error: Expected `=` and the value of the constant".to_string()
+                ),
+                (47, "Syntax error: Unexpected indentation".to_string()),
+            ]
+        );
+    }
+
+    // Both errors are reported when the parsing fails afterward, without changing their type
+    #[test]
+    fn two_independent_errors() {
+        let input = Parser::new_extra(
+            "foo: a -> a )\nbar: a -> a\nbaz : ( ->\n",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (mut rest, _parsed) = parse_mod(input.clone()).finish().unwrap();
+
+        rest.extra
+            .diagnostics
+            .push_warning(Diagnostic::new_unused_function(Span::from(input.clone())));
+
+        let err = VerboseError {
+            errors: vec![
+                (rest, VerboseErrorKind::Nom(ErrorKind::Eof)),
+                (input, VerboseErrorKind::Context("a module")),
+            ],
+        };
+
+        let diagnostics = Diagnostics::from(err);
+
+        let reported = diagnostics
+            .list
+            .iter()
+            .zip(&diagnostics.list_types)
+            .map(|(diag, diag_type)| (diag.span.start, diag_type.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            reported,
+            vec![(11, "error"), (26, "error"), (0, "warning"), (37, "error")]
+        );
+    }
+}
+
+#[cfg(test)]
+mod parse_lint_annotation {
+    use super::*;
+
+    #[test]
+    fn valid() {
+        let input = Parser::new_extra(
+            "@[allow(unused-function, warnings)]",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (rest, (level, names)) = parse_lint_annotation(input).finish().unwrap();
+
+        assert!(rest.fragment().is_empty());
+        assert_eq!(level, LintLevel::Allow);
+        assert_eq!(
+            names
+                .iter()
+                .map(|name| *name.fragment())
+                .collect::<Vec<_>>(),
+            vec!["unused-function", "warnings"]
+        );
+    }
+
+    #[test]
+    fn scope_of_the_item() {
+        let input = Parser::new_extra(
+            "@[deny(unused-function)]\nfoo: a -> a\nbar: a -> a",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (rest, parsed) = parse_mod(input).finish().unwrap();
+
+        assert_eq!(parsed.top_levels.len(), 2);
+
+        let scopes = rest.extra.lint_scopes();
+
+        assert_eq!(scopes.len(), 1);
+        assert_eq!((scopes[0].0.start, scopes[0].0.end), (0, 36));
+        assert_eq!(scopes[0].1.level(Lint::UnusedFunction), LintLevel::Deny);
+    }
+}
+
 #[cfg(test)]
 mod parse_prototype {
     use super::*;
@@ -850,6 +1016,32 @@ mod parse_assign {
 
         assert!(rest.fragment().is_empty());
     }
+
+    #[test]
+    fn valid_let_ascription() {
+        let input = Parser::new_extra(
+            "let a: [Int64] = []",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (rest, parsed) = parse_assign(input).finish().unwrap();
+
+        assert!(rest.fragment().is_empty());
+        assert_eq!(
+            parsed.ty.unwrap().ty,
+            Type::Primitive(PrimitiveType::Array(Box::new(Type::int64()), 0))
+        );
+    }
+
+    #[test]
+    fn reassign_ascription() {
+        let input = Parser::new_extra(
+            "a: Int64 = 2",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        assert!(parse_assign(input).finish().is_err());
+    }
 }
 
 #[cfg(test)]
@@ -987,4 +1179,451 @@ mod parse_string {
 
         assert!(rest.fragment().is_empty());
     }
+
+    #[test]
+    fn keeps_leading_spaces() {
+        let input = Parser::new_extra(
+            "\"  a\\nb\"",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (rest, parsed) = parse_string(input).finish().unwrap();
+
+        assert!(matches!(&parsed.kind, LiteralKind::String(s) if s == "  a\nb"));
+
+        let span = rest.extra.identities().get(&parsed.node_id).cloned();
+
+        assert_eq!(span.map(|span| span.end - span.start), Some(8));
+    }
+}
+
+#[cfg(test)]
+mod parse_test {
+    use super::*;
+
+    #[test]
+    fn valid_test() {
+        let input = Parser::new_extra(
+            "test \"adds numbers\": ->\n  1 + 1\n",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (_rest, parsed) = parse_test(input).finish().unwrap();
+
+        assert_eq!(parsed.name, "adds numbers");
+        assert!(parsed.func.name.name.starts_with(TEST_PREFIX));
+        assert!(parsed.func.arguments.is_empty());
+    }
+
+    #[test]
+    fn is_a_top_level() {
+        let input = Parser::new_extra(
+            "test \"a\": -> 1\ntest \"b\": -> 2\nmain: -> 0\n",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (_rest, parsed) = parse_root(input).finish().unwrap();
+
+        let tests = parsed
+            .r#mod
+            .top_levels
+            .iter()
+            .filter(|top_level| matches!(top_level, TopLevel::Test(_)))
+            .count();
+
+        assert_eq!(tests, 2);
+    }
+}
+
+#[cfg(test)]
+mod parse_const {
+    use super::*;
+
+    #[test]
+    fn valid_const() {
+        let input = Parser::new_extra(
+            "const max_len: Int64 = 4096\n",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (_rest, parsed) = parse_const(input).finish().unwrap();
+
+        assert_eq!(parsed.name.name, "max_len");
+        assert_eq!(parsed.ty, Type::int64());
+        assert!(parsed.value.is_literal());
+    }
+
+    #[test]
+    fn not_a_function() {
+        let input = Parser::new_extra(
+            "const f: (Int64 => Int64) = g\n",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        assert!(parse_const(input).finish().is_err());
+    }
+}
+
+#[cfg(test)]
+mod parse_type_alias {
+    use super::*;
+
+    // The resolver expands it, once every alias of the module is known
+    #[test]
+    fn kept_by_name() {
+        let input = Parser::new_extra(
+            "type Row = [Int64]\nRow => Int64",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (input, parsed) = parse_type_alias(input).finish().unwrap();
+
+        assert_eq!(parsed.name.name, "Row");
+        assert_eq!(
+            parsed.ty,
+            Type::Primitive(PrimitiveType::Array(Box::new(Type::int64()), 0))
+        );
+
+        let (_rest, parsed) = preceded(line_ending, parse_signature)(input)
+            .finish()
+            .unwrap();
+
+        assert_eq!(parsed.arguments[0], Type::Trait("Row".to_string()));
+    }
+
+    #[test]
+    fn newtype() {
+        let input = Parser::new_extra(
+            "newtype UserId = Int64\n",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (_input, parsed) = parse_newtype(input).finish().unwrap();
+
+        assert_eq!(parsed.name.name, "UserId");
+        assert_eq!(parsed.ty, Type::int64());
+        assert!(parsed.to_type().is_newtype());
+        assert_eq!(
+            parsed.to_type().try_as_newtype_inner(),
+            Some(&Type::int64())
+        );
+    }
+
+    #[test]
+    fn newtype_of_function() {
+        let input = Parser::new_extra(
+            "newtype Callback = (Int64 => Int64)\n",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        assert!(parse_newtype(input).finish().is_err());
+    }
+}
+
+#[cfg(test)]
+mod parse_clause {
+    use super::*;
+
+    #[test]
+    fn literal_and_guard() {
+        let input = Parser::new_extra(
+            "pick: 0, n | n > 3 -> n",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (_rest, parsed) = parse_clause(input).finish().unwrap();
+
+        assert_eq!(parsed.name.name, "pick");
+        assert!(matches!(parsed.patterns[0], Pattern::Literal(_, _)));
+        assert!(matches!(parsed.patterns[1], Pattern::Identifier(_)));
+        assert!(parsed.guard.is_some());
+    }
+
+    #[test]
+    fn literal_patterns() {
+        for source in ["0", "1.5", "true", "\"a\"", "'a'"] {
+            let input =
+                Parser::new_extra(source, ParserCtx::new(PathBuf::new(), Config::default()));
+
+            let (_rest, parsed) = parse_pattern(input).finish().unwrap();
+
+            match parsed {
+                Pattern::Literal(_, op) => assert_eq!(op.name, "=="),
+                Pattern::Identifier(_) => panic!("{} is not a literal pattern", source),
+            }
+        }
+    }
+
+    #[test]
+    fn merged() {
+        let input = Parser::new_extra(
+            "fact: 0 -> 1\nfact: n -> n * fact (n - 1)\nmain: -> fact 4\n",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (_rest, parsed) = parse_root(input).finish().unwrap();
+
+        match parsed.r#mod.top_levels.as_slice() {
+            [TopLevel::Clauses(c), TopLevel::Function(f)] => {
+                assert_eq!(c.name.name, "fact");
+                assert_eq!(c.clauses.len(), 2);
+                assert_eq!(f.name.name, "main");
+            }
+            top_levels => panic!("Unexpected top levels: {:?}", top_levels),
+        }
+    }
+
+    // Without a literal or a guard, the second one is a duplicate
+    #[test]
+    fn plain_repeat() {
+        let input = Parser::new_extra(
+            "f: a -> a\nf: b -> b\n",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (_rest, parsed) = parse_root(input).finish().unwrap();
+
+        assert!(matches!(
+            parsed.r#mod.top_levels.as_slice(),
+            [TopLevel::Function(_), TopLevel::Function(_)]
+        ));
+    }
+
+    #[test]
+    fn plain_function() {
+        let input = Parser::new_extra(
+            "f: a, b -> a\n",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (_rest, parsed) = parse_root(input).finish().unwrap();
+
+        assert!(matches!(
+            parsed.r#mod.top_levels.as_slice(),
+            [TopLevel::Function(_)]
+        ));
+    }
+}
+
+#[cfg(test)]
+mod harness {
+    use super::*;
+
+    fn functions(root: &Root) -> Vec<String> {
+        root.r#mod
+            .top_levels
+            .iter()
+            .filter_map(|top_level| match top_level {
+                TopLevel::Function(f) => Some(f.name.name.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn replaces_main() {
+        let input = Parser::new_extra(
+            "test \"a\": -> 1\nmain: -> 0\n",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (rest, mut parsed) = parse_root(input).finish().unwrap();
+
+        test_harness::add_harness(&mut parsed, rest.extra);
+
+        assert_eq!(
+            functions(&parsed),
+            vec![test_harness::USER_MAIN.to_string(), "main".to_string()]
+        );
+    }
+
+    #[test]
+    fn without_tests() {
+        let input = Parser::new_extra(
+            "f: -> 1\n",
+            ParserCtx::new(PathBuf::new(), Config::default()),
+        );
+
+        let (rest, mut parsed) = parse_root(input).finish().unwrap();
+
+        let ctx = test_harness::add_harness(&mut parsed, rest.extra);
+
+        assert_eq!(
+            functions(&parsed),
+            vec!["f".to_string(), "main".to_string()]
+        );
+        assert!(ctx
+            .files()
+            .contains_key(&PathBuf::from(test_harness::HARNESS_PATH)));
+    }
+}
+
+#[cfg(test)]
+mod attach_docs {
+    use super::*;
+
+    fn docs(source: &str) -> Vec<(String, String)> {
+        let file = SourceFile::from_str("main.rk", source).unwrap();
+
+        let mut ctx = ParserCtx::new(PathBuf::from("main.rk"), Config::default());
+
+        ctx.files.insert(file.file_path.clone(), file.clone());
+
+        let (rest, root) = parse_root(Parser::new_extra(&file.content, ctx))
+            .finish()
+            .unwrap();
+
+        let docs = doc_comments::collect(&root, &rest.extra.identities, &rest.extra.files);
+
+        let mut docs = doc_comments::items(&root.r#mod)
+            .into_iter()
+            .filter_map(|(node_id, name)| Some((name, docs.get(&node_id)?.clone())))
+            .collect::<Vec<_>>();
+
+        docs.sort();
+
+        docs
+    }
+
+    #[test]
+    fn attached_to_items() {
+        let source = "## Adds
+##   two numbers
+add: a, b -> a + b
+
+# Not a doc
+## Point
+@[allow(unused-function)]
+struct Point
+  ## The x
+  x: Int64
+  y: Int64
+
+impl Point
+  ## Gets x
+  @get: -> @x
+
+## Lost in the middle
+
+main: -> 0
+";
+
+        assert_eq!(
+            docs(source),
+            vec![
+                ("Point".to_string(), "Point".to_string()),
+                ("Point.get".to_string(), "Gets x".to_string()),
+                ("Point.x".to_string(), "The x".to_string()),
+                ("add".to_string(), "Adds\n  two numbers".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn examples() {
+        let doc = "Some text
+
+```
+let a = 1
+  a
+```
+
+```text
+not run
+```
+
+```rock
+2
+```";
+
+        assert_eq!(
+            doc_comments::examples(doc),
+            vec!["let a = 1\n  a".to_string(), "2".to_string()]
+        );
+    }
+}
+
+#[cfg(test)]
+mod tokenize {
+    use crate::parser::lexer::*;
+
+    fn kinds(source: &str) -> Vec<(TokenKind, &str)> {
+        tokenize(source)
+            .into_iter()
+            .map(|token| (token.kind, token.text(source)))
+            .collect()
+    }
+
+    #[test]
+    fn is_lossless() {
+        let source = "f: -> \"a # b\" # c\r\n/* d\n*/ 'e' '\\'' x\n";
+
+        let text = tokenize(source)
+            .iter()
+            .map(|token| token.text(source))
+            .collect::<String>();
+
+        assert_eq!(text, source);
+    }
+
+    #[test]
+    fn hash_in_string() {
+        assert_eq!(
+            kinds("\"issue #12\" # comment\n"),
+            vec![
+                (TokenKind::String, "\"issue #12\""),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::LineComment, "# comment"),
+                (TokenKind::Newline, "\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn doc_comment() {
+        assert_eq!(
+            kinds("## doc\n#comment\n"),
+            vec![
+                (TokenKind::DocComment, "## doc"),
+                (TokenKind::Newline, "\n"),
+                (TokenKind::LineComment, "#comment"),
+                (TokenKind::Newline, "\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn escaped_quotes() {
+        assert_eq!(
+            kinds("\"a\\\"#\" '\\''"),
+            vec![
+                (TokenKind::String, "\"a\\\"#\""),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::Char, "'\\''"),
+            ]
+        );
+    }
+
+    #[test]
+    fn block_comment() {
+        assert_eq!(
+            kinds("a /* b\n c */b"),
+            vec![
+                (TokenKind::Code, "a"),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::BlockComment, "/* b\n c */"),
+                (TokenKind::Code, "b"),
+            ]
+        );
+    }
+
+    #[test]
+    fn strip_comments_keeps_offsets() {
+        let source = "a: \"#\" # é\n/* b\nc */d\n";
+
+        let stripped = strip_comments(source);
+
+        assert_eq!(stripped.len(), source.len());
+        assert_eq!(stripped, "a: \"#\"     \n    \n    d\n");
+    }
 }
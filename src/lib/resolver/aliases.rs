@@ -0,0 +1,317 @@
+use std::{
+    collections::{btree_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet},
+    path::PathBuf,
+};
+
+use crate::{
+    ast::{tree::*, visit::Visitor, visit_mut::*, NodeId},
+    diagnostics::{Diagnostic, Diagnostics},
+    parser::span::Span,
+    ty::{PrimitiveType, Type},
+};
+
+// An alias that is its own expansion, directly or not, stops there
+const MAX_ALIAS_DEPTH: usize = 32;
+
+// The modules are named by their path, like `root::foo::bar`
+fn mod_name(path: &IdentifierPath) -> String {
+    path.path
+        .iter()
+        .map(|ident| ident.name.clone())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+#[derive(Debug, Clone)]
+struct Alias {
+    ty: Type,
+    module: String, // Where it is declared, its type is expanded there
+    node_id: NodeId,
+}
+
+// The aliases and the newtypes declared in each module, and the items that each module imports
+#[derive(Debug, Default)]
+struct AliasCollector {
+    cur_mod: Vec<IdentifierPath>,
+    declared: HashMap<String, BTreeMap<String, Alias>>, // <Mod, <Name, Alias>>
+    uses: HashMap<String, Vec<(String, String)>>,       // <Mod, [(ImportedMod, Name)]>
+    named: BTreeSet<String>, // The structs and the traits, that keep their name
+}
+
+impl AliasCollector {
+    fn cur_mod(&self) -> &IdentifierPath {
+        self.cur_mod.last().unwrap()
+    }
+
+    fn declare(&mut self, name: String, ty: Type, node_id: NodeId) {
+        let module = mod_name(self.cur_mod());
+
+        self.declared.entry(module.clone()).or_default().insert(
+            name,
+            Alias {
+                ty,
+                module,
+                node_id,
+            },
+        );
+    }
+
+    // The same resolution of the path than for the other items
+    fn add_use(&mut self, r#use: &Use) {
+        if r#use.path.path.len() < 2 {
+            return;
+        }
+
+        let mut mod_path = if r#use.path.has_root() {
+            r#use.path.parent()
+        } else {
+            r#use.path.parent().prepend_mod(self.cur_mod().clone())
+        };
+
+        mod_path.resolve_supers();
+
+        let cur_mod = mod_name(self.cur_mod());
+
+        self.uses.entry(cur_mod).or_default().push((
+            mod_name(&mod_path),
+            r#use.path.last_segment_ref().name.clone(),
+        ));
+    }
+
+    // Every module sees its own aliases and the ones it imports, even through another import
+    fn visible_aliases(self) -> HashMap<String, BTreeMap<String, Alias>> {
+        let mut visible = self.declared;
+
+        loop {
+            let mut changed = false;
+
+            for (module, uses) in &self.uses {
+                for (imported_mod, name) in uses {
+                    let imported = match visible.get(imported_mod) {
+                        Some(aliases) if name == "(*)" => aliases.clone(),
+                        Some(aliases) => aliases
+                            .get(name)
+                            .map(|alias| BTreeMap::from([(name.clone(), alias.clone())]))
+                            .unwrap_or_default(),
+                        None => continue,
+                    };
+
+                    let aliases = visible.entry(module.clone()).or_default();
+
+                    for (name, alias) in imported {
+                        if let Entry::Vacant(entry) = aliases.entry(name) {
+                            entry.insert(alias);
+
+                            changed = true;
+                        }
+                    }
+                }
+            }
+
+            if !changed {
+                return visible;
+            }
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for AliasCollector {
+    fn visit_top_level(&mut self, top: &'a TopLevel) {
+        match top {
+            TopLevel::TypeAlias(t) => {
+                self.declare(t.name.name.clone(), t.ty.clone(), t.name.node_id)
+            }
+            TopLevel::Newtype(n) => self.declare(n.name.name.clone(), n.to_type(), n.node_id),
+            TopLevel::Struct(s) => {
+                self.named.insert(s.name.name.clone());
+            }
+            TopLevel::Trait(t) => {
+                self.named.insert(t.name.get_name());
+            }
+            TopLevel::Use(u) => self.add_use(u),
+            TopLevel::Mod(name, m) => {
+                self.cur_mod.push(self.cur_mod().child(name.clone()));
+
+                self.visit_mod(m);
+
+                self.cur_mod.pop();
+            }
+            _ => (),
+        }
+    }
+}
+
+// Replaces the aliases by their type, and the names of the newtypes by the newtypes
+struct AliasExpander<'b> {
+    cur_mod: Vec<IdentifierPath>,
+    aliases: HashMap<String, BTreeMap<String, Alias>>,
+    named: BTreeSet<String>,
+    cur_node: NodeId, // The closest node with a span, the types have none
+    identities: &'b BTreeMap<NodeId, Span>,
+    expanded: HashSet<(PathBuf, NodeId)>,
+    diagnostics: Diagnostics,
+}
+
+impl<'b> AliasExpander<'b> {
+    fn expand(&mut self, t: &Type, module: &str, depth: usize) -> Type {
+        match t {
+            Type::Trait(name) if depth < MAX_ALIAS_DEPTH => {
+                let alias = self
+                    .aliases
+                    .get(module)
+                    .and_then(|aliases| aliases.get(name))
+                    .cloned();
+
+                match alias {
+                    Some(alias) => {
+                        if let Some(span) = self.identities.get(&self.cur_node) {
+                            self.expanded
+                                .insert((span.file_path.clone(), alias.node_id));
+                        }
+
+                        self.expand(&alias.ty, &alias.module, depth + 1)
+                    }
+                    None => {
+                        if !self.named.contains(name) {
+                            self.unknown_type(name);
+                        }
+
+                        t.clone()
+                    }
+                }
+            }
+            Type::Newtype(name, inner) => Type::Newtype(
+                name.clone(),
+                Box::new(self.expand(inner, module, depth + 1)),
+            ),
+            Type::Primitive(PrimitiveType::Array(inner, size)) => Type::Primitive(
+                PrimitiveType::Array(Box::new(self.expand(inner, module, depth)), *size),
+            ),
+            Type::Func(f) => {
+                let mut f = f.clone();
+
+                for arg in &mut f.arguments {
+                    *arg = self.expand(arg, module, depth);
+                }
+
+                f.ret = Box::new(self.expand(&f.ret, module, depth));
+
+                Type::Func(f)
+            }
+            Type::Struct(s) => {
+                let mut s = s.clone();
+
+                for field in s.defs.values_mut() {
+                    **field = self.expand(field, module, depth);
+                }
+
+                Type::Struct(s)
+            }
+            _ => t.clone(),
+        }
+    }
+
+    fn unknown_type(&mut self, name: &str) {
+        let span = self.identities.get(&self.cur_node).cloned();
+
+        self.diagnostics.push_error(Diagnostic::new_unknown_type(
+            span.unwrap_or_else(Span::new_placeholder),
+            name.to_string(),
+        ));
+    }
+}
+
+impl<'a, 'b> VisitorMut<'a> for AliasExpander<'b> {
+    fn visit_top_level(&mut self, top: &'a mut TopLevel) {
+        match top {
+            TopLevel::Mod(name, m) => {
+                let child = self.cur_mod.last().unwrap().child(name.clone());
+
+                self.cur_mod.push(child);
+
+                self.visit_mod(m);
+
+                self.cur_mod.pop();
+            }
+            TopLevel::TypeAlias(t) => {
+                self.cur_node = t.name.node_id;
+
+                walk_type_alias(self, t);
+            }
+            _ => walk_top_level(self, top),
+        }
+    }
+
+    fn visit_prototype(&mut self, prototype: &'a mut Prototype) {
+        self.cur_node = prototype.name.node_id;
+
+        walk_prototype(self, prototype);
+    }
+
+    fn visit_const(&mut self, c: &'a mut Const) {
+        self.cur_node = c.node_id;
+
+        walk_const(self, c);
+    }
+
+    fn visit_newtype(&mut self, n: &'a mut Newtype) {
+        self.cur_node = n.node_id;
+
+        walk_newtype(self, n);
+    }
+
+    fn visit_trait(&mut self, t: &'a mut Trait) {
+        self.cur_node = t.node_id;
+
+        walk_trait(self, t);
+    }
+
+    fn visit_impl(&mut self, i: &'a mut Impl) {
+        self.cur_node = i.node_id;
+
+        walk_impl(self, i);
+    }
+
+    fn visit_type_ascription(&mut self, ty: &'a mut TypeAscription) {
+        self.cur_node = ty.node_id;
+
+        walk_type_ascription(self, ty);
+    }
+
+    fn visit_type(&mut self, t: &'a mut Type) {
+        let module = mod_name(self.cur_mod.last().unwrap());
+
+        *t = self.expand(t, &module, 0);
+    }
+}
+
+// The aliases are resolved like the other items: in their module, in any order, and in the
+// modules that `use` them. The names that are neither an alias, a struct or a trait are unknown.
+// Gives the aliases expanded in each file, that make their imports used.
+pub fn expand_aliases(
+    root: &mut Root,
+    identities: &BTreeMap<NodeId, Span>,
+) -> (HashSet<(PathBuf, NodeId)>, Diagnostics) {
+    let mut collector = AliasCollector {
+        cur_mod: vec![IdentifierPath::new_root()],
+        ..AliasCollector::default()
+    };
+
+    collector.visit_mod(&root.r#mod);
+
+    let named = std::mem::take(&mut collector.named);
+
+    let mut expander = AliasExpander {
+        cur_mod: vec![IdentifierPath::new_root()],
+        aliases: collector.visible_aliases(),
+        named,
+        cur_node: 0,
+        identities,
+        expanded: HashSet::new(),
+        diagnostics: Diagnostics::default(),
+    };
+
+    expander.visit_mod(&mut root.r#mod);
+
+    (expander.expanded, expander.diagnostics)
+}
@@ -1,17 +1,28 @@
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::PathBuf,
+};
 
 use crate::{
-    ast::tree::{IdentifierPath, Root},
-    diagnostics::Diagnostic,
+    ast::{
+        tree::{IdentifierPath, Root, Use},
+        NodeId,
+    },
+    cache::{ModuleGraph, ResolvedModule, ID_BASE_SHIFT},
+    diagnostics::{Diagnostic, DiagnosticType, Lint, Suggestion},
     helpers::scopes::Scopes,
     infer::trait_solver::TraitSolver,
-    parser::ParsingCtx,
+    parser::{self, span::Span, ParsingCtx},
 };
 
+mod aliases;
 mod resolution_map;
 mod resolve_ctx;
 mod unused_collector;
 
+#[cfg(test)]
+mod tests;
+
 pub use resolution_map::*;
 pub use resolve_ctx::*;
 
@@ -20,34 +31,289 @@ pub fn resolve(root: &mut Root, parsing_ctx: &mut ParsingCtx) -> Result<(), Diag
 
     scopes.insert(IdentifierPath::new_root(), Scopes::new());
 
-    let (resolutions, trait_solver) = {
+    let (expanded_aliases, alias_diagnostics) =
+        aliases::expand_aliases(root, &parsing_ctx.identities);
+
+    parsing_ctx.diagnostics.append(alias_diagnostics);
+
+    let graph = ModuleGraph::new(&root.r#mod, parsing_ctx);
+    let reused_mods = parsing_ctx.modules.reusable_resolutions(&graph);
+
+    let first_diagnostic = parsing_ctx.diagnostics.list.len();
+    let mut resolutions = ResolutionMap::default();
+
+    for name in &reused_mods {
+        let cached = &parsing_ctx.modules.previous_resolved[name];
+
+        for (pointer, pointee) in &cached.resolutions {
+            resolutions.insert(*pointer, *pointee);
+        }
+
+        parsing_ctx.diagnostics.append(cached.diagnostics.clone());
+    }
+
+    let (resolutions, trait_solver, imports, scopes) = {
         let mut ctx = ResolveCtx {
             parsing_ctx,
             scopes,
             cur_scope: IdentifierPath::new_root(),
-            resolutions: ResolutionMap::default(),
+            resolutions,
             trait_solver: TraitSolver::new(),
+            imports: HashMap::new(),
+            consts: HashSet::new(),
+            reused_mods: reused_mods.clone(),
         };
 
         ctx.run(root);
 
-        (ctx.resolutions, ctx.trait_solver)
+        (ctx.resolutions, ctx.trait_solver, ctx.imports, ctx.scopes)
     };
 
+    parsing_ctx.modules.resolved =
+        resolved_modules(&graph, &resolutions, parsing_ctx, first_diagnostic);
+    parsing_ctx.modules.reused_resolutions = reused_mods;
+    parsing_ctx.modules.graph = graph;
+
     root.resolutions = resolutions;
     root.trait_solver = trait_solver;
+    root.mod_scopes = scopes
+        .into_iter()
+        .filter_map(|(path, scopes)| Some((path, scopes.scopes.into_iter().next()?)))
+        .collect();
+
+    let unused = unused_collector::collect_unused(root);
 
-    let (mut unused_fns, unused_methods) = unused_collector::collect_unused(root);
+    let mut unused_fns = unused.fns;
 
     for unused_fn in &unused_fns {
         let span = parsing_ctx.identities.get(unused_fn).unwrap();
 
-        parsing_ctx
-            .diagnostics
-            .push_warning(Diagnostic::new_unused_function(span.clone()));
+        parsing_ctx.push_lint(
+            Lint::UnusedFunction,
+            Diagnostic::new_unused_function(span.clone()),
+        );
+    }
+
+    for param in &unused.params {
+        let span = parsing_ctx.identities.get(param).unwrap().clone();
+
+        if let Some(removal) = removal_span(parsing_ctx, &span, unused_collector::param_removal) {
+            let name = span_text(parsing_ctx, &span);
+
+            // A method of a trait impl keeps the parameters of the trait
+            let suggestion = if unused.trait_params.contains(param) {
+                prefix_suggestion(&span, &name)
+            } else {
+                Suggestion::new_removal("Remove the parameter, or prefix it with `_`", removal)
+            };
+
+            parsing_ctx.push_lint(
+                Lint::UnusedParameter,
+                Diagnostic::new_unused_parameter(span, name).with_suggestion(suggestion),
+            );
+        }
+    }
+
+    for variable in &unused.variables {
+        let span = parsing_ctx.identities.get(variable).unwrap().clone();
+
+        if let Some(removal) = removal_span(parsing_ctx, &span, |content, start, _end| {
+            unused_collector::statement_removal(content, start)
+        }) {
+            let name = span_text(parsing_ctx, &span);
+
+            let diag = Diagnostic::new_unused_variable(span.clone(), name.clone());
+
+            // The calls of its value might be made for what they do
+            let diag = if unused.calls.contains(variable) {
+                let binding = removal_span(parsing_ctx, &span, unused_collector::binding_removal);
+
+                diag.with_suggestion(prefix_suggestion(&span, &name))
+                    .with_suggestion(Suggestion::new_removal(
+                        "Keep only the expression",
+                        binding.unwrap(),
+                    ))
+            } else {
+                diag.with_suggestion(Suggestion::new_removal("Remove the variable", removal))
+            };
+
+            parsing_ctx.push_lint(Lint::UnusedVariable, diag);
+        }
     }
 
-    unused_fns.extend(unused_methods);
+    for r#use in &unused.uses {
+        if is_used_import(r#use, &imports, &expanded_aliases, root, parsing_ctx) {
+            continue;
+        }
+
+        let start = parsing_ctx.identities.get(&r#use.node_id).unwrap().clone();
+        let end = parsing_ctx
+            .identities
+            .get(&r#use.path.last_segment_ref().node_id)
+            .unwrap();
+        let span = Span::new(start.file_path.clone(), start.start, end.end);
+
+        if let Some(removal) = removal_span(parsing_ctx, &span, |content, start, _end| {
+            unused_collector::statement_removal(content, start)
+        }) {
+            let path = span_text(parsing_ctx, &span);
+
+            parsing_ctx.push_lint(
+                Lint::UnusedImport,
+                Diagnostic::new_unused_import(span, path)
+                    .with_suggestion(Suggestion::new_removal("Remove the import", removal)),
+            );
+        }
+    }
+
+    unused_fns.extend(unused.methods);
 
     parsing_ctx.return_if_error()
 }
+
+// Splits the resolutions and the diagnostics of the resolution by module, for the next builds
+fn resolved_modules(
+    graph: &ModuleGraph,
+    resolutions: &ResolutionMap<NodeId>,
+    parsing_ctx: &ParsingCtx,
+    first_diagnostic: usize,
+) -> BTreeMap<String, ResolvedModule> {
+    let mut modules = graph
+        .modules
+        .iter()
+        .map(|(name, module)| {
+            let resolved = ResolvedModule {
+                fingerprint: module.resolution_fingerprint,
+                ..Default::default()
+            };
+
+            (name.clone(), resolved)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let bases = graph
+        .modules
+        .iter()
+        .map(|(name, module)| (module.id_base, name.clone()))
+        .collect::<HashMap<_, _>>();
+
+    for (pointer, pointee) in resolutions.inner() {
+        let base = pointer >> ID_BASE_SHIFT << ID_BASE_SHIFT;
+
+        if let Some(module) = bases.get(&base).and_then(|name| modules.get_mut(name)) {
+            module.resolutions.push((*pointer, *pointee));
+        }
+    }
+
+    let files = graph
+        .modules
+        .iter()
+        .map(|(name, module)| (&module.file_path, name.clone()))
+        .collect::<HashMap<_, _>>();
+
+    let diagnostics = &parsing_ctx.diagnostics;
+
+    for (diag, diag_type) in diagnostics
+        .list
+        .iter()
+        .zip(&diagnostics.list_types)
+        .skip(first_diagnostic)
+    {
+        if let Some(module) = files
+            .get(&diag.span.file_path)
+            .and_then(|name| modules.get_mut(name))
+        {
+            match diag_type {
+                DiagnosticType::Warning => module.diagnostics.push_warning(diag.clone()),
+                DiagnosticType::Error => module.diagnostics.push_error(diag.clone()),
+            }
+        }
+    }
+
+    for module in modules.values_mut() {
+        module.resolutions.sort_unstable();
+    }
+
+    modules
+}
+
+// Computes the span to remove from the content of the file, `None` for the std files
+fn removal_span(
+    parsing_ctx: &ParsingCtx,
+    span: &Span,
+    removal: impl Fn(&str, usize, usize) -> (usize, usize),
+) -> Option<Span> {
+    if parser::is_stdlib_file(span.file_path.to_str().unwrap()) {
+        return None;
+    }
+
+    let file = parsing_ctx.files.get(&span.file_path)?;
+
+    let (start, end) = removal(&file.content, span.start, span.end);
+
+    Some(Span::new(span.file_path.clone(), start, end))
+}
+
+fn prefix_suggestion(span: &Span, name: &str) -> Suggestion {
+    Suggestion::new_replacement("Prefix it with `_`", span.clone(), format!("_{}", name))
+}
+
+fn span_text(parsing_ctx: &ParsingCtx, span: &Span) -> String {
+    parsing_ctx
+        .files
+        .get(&span.file_path)
+        .and_then(|file| file.content.get(span.start..span.end))
+        .unwrap_or_default()
+        .to_string()
+}
+
+// An import is used when an identifier of its file resolves to one of the items it imports
+fn is_used_import(
+    r#use: &Use,
+    imports: &HashMap<NodeId, Vec<NodeId>>,
+    expanded_aliases: &HashSet<(PathBuf, NodeId)>,
+    root: &Root,
+    parsing_ctx: &ParsingCtx,
+) -> bool {
+    // The std prelude is imported in every file
+    let path = r#use
+        .path
+        .path
+        .iter()
+        .map(|ident| ident.name.clone())
+        .collect::<Vec<_>>()
+        .join("::");
+
+    if path == "std::prelude::(*)" || path == "root::std::prelude::(*)" {
+        return true;
+    }
+
+    // Not resolved, an error has already been reported
+    let imported = match imports.get(&r#use.node_id) {
+        Some(imported) => imported.iter().collect::<HashSet<_>>(),
+        None => return true,
+    };
+
+    let file_path = &parsing_ctx
+        .identities
+        .get(&r#use.node_id)
+        .unwrap()
+        .file_path;
+
+    // The aliases are not identifiers, they are replaced by their type where they are used
+    if imported
+        .iter()
+        .any(|id| expanded_aliases.contains(&(file_path.clone(), **id)))
+    {
+        return true;
+    }
+
+    root.resolutions.inner().iter().any(|(pointer, pointee)| {
+        imported.contains(pointee)
+            && parsing_ctx
+                .identities
+                .get(pointer)
+                .map(|span| &span.file_path == file_path)
+                .unwrap_or(false)
+    })
+}
@@ -1,9 +1,10 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use crate::{
     ast::{tree::*, visit::*, NodeId},
-    diagnostics::Diagnostic,
-    helpers::scopes::*,
+    ast_lowering::check_clauses,
+    diagnostics::{Diagnostic, Lint, Suggestion},
+    helpers::{edit_distance::find_best_match, scopes::*},
     infer::trait_solver::TraitSolver,
     parser::span::Span,
     parser::ParsingCtx,
@@ -17,6 +18,9 @@ pub struct ResolveCtx<'a> {
     pub cur_scope: IdentifierPath,
     pub resolutions: ResolutionMap<NodeId>,
     pub trait_solver: TraitSolver,
+    pub imports: HashMap<NodeId, Vec<NodeId>>, // <UseId, ImportedIds>
+    pub consts: HashSet<NodeId>,
+    pub reused_mods: BTreeSet<String>, // Resolved in a previous build
 }
 
 impl<'a> ResolveCtx<'a> {
@@ -68,6 +72,15 @@ impl<'a> ResolveCtx<'a> {
         self.cur_scope = name;
     }
 
+    pub fn cur_mod_name(&self) -> String {
+        self.cur_scope
+            .path
+            .iter()
+            .map(|ident| ident.name.clone())
+            .collect::<Vec<_>>()
+            .join("::")
+    }
+
     pub fn push_scope(&mut self) {
         if let Some(ref mut scopes) = self.scopes.get_mut(&self.cur_scope) {
             scopes.push();
@@ -90,11 +103,82 @@ impl<'a> ResolveCtx<'a> {
     pub fn get_span(&self, node_id: NodeId) -> Span {
         self.parsing_ctx.identities.get(&node_id).unwrap().clone()
     }
+
+    // A function is defined once per module, its clauses are already merged
+    fn add_function(&mut self, fn_names: &mut HashMap<String, NodeId>, name: &Identifier) {
+        let first = *fn_names.entry(name.name.clone()).or_insert(name.node_id);
+
+        if first != name.node_id {
+            self.parsing_ctx
+                .diagnostics
+                .push_error(Diagnostic::new_duplicated_function(
+                    self.get_span(name.node_id),
+                    name.name.clone(),
+                    self.get_span(first),
+                ));
+        }
+    }
+
+    // The names of a module that can be suggested in place of an unknown one
+    pub fn names_in_scope(&self, mod_path: &IdentifierPath) -> Vec<String> {
+        self.scopes
+            .get(mod_path)
+            .map(|scopes| {
+                scopes
+                    .keys()
+                    .filter(|name| name.starts_with(|c: char| c.is_alphabetic() || c == '_'))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn unknown_identifier(&self, ident: &Identifier, mod_path: &IdentifierPath) -> Diagnostic {
+        Diagnostic::new_unknown_identifier(self.get_span(ident.node_id))
+            .with_best_match(&ident.name, &self.names_in_scope(mod_path))
+    }
+
+    // `mod_path` is the resolved module of `path`, that does not exist
+    pub fn module_not_found(&self, path: &IdentifierPath, mod_path: &IdentifierPath) -> Diagnostic {
+        let diag = Diagnostic::new_module_not_found(
+            self.get_span(path.last_segment_ref().node_id),
+            mod_path
+                .path
+                .iter()
+                .map(|p| p.name.clone())
+                .collect::<Vec<_>>()
+                .join("/"),
+        );
+
+        // We only look for a misspelled last module, when its parent exists
+        let parent = mod_path.parent();
+        let segment = &path.path[path.path.len() - 2];
+
+        if segment.name == "super" || segment.name == "root" || !self.scopes.contains_key(&parent) {
+            return diag;
+        }
+
+        let siblings = self
+            .scopes
+            .keys()
+            .filter(|p| p.path.len() == mod_path.path.len() && p.parent() == parent)
+            .map(|p| p.last_segment_ref().name.clone())
+            .collect::<Vec<_>>();
+
+        match find_best_match(&segment.name, &siblings) {
+            Some(best) => diag.with_suggestion(Suggestion::new_did_you_mean(
+                self.get_span(segment.node_id),
+                best,
+            )),
+            None => diag,
+        }
+    }
 }
 
 impl<'a> Visitor<'a> for ResolveCtx<'a> {
     fn visit_mod(&mut self, m: &'a Mod) {
         let mut sig_names = HashMap::new();
+        let mut fn_names = HashMap::new();
 
         // We add every top level first
         for top in &m.top_levels {
@@ -158,8 +242,34 @@ impl<'a> Visitor<'a> for ResolveCtx<'a> {
                         self.resolutions.insert(sig_id, f.node_id);
                     }
 
+                    self.add_function(&mut fn_names, &f.name);
                     self.add_to_current_scope((*f.name).clone(), f.node_id);
                 }
+                TopLevel::Clauses(c) => {
+                    if let Some(sig_id) = sig_names.remove(&c.name.name) {
+                        self.resolutions.insert(sig_id, c.node_id);
+                    }
+
+                    self.add_function(&mut fn_names, &c.name);
+                    self.add_to_current_scope((*c.name).clone(), c.node_id);
+                }
+                TopLevel::Test(t) => {
+                    self.add_to_current_scope((*t.func.name).clone(), t.func.node_id);
+                }
+                TopLevel::Const(c) => {
+                    self.consts.insert(c.node_id);
+
+                    self.add_to_current_scope(c.name.name.clone(), c.node_id);
+                }
+                TopLevel::TypeAlias(t) => {
+                    self.add_to_current_scope(t.name.name.clone(), t.name.node_id);
+                }
+                TopLevel::Newtype(n) => {
+                    // Its scope holds the methods of its impls, like for a struct
+                    self.new_struct(n.name.clone());
+
+                    self.add_to_current_scope(n.name.name.clone(), n.node_id);
+                }
             }
         }
 
@@ -172,6 +282,17 @@ impl<'a> Visitor<'a> for ResolveCtx<'a> {
                 ))
         });
 
+        // The items of a module that did not change are already resolved
+        if self.reused_mods.contains(&self.cur_mod_name()) {
+            for top in &m.top_levels {
+                if let TopLevel::Use(_) | TopLevel::Mod(_, _) = top {
+                    self.visit_top_level(top);
+                }
+            }
+
+            return;
+        }
+
         walk_list!(self, visit_top_level, &m.top_levels);
     }
 
@@ -192,6 +313,15 @@ impl<'a> Visitor<'a> for ResolveCtx<'a> {
 
                 if !assign.is_let {
                     if let Some(previous_assign_node_id) = self.get(ident.name.clone()) {
+                        if self.consts.contains(&previous_assign_node_id) {
+                            self.parsing_ctx.diagnostics.push_error(
+                                Diagnostic::new_assign_to_const(
+                                    self.get_span(ident.node_id),
+                                    ident.name.clone(),
+                                ),
+                            );
+                        }
+
                         self.resolutions
                             .insert(ident.node_id, previous_assign_node_id);
                     }
@@ -236,6 +366,11 @@ impl<'a> Visitor<'a> for ResolveCtx<'a> {
             TopLevel::Impl(i) => self.visit_impl(i),
             TopLevel::Struct(s) => self.visit_struct_decl(s),
             TopLevel::Function(f) => self.visit_function_decl(f),
+            TopLevel::Clauses(c) => self.visit_function_clauses(c),
+            TopLevel::Test(t) => self.visit_function_decl(&t.func),
+            TopLevel::Const(c) => self.visit_expression(&c.value),
+            TopLevel::TypeAlias(_t) => (),
+            TopLevel::Newtype(_n) => (),
             TopLevel::Mod(name, m) => {
                 let current_mod = self.cur_scope.clone();
 
@@ -263,9 +398,7 @@ impl<'a> Visitor<'a> for ResolveCtx<'a> {
             None => self
                 .parsing_ctx
                 .diagnostics
-                .push_error(Diagnostic::new_unknown_identifier(
-                    self.get_span(s.name.node_id).into(),
-                )),
+                .push_error(self.unknown_identifier(&s.name, &self.cur_scope)),
         };
 
         self.visit_identifier(&s.name);
@@ -287,11 +420,79 @@ impl<'a> Visitor<'a> for ResolveCtx<'a> {
         self.pop_scope();
     }
 
+    fn visit_function_clauses(&mut self, c: &'a FunctionClauses) {
+        walk_function_clauses(self, c);
+
+        let check = check_clauses(c);
+
+        if let Some(i) = check.arity_mismatch {
+            self.parsing_ctx
+                .diagnostics
+                .push_error(Diagnostic::new_syntax_error(
+                    self.get_span(c.clauses[i].name.node_id),
+                    format!(
+                        "The clauses of `{}` must take {} arguments",
+                        c.name.name,
+                        c.clauses[0].patterns.len()
+                    ),
+                ));
+
+            return;
+        }
+
+        for i in &check.unreachable {
+            self.parsing_ctx.push_lint(
+                Lint::UnreachableClause,
+                Diagnostic::new_unreachable_clause(self.get_span(c.clauses[*i].name.node_id)),
+            );
+        }
+
+        if !check.exhaustive {
+            self.parsing_ctx
+                .diagnostics
+                .push_error(Diagnostic::new_non_exhaustive_clauses(
+                    self.get_span(c.name.node_id),
+                    c.name.name.clone(),
+                ));
+        }
+    }
+
+    // The identifiers of the patterns are bound like parameters
+    fn visit_clause(&mut self, clause: &'a Clause) {
+        self.push_scope();
+
+        for pattern in &clause.patterns {
+            match pattern {
+                Pattern::Identifier(id) => self.add_to_current_scope(id.name.clone(), id.node_id),
+                Pattern::Literal(_lit, op) => self.visit_identifier(op),
+            }
+        }
+
+        if let Some(guard) = &clause.guard {
+            self.visit_expression(guard);
+        }
+
+        self.visit_body(&clause.body);
+
+        self.pop_scope();
+    }
+
     fn visit_use(&mut self, r#use: &'a Use) {
         let ident = r#use.path.last_segment_ref();
 
+        // Importing a whole module is not supported
         if r#use.path.path.len() == 1 {
-            panic!("Unimplemented");
+            self.parsing_ctx
+                .diagnostics
+                .push_error(Diagnostic::new_syntax_error(
+                    self.get_span(ident.node_id),
+                    format!(
+                        "Expected an item of the module, like `use {}::(*)`",
+                        ident.name
+                    ),
+                ));
+
+            return;
         }
 
         let mut mod_path = if r#use.path.has_root() {
@@ -307,9 +508,13 @@ impl<'a> Visitor<'a> for ResolveCtx<'a> {
                 if ident.name == "(*)" {
                     let scope = scopes.scopes.get(0).unwrap();
 
+                    let mut imported = vec![];
+
                     for (k, v) in &scope.clone() {
                         self.add_to_current_scope(k.clone(), v.clone());
 
+                        imported.push(*v);
+
                         // This is barbarian, we try each resolution if it match a scope name
                         // If so, we hard-copy the struct scope into the current
                         let mut struct_scope_name = mod_path.clone();
@@ -322,17 +527,27 @@ impl<'a> Visitor<'a> for ResolveCtx<'a> {
 
                             struct_scope_name.path.push(Identifier::new(k.clone(), *v));
 
+                            // `Struct::method` resolves to the method, not to the struct
+                            if let Some(struct_scope) = struct_scopes.scopes.first() {
+                                imported.extend(struct_scope.values().copied());
+                            }
+
                             self.scopes.insert(struct_scope_name, struct_scopes);
                         }
                     }
+
+                    self.imports.insert(r#use.node_id, imported);
                 } else {
                     match scopes.get((*ident).to_string()) {
                         Some(pointed) => {
                             self.add_to_current_scope((*ident).name.clone(), pointed);
+
+                            self.imports.insert(r#use.node_id, vec![pointed]);
                         }
-                        None => self.parsing_ctx.diagnostics.push_error(
-                            Diagnostic::new_unknown_identifier(self.get_span(ident.node_id).into()),
-                        ),
+                        None => self
+                            .parsing_ctx
+                            .diagnostics
+                            .push_error(self.unknown_identifier(ident, &mod_path)),
                     };
                 }
             }
@@ -340,15 +555,7 @@ impl<'a> Visitor<'a> for ResolveCtx<'a> {
             None => self
                 .parsing_ctx
                 .diagnostics
-                .push_error(Diagnostic::new_module_not_found(
-                    self.get_span(ident.node_id).into(),
-                    mod_path
-                        .path
-                        .iter()
-                        .map(|p| p.name.clone())
-                        .collect::<Vec<_>>()
-                        .join("/"),
-                )),
+                .push_error(self.module_not_found(&r#use.path, &mod_path)),
         };
     }
 
@@ -368,28 +575,17 @@ impl<'a> Visitor<'a> for ResolveCtx<'a> {
         match self.scopes.get(&mod_path) {
             Some(scopes) => match scopes.get((*ident).to_string()) {
                 Some(pointed) => self.resolutions.insert(ident.node_id, pointed),
-                None => {
-                    self.parsing_ctx
-                        .diagnostics
-                        .push_error(Diagnostic::new_unknown_identifier(
-                            self.get_span(ident.node_id).into(),
-                        ))
-                }
+                None => self
+                    .parsing_ctx
+                    .diagnostics
+                    .push_error(self.unknown_identifier(ident, &mod_path)),
             },
 
             // TODO: change to Unknown Mod diagnostic
             None => self
                 .parsing_ctx
                 .diagnostics
-                .push_error(Diagnostic::new_module_not_found(
-                    self.get_span(ident.node_id).into(),
-                    mod_path
-                        .path
-                        .iter()
-                        .map(|p| p.name.clone())
-                        .collect::<Vec<_>>()
-                        .join("/"),
-                )),
+                .push_error(self.module_not_found(path, &mod_path)),
         };
     }
 
@@ -399,9 +595,7 @@ impl<'a> Visitor<'a> for ResolveCtx<'a> {
             None => self
                 .parsing_ctx
                 .diagnostics
-                .push_error(Diagnostic::new_unknown_identifier(
-                    self.get_span(id.node_id).into(),
-                )),
+                .push_error(self.unknown_identifier(id, &self.cur_scope)),
         };
     }
 
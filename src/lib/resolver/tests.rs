@@ -0,0 +1,189 @@
+use std::path::PathBuf;
+
+use crate::{
+    parser::{ParsingCtx, SourceFile},
+    Config,
+};
+
+// The source fixed by each suggestion of the diagnostics of a program without the std
+fn fixes(source: &str) -> Vec<(String, String)> {
+    let config = Config {
+        quiet: true,
+        ..Config::default()
+    };
+
+    let mut file = SourceFile::from_str("main.rk", source).unwrap();
+
+    file.mod_path = PathBuf::from("root");
+
+    let mut parsing_ctx = ParsingCtx::new(&config);
+
+    parsing_ctx.add_file(&file);
+
+    let _ = crate::parse_str(&mut parsing_ctx, &config);
+
+    parsing_ctx
+        .diagnostics
+        .list
+        .iter()
+        .flat_map(|diag| diag.suggestions())
+        .map(|suggestion| {
+            let mut fixed = source.to_string();
+
+            fixed.replace_range(
+                suggestion.span.start..suggestion.span.end,
+                &suggestion.replacement,
+            );
+
+            (suggestion.message.clone(), fixed)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod removal {
+    use super::super::unused_collector::{binding_removal, param_removal, statement_removal};
+
+    #[test]
+    fn first_param() {
+        let content = "f: a, b -> b\n";
+
+        assert_eq!(param_removal(content, 3, 4), (3, 6));
+    }
+
+    #[test]
+    fn last_param() {
+        let content = "f: a, b -> a\n";
+
+        assert_eq!(param_removal(content, 6, 7), (4, 7));
+    }
+
+    #[test]
+    fn single_param() {
+        let content = "f: a -> 2\n";
+
+        assert_eq!(param_removal(content, 3, 4), (3, 4));
+    }
+
+    #[test]
+    fn single_line_statement() {
+        let content = "main: ->\n  let a = 2\n  3\n";
+
+        assert_eq!(statement_removal(content, 15), (9, 21));
+    }
+
+    #[test]
+    fn multi_line_statement() {
+        let content = "main: ->\n  let a = Foo\n    x: 2\n  3\n";
+
+        assert_eq!(statement_removal(content, 15), (9, 32));
+    }
+
+    #[test]
+    fn binding() {
+        let content = "main: ->\n  let a = f!\n  3\n";
+
+        assert_eq!(binding_removal(content, 15, 16), (11, 19));
+    }
+
+    #[test]
+    fn annotated_binding() {
+        let content = "main: ->\n  let a: Int64 = f!\n  3\n";
+
+        assert_eq!(binding_removal(content, 15, 16), (11, 26));
+    }
+}
+
+#[cfg(test)]
+mod unused {
+    use super::*;
+
+    #[test]
+    fn param() {
+        let source = "f: a, b -> b\nmain: -> f 1, 2\n";
+
+        assert_eq!(
+            fixes(source),
+            vec![(
+                "Remove the parameter, or prefix it with `_`".to_string(),
+                "f: b -> b\nmain: -> f 1, 2\n".to_string()
+            )]
+        );
+    }
+
+    // The method must take the parameters of the trait
+    #[test]
+    fn trait_impl_param() {
+        let source = "trait Combine\n  combine: a, a -> a\n\nimpl Combine Int64\n  combine: x, y -> x\n\nmain: -> 0\n";
+
+        assert_eq!(
+            fixes(source),
+            vec![(
+                "Prefix it with `_`".to_string(),
+                source.replace("x, y ->", "x, _y ->")
+            )]
+        );
+    }
+
+    #[test]
+    fn variable() {
+        let source = "main: ->\n  let a = 2\n  3\n";
+
+        assert_eq!(
+            fixes(source),
+            vec![(
+                "Remove the variable".to_string(),
+                "main: ->\n  3\n".to_string()
+            )]
+        );
+    }
+
+    // The call might be there for what it does
+    #[test]
+    fn variable_with_call() {
+        let source = "f: -> 2\nmain: ->\n  let a = f!\n  3\n";
+
+        assert_eq!(
+            fixes(source),
+            vec![
+                (
+                    "Prefix it with `_`".to_string(),
+                    source.replace("let a", "let _a")
+                ),
+                (
+                    "Keep only the expression".to_string(),
+                    source.replace("let a = f!", "f!")
+                ),
+            ]
+        );
+    }
+}
+
+#[cfg(test)]
+mod std_lints {
+    use super::*;
+
+    // The std allows the lints of the functions that a program may not use
+    #[test]
+    fn no_warnings() {
+        let config = Config {
+            quiet: true,
+            std: true,
+            ..Config::default()
+        };
+
+        let mut file =
+            SourceFile::from_str("main.rk", "mod std\nuse std::prelude::(*)\nmain: -> 0\n")
+                .unwrap();
+
+        file.mod_path = PathBuf::from("root");
+
+        let mut parsing_ctx = ParsingCtx::new(&config);
+
+        parsing_ctx.add_file(&file);
+
+        crate::parse_str(&mut parsing_ctx, &config).unwrap();
+
+        assert!(parsing_ctx.diagnostics.list.is_empty());
+    }
+}
@@ -1,7 +1,8 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     ast::{tree::*, visit::*, NodeId},
+    parser::test_harness,
     resolver::ResolutionMap,
 };
 
@@ -10,6 +11,23 @@ pub struct UnusedCollector {
     resolutions: ResolutionMap<NodeId>,
     fn_list: HashMap<NodeId, bool>,
     method_list: HashMap<NodeId, bool>,
+    params: Vec<NodeId>,
+    variables: Vec<NodeId>,
+    uses: Vec<Use>,
+    in_trait_impl: bool,
+    trait_params: HashSet<NodeId>,
+    calls: HashSet<NodeId>,
+}
+
+#[derive(Debug, Default)]
+pub struct Unused {
+    pub fns: Vec<NodeId>,
+    pub methods: Vec<NodeId>,
+    pub params: Vec<NodeId>,
+    pub variables: Vec<NodeId>,
+    pub uses: Vec<Use>, // Every use, their usage depends on the resolved imports
+    pub trait_params: HashSet<NodeId>, // Their method must keep the signature of its trait
+    pub calls: HashSet<NodeId>, // The variables whose value calls a function
 }
 
 impl UnusedCollector {
@@ -20,19 +38,41 @@ impl UnusedCollector {
         }
     }
 
-    // (fns, methods)
-    pub fn take_unused(self) -> (Vec<NodeId>, Vec<NodeId>) {
-        (
-            self.fn_list
+    pub fn take_unused(self) -> Unused {
+        // A binding is used when something resolves to it
+        let used = self
+            .resolutions
+            .inner()
+            .values()
+            .copied()
+            .collect::<HashSet<_>>();
+
+        Unused {
+            fns: self
+                .fn_list
                 .into_iter()
                 .filter_map(|(id, used)| if !used { Some(id + 1) } else { None }) // +1 to get the
                 // fn identifier's node_id, as it always follows the fn node_id
                 .collect(),
-            self.method_list
+            methods: self
+                .method_list
                 .into_iter()
                 .filter_map(|(id, used)| if !used { Some(id) } else { None })
                 .collect(),
-        )
+            params: self
+                .params
+                .into_iter()
+                .filter(|id| !used.contains(id))
+                .collect(),
+            variables: self
+                .variables
+                .into_iter()
+                .filter(|id| !used.contains(id))
+                .collect(),
+            uses: self.uses,
+            trait_params: self.trait_params,
+            calls: self.calls,
+        }
     }
 }
 
@@ -57,10 +97,19 @@ impl<'a> Visitor<'a> for UnusedCollector {
                 TopLevel::Function(f) => {
                     self.fn_list.insert(f.node_id, false);
 
-                    if f.name.name == *"main" {
+                    // The main function that the test harness replaces is not called either
+                    if f.name.name == *"main" || f.name.name == test_harness::USER_MAIN {
                         self.fn_list.insert(f.node_id, true);
                     }
                 }
+                TopLevel::Clauses(c) => {
+                    self.fn_list.insert(c.node_id, false);
+                }
+                // Only called by the test harness
+                TopLevel::Test(_t) => (),
+                TopLevel::Const(_c) => (),
+                TopLevel::TypeAlias(_t) => (),
+                TopLevel::Newtype(_n) => (),
             }
         }
 
@@ -71,7 +120,7 @@ impl<'a> Visitor<'a> for UnusedCollector {
         match &top_level {
             TopLevel::Extern(p) => self.visit_prototype(p),
             TopLevel::FnSignature(p) => self.visit_prototype(p),
-            TopLevel::Use(_u) => (),
+            TopLevel::Use(u) => self.uses.push(u.clone()),
             TopLevel::Trait(t) => self.visit_trait(t),
             TopLevel::Impl(i) => self.visit_impl(i),
             TopLevel::Struct(i) => self.visit_struct_decl(i),
@@ -80,6 +129,11 @@ impl<'a> Visitor<'a> for UnusedCollector {
                 self.visit_mod(m);
             }
             TopLevel::Function(f) => self.visit_function_decl(f),
+            TopLevel::Clauses(c) => self.visit_function_clauses(c),
+            TopLevel::Test(t) => self.visit_function_decl(&t.func),
+            TopLevel::Const(c) => self.visit_expression(&c.value),
+            TopLevel::TypeAlias(_t) => (),
+            TopLevel::Newtype(_n) => (),
             TopLevel::Infix(_ident, _) => (),
         };
     }
@@ -88,12 +142,57 @@ impl<'a> Visitor<'a> for UnusedCollector {
         self.visit_func_type(&prototype.signature);
     }
 
+    fn visit_impl(&mut self, i: &'a Impl) {
+        // `impl Trait Type`
+        self.in_trait_impl = !i.types.is_empty();
+
+        walk_impl(self, i);
+
+        self.in_trait_impl = false;
+    }
+
     fn visit_function_decl(&mut self, f: &'a FunctionDecl) {
+        for arg in &f.arguments {
+            if arg.name != "self" && !arg.name.starts_with('_') {
+                self.params.push(arg.node_id);
+
+                if self.in_trait_impl {
+                    self.trait_params.insert(arg.node_id);
+                }
+            }
+        }
+
         walk_list!(self, visit_identifier, &f.arguments);
 
         self.visit_body(&f.body);
     }
 
+    fn visit_pattern(&mut self, pattern: &'a Pattern) {
+        if let Pattern::Identifier(id) = pattern {
+            if !id.name.starts_with('_') {
+                self.params.push(id.node_id);
+            }
+        }
+
+        walk_pattern(self, pattern);
+    }
+
+    fn visit_assign(&mut self, assign: &'a Assign) {
+        if let (true, AssignLeftSide::Identifier(id)) = (assign.is_let, &assign.name) {
+            if let Some(ident) = id.as_identifier() {
+                if !ident.name.starts_with('_') {
+                    self.variables.push(ident.node_id);
+
+                    if has_call(&assign.value) {
+                        self.calls.insert(ident.node_id);
+                    }
+                }
+            }
+        }
+
+        walk_assign(self, assign);
+    }
+
     fn visit_identifier(&mut self, id: &'a Identifier) {
         if let Some(reso) = self.resolutions.get_recur(&id.node_id) {
             if let Some(used) = self.fn_list.get_mut(&reso) {
@@ -105,10 +204,93 @@ impl<'a> Visitor<'a> for UnusedCollector {
     }
 }
 
-pub fn collect_unused(root: &Root) -> (Vec<NodeId>, Vec<NodeId>) {
+// Finds the calls of an expression, removing it would also remove what they do
+#[derive(Default)]
+struct CallFinder {
+    found: bool,
+}
+
+impl<'a> Visitor<'a> for CallFinder {
+    fn visit_secondary_expr(&mut self, secondary: &'a SecondaryExpr) {
+        if let SecondaryExpr::Arguments(_) = secondary {
+            self.found = true;
+        }
+
+        walk_secondary_expr(self, secondary);
+    }
+}
+
+fn has_call(expr: &Expression) -> bool {
+    let mut finder = CallFinder::default();
+
+    finder.visit_expression(expr);
+
+    finder.found
+}
+
+pub fn collect_unused(root: &Root) -> Unused {
     let mut unused_collector = UnusedCollector::new(root.resolutions.clone());
 
     unused_collector.visit_root(root);
 
     unused_collector.take_unused()
 }
+
+// The span to remove with an unused parameter, with its separating comma
+pub fn param_removal(content: &str, start: usize, end: usize) -> (usize, usize) {
+    let after = &content[end..];
+    let after_comma = after.trim_start_matches([' ', '\t']);
+
+    if let Some(rest) = after_comma.strip_prefix(',') {
+        let rest_len = rest.trim_start_matches([' ', '\t']).len();
+
+        return (start, content.len() - rest_len);
+    }
+
+    let before = content[..start].trim_end_matches([' ', '\t']);
+
+    match before.strip_suffix(',') {
+        Some(before) => (before.len(), end),
+        None => (start, end),
+    }
+}
+
+// The span of `let name = ` before the value of a binding, `name` being at `start..end`
+pub fn binding_removal(content: &str, start: usize, end: usize) -> (usize, usize) {
+    let let_start = content[..start].rfind("let").unwrap_or(start);
+
+    let value_start = content[end..]
+        .find('=')
+        .map(|eq| {
+            let after_eq = &content[end + eq + 1..];
+
+            content.len() - after_eq.trim_start_matches([' ', '\t']).len()
+        })
+        .unwrap_or(end);
+
+    (let_start, value_start)
+}
+
+// The span of the whole lines of the statement at `start`, with the more indented
+// lines that follow it
+pub fn statement_removal(content: &str, start: usize) -> (usize, usize) {
+    let line_start = content[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+
+    let indent_of = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+
+    let mut lines = content[line_start..].split_inclusive('\n');
+
+    let first = lines.next().unwrap_or_default();
+    let indent = indent_of(first);
+    let mut end = line_start + first.len();
+
+    for line in lines {
+        if line.trim().is_empty() || indent_of(line) <= indent {
+            break;
+        }
+
+        end += line.len();
+    }
+
+    (line_start, end)
+}
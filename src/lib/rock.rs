@@ -10,7 +10,7 @@ extern crate log;
 #[macro_use]
 extern crate nom_locate;
 
-use std::path::PathBuf;
+use std::{collections::HashSet, io::Write, path::PathBuf, time::Instant};
 
 #[macro_use]
 mod helpers;
@@ -21,68 +21,218 @@ mod ast;
 mod infer;
 
 mod ast_lowering;
+mod cache;
 mod codegen;
 pub mod diagnostics;
+pub mod doc;
+pub mod formatter;
 mod hir;
+mod interp;
+pub mod lsp;
 mod parser;
 mod resolver;
 mod tests;
 mod ty;
 
+use cache::{BuildCache, ModuleCache};
+pub use codegen::{available_targets, host_triple};
 use diagnostics::Diagnostic;
-pub use helpers::config::Config;
+pub use helpers::config::{self, Config};
 use parser::{ParsingCtx, SourceFile};
 
-pub fn compile_file(in_name: String, config: &Config) -> Result<(), Diagnostic> {
+fn load_entry_file(in_name: String, config: &Config) -> Result<SourceFile, Diagnostic> {
     let mut source_file = SourceFile::from_file(in_name)?;
 
     if config.std {
-        source_file.content = "mod std\nuse std::prelude::(*)\n".to_owned() + &source_file.content;
+        source_file.prepend_header("mod std\nuse std::prelude::(*)\n");
     }
 
     source_file.mod_path = PathBuf::from("root");
 
-    compile_str(&source_file, config)
+    Ok(source_file)
+}
+
+pub fn compile_file(in_name: String, config: &Config) -> Result<(), Diagnostic> {
+    compile_str(&load_entry_file(in_name, config)?, config)
 }
 
 pub fn compile_str(input: &SourceFile, config: &Config) -> Result<(), Diagnostic> {
     let mut parsing_ctx = ParsingCtx::new(config);
 
+    let previous_cache = BuildCache::load(config);
+
+    let show_any = config.show_ast || config.show_hir || config.show_thir || config.show_ir;
+
+    if let Some(previous_cache) = &previous_cache {
+        if let Some(files) = previous_cache.up_to_date_files(input, config) {
+            if !show_any {
+                for stage in ["Parsing", "Resolving", "Lowering", "Inference", "Codegen"] {
+                    parsing_ctx.timings.skip(stage, "up to date");
+                }
+
+                // The warnings are the same as the last time
+                parsing_ctx.files = files;
+                parsing_ctx.diagnostics = previous_cache.diagnostics.clone();
+
+                parsing_ctx.print_success_diagnostics();
+
+                if config.timings {
+                    parsing_ctx.timings.print();
+                }
+
+                return Ok(());
+            }
+        }
+    }
+
+    let previous_modules = previous_cache
+        .map(|previous_cache| {
+            parsing_ctx.modules = ModuleCache::new(previous_cache.parsed, previous_cache.resolved);
+
+            previous_cache.modules
+        })
+        .unwrap_or_default();
+
     parsing_ctx.add_file(input);
 
     let hir = parse_str(&mut parsing_ctx, config)?;
 
-    generate_ir(hir, config)?;
+    let start = Instant::now();
+
+    let unchanged_files = parsing_ctx.modules.graph.unchanged_files(&previous_modules);
+
+    let reused = generate_ir(hir, config, unchanged_files, &mut parsing_ctx)?;
+
+    if reused > 0 {
+        parsing_ctx
+            .timings
+            .partial("Codegen", start, &format!("{} functions reused", reused));
+    } else {
+        parsing_ctx.timings.done("Codegen", start);
+    }
+
+    BuildCache::new(config, &mut parsing_ctx).save(&config.build_folder);
 
     parsing_ctx.print_success_diagnostics();
 
+    if config.timings {
+        parsing_ctx.timings.print();
+    }
+
     Ok(())
 }
 
+// Runs the program with the interpreter instead of building it. The arguments are the
+// `argv` of the program, with its name. Gives the exit code of the program, if it does
+// not depend on the address of a returned pointer.
+pub fn interpret_file(
+    in_name: String,
+    config: &Config,
+    args: Vec<String>,
+) -> Result<Option<i64>, Diagnostic> {
+    interpret_str(
+        &load_entry_file(in_name, config)?,
+        config,
+        args,
+        &mut std::io::stdout(),
+    )
+}
+
+pub fn interpret_str(
+    input: &SourceFile,
+    config: &Config,
+    args: Vec<String>,
+    out: &mut dyn Write,
+) -> Result<Option<i64>, Diagnostic> {
+    let mut parsing_ctx = ParsingCtx::new(config);
+
+    parsing_ctx.add_file(input);
+
+    let hir = parse_str(&mut parsing_ctx, config)?;
+
+    parsing_ctx.print_success_diagnostics();
+
+    let start = Instant::now();
+
+    let mut interpreter = interp::Interpreter::new(&hir, args, out);
+
+    let res = interpreter.run_main();
+
+    parsing_ctx.timings.done("Interpretation", start);
+
+    if config.timings {
+        parsing_ctx.timings.print();
+    }
+
+    match res {
+        Ok(code) => Ok(code),
+        Err(()) => {
+            parsing_ctx.diagnostics = std::mem::take(&mut interpreter.diagnostics);
+
+            parsing_ctx.print_diagnostics();
+
+            Err(Diagnostic::new_empty())
+        }
+    }
+}
+
 pub fn parse_str(parsing_ctx: &mut ParsingCtx, config: &Config) -> Result<hir::Root, Diagnostic> {
     // Text to Ast
     debug!("    -> Parsing");
+    let start = Instant::now();
     let mut ast = parser::parse(parsing_ctx)?;
+    done_or_reused(
+        parsing_ctx,
+        "Parsing",
+        start,
+        parsing_ctx.modules.reused_parses.len(),
+    );
 
     // Name resolving
     debug!("    -> Resolving");
+    let start = Instant::now();
     resolver::resolve(&mut ast, parsing_ctx)?;
+    done_or_reused(
+        parsing_ctx,
+        "Resolving",
+        start,
+        parsing_ctx.modules.reused_resolutions.len(),
+    );
 
     // Lowering to HIR
     debug!("    -> Lowering to HIR");
+    let start = Instant::now();
     let mut hir = ast_lowering::lower_crate(&ast);
+    parsing_ctx.timings.done("Lowering", start);
 
     // Infer Hir
     debug!("    -> Infer HIR");
+    let start = Instant::now();
     let new_hir = infer::infer(&mut hir, parsing_ctx, config)?;
+    parsing_ctx.timings.done("Inference", start);
 
     Ok(new_hir)
 }
 
-pub fn generate_ir(hir: hir::Root, config: &Config) -> Result<(), Diagnostic> {
+fn done_or_reused(parsing_ctx: &mut ParsingCtx, stage: &str, start: Instant, reused: usize) {
+    if reused > 0 {
+        parsing_ctx
+            .timings
+            .partial(stage, start, &format!("{} modules reused", reused));
+    } else {
+        parsing_ctx.timings.done(stage, start);
+    }
+}
+
+pub fn generate_ir(
+    hir: hir::Root,
+    config: &Config,
+    unchanged_files: HashSet<PathBuf>,
+    parsing_ctx: &mut ParsingCtx,
+) -> Result<usize, Diagnostic> {
     // Generate code
     debug!("    -> Lower to LLVM IR");
-    codegen::generate(config, hir)?;
+    let reused = codegen::generate(config, hir, unchanged_files, parsing_ctx)?;
 
-    Ok(())
+    Ok(reused)
 }
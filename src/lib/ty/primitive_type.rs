@@ -15,6 +15,9 @@ pub enum PrimitiveType {
 }
 
 impl PrimitiveType {
+    // The size of the arrays only known at runtime, like the arguments of `main`
+    pub const RUNTIME_SIZE: usize = usize::MAX;
+
     pub fn is_solved(&self) -> bool {
         if let PrimitiveType::Array(t, _) = self {
             t.is_solved()
@@ -11,7 +11,8 @@ pub enum Type {
     Struct(StructType),
     Trait(String),
     ForAll(String),
-    Undefined(u64), // FIXME: To remove
+    Newtype(String, Box<Type>), // Distinct from its inner type, but represented like it
+    Undefined(u64),             // FIXME: To remove
 }
 
 impl std::hash::Hash for Type {
@@ -72,6 +73,7 @@ impl Type {
             Type::Struct(_) => true,
             Type::Trait(_) => true,
             Type::ForAll(_) => false,
+            Type::Newtype(_, inner) => inner.is_solved(),
             Type::Undefined(_) => false,
         }
     }
@@ -100,6 +102,10 @@ impl Type {
         matches!(self, Self::ForAll(_x))
     }
 
+    pub fn is_newtype(&self) -> bool {
+        matches!(self, Self::Newtype(_, _))
+    }
+
     pub fn get_name(&self) -> String {
         match self {
             Self::Primitive(p) => p.get_name(),
@@ -107,6 +113,7 @@ impl Type {
             Self::Struct(s) => s.name.clone(),
             Self::Trait(t) => t.clone(),
             Self::ForAll(n) => String::from(n),
+            Self::Newtype(n, _) => n.clone(),
             Self::Undefined(s) => s.to_string(),
         }
     }
@@ -155,6 +162,14 @@ impl Type {
             _ => None,
         }
     }
+
+    // The type that a newtype wraps
+    pub fn try_as_newtype_inner(&self) -> Option<&Type> {
+        match self {
+            Type::Newtype(_, inner) => Some(inner),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Debug for Type {
@@ -0,0 +1,116 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+// A project with the given files in its `src` folder
+fn project(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rock_cli_{}_{}", name, std::process::id()));
+
+    let _ = fs::remove_dir_all(&dir);
+
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(
+        dir.join("Rock.toml"),
+        format!("[package]\nname = \"{}\"\n", name),
+    )
+    .unwrap();
+
+    for (file, content) in files {
+        fs::write(dir.join("src").join(file), content).unwrap();
+    }
+
+    dir
+}
+
+fn rock(dir: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rock"));
+
+    cmd.current_dir(dir).args(args);
+
+    cmd
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn link_without_linker() {
+    let dir = project("no_linker", &[("main.rk", "main: -> 0\n")]);
+
+    let output = rock(&dir, &["--nostd", "build"])
+        .env("PATH", dir.join("empty"))
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(
+        stdout(&output).contains("[R0031] Error: Cannot link: `clang` cannot run"),
+        "{}",
+        stdout(&output)
+    );
+}
+
+// Each test runs in its own process, a failed one does not stop the others
+#[test]
+fn test_command() {
+    let dir = project(
+        "test_command",
+        &[(
+            "main.rk",
+            "main: -> 0\n\ntest \"passes\": ->\n  assert_eq (1 + 1), 2\n\ntest \"fails\": ->\n  assert_eq (1 + 1), 3\n",
+        )],
+    );
+
+    let output = rock(&dir, &["test"]).output().unwrap();
+    let text = stdout(&output);
+
+    assert_eq!(output.status.code(), Some(1), "{}", text);
+    assert!(text.contains("running 2 tests"), "{}", text);
+    assert!(text.contains("test passes ... ok"), "{}", text);
+    assert!(text.contains("test fails ... FAILED"), "{}", text);
+    assert!(text.contains("failures:"), "{}", text);
+    assert!(text.contains("assertion failed: left == right"), "{}", text);
+    assert!(
+        text.contains("test result: FAILED. 1 passed; 1 failed; 0 filtered out"),
+        "{}",
+        text
+    );
+
+    let output = rock(&dir, &["test", "passes"]).output().unwrap();
+    let text = stdout(&output);
+
+    assert!(output.status.success(), "{}", text);
+    assert!(
+        text.contains("test result: ok. 1 passed; 0 failed; 1 filtered out"),
+        "{}",
+        text
+    );
+}
+
+// The length of the arguments of `main` is the one given at runtime, with the program name
+#[test]
+fn main_args_len() {
+    let dir = project(
+        "main_args_len",
+        &[(
+            "main.rk",
+            "count: arr -> ~Len arr arr\n\nmain: args ->\n  if (~Len args args) == (count args)\n  then count args\n  else 0\n",
+        )],
+    );
+
+    for args in [
+        &["run", "--", "a", "b", "c"][..],
+        &["run", "--interp", "--", "a", "b", "c"],
+    ] {
+        let output = rock(&dir, args).output().unwrap();
+
+        assert_eq!(output.status.code(), Some(4), "{}", stdout(&output));
+    }
+
+    let output = rock(&dir, &["run"]).output().unwrap();
+
+    assert_eq!(output.status.code(), Some(1), "{}", stdout(&output));
+}